@@ -87,6 +87,53 @@ fn copy_build_artifacts() -> Result<()> {
     Ok(())
 }
 
+/// Runs bindgen over the vendored FSR2 headers. The output is only used by the layout tests,
+/// which compare every hand-written #[repr(C)] type against its C counterpart.
+#[cfg(feature = "layout-tests")]
+fn generate_layout_bindings() -> Result<()> {
+    let api_dir = Path::new(FSR2_SOURCE_DIR).join("src").join("ffx-fsr2-api");
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed={}", api_dir.as_os_str().to_str().unwrap());
+
+    let builder = bindgen::Builder::default()
+        .header(api_dir.join("ffx_fsr2.h").as_os_str().to_str().unwrap())
+        .clang_args(["-x", "c++", "-std=c++11"])
+        .clang_arg(format!("-I{}", api_dir.as_os_str().to_str().unwrap()))
+        .allowlist_type("Ffx.*")
+        .allowlist_var("FFX_.*")
+        .prepend_enum_name(false)
+        .layout_tests(false)
+        .derive_debug(false)
+        .derive_copy(false);
+
+    #[cfg(feature = "vk")]
+    let builder = {
+        let mut builder = builder
+            .header(api_dir.join("vk").join("ffx_fsr2_vk.h").as_os_str().to_str().unwrap())
+            .allowlist_type("VkExtensionProperties")
+            .allowlist_type("VkMemoryType")
+            .allowlist_type("VkMemoryHeap")
+            .allowlist_type("VkPhysicalDeviceMemoryProperties")
+            .allowlist_type("VkPhysicalDeviceProperties2")
+            .allowlist_type("VkPhysicalDeviceFeatures2")
+            .allowlist_type("VkPhysicalDeviceSubgroupProperties")
+            .allowlist_type("VkPhysicalDeviceShaderFloat16Int8Features")
+            .allowlist_type("VkPhysicalDeviceSubgroupSizeControlProperties")
+            .allowlist_type("VkPhysicalDeviceSubgroupSizeControlFeatures");
+        if let Ok(sdk) = env::var("VULKAN_SDK") {
+            builder = builder.clang_arg(format!("-I{}", Path::new(&sdk).join("include").display()));
+        }
+        builder
+    };
+
+    let bindings = builder
+        .generate()
+        .map_err(|e| anyhow!("Failed to generate layout bindings: {e}"))?;
+    let out_dir = env::var("OUT_DIR").unwrap();
+    bindings.write_to_file(Path::new(&out_dir).join("layout_bindings.rs"))?;
+    Ok(())
+}
+
 fn main() -> Result<()> {
     #[cfg(feature = "vk")]
     const API: &'static str = "VK";
@@ -109,5 +156,8 @@ fn main() -> Result<()> {
     #[cfg(feature = "dx12")]
     println!("cargo:rustc-link-lib=./ffx_fsr2_api_dx12_x64");
 
+    #[cfg(feature = "layout-tests")]
+    generate_layout_bindings()?;
+
     Ok(())
 }
@@ -0,0 +1,414 @@
+use crate::backend::vk::*;
+use ash::vk;
+use ash::vk::Handle;
+
+macro_rules! handle_conversions {
+    ($($ours:ident <=> $ash:ty),* $(,)?) => {
+        $(
+            impl From<$ash> for $ours {
+                fn from(handle: $ash) -> Self {
+                    $ours::from_raw(handle.as_raw())
+                }
+            }
+
+            impl From<$ours> for $ash {
+                fn from(handle: $ours) -> Self {
+                    <$ash>::from_raw(handle.as_raw())
+                }
+            }
+        )*
+    };
+}
+
+handle_conversions! {
+    VkPhysicalDevice <=> vk::PhysicalDevice,
+    VkInstance <=> vk::Instance,
+    VkDevice <=> vk::Device,
+    VkCommandBuffer <=> vk::CommandBuffer,
+    VkImage <=> vk::Image,
+    VkImageView <=> vk::ImageView,
+    VkBuffer <=> vk::Buffer,
+}
+
+impl From<vk::Format> for VkFormat {
+    fn from(format: vk::Format) -> Self {
+        VkFormat::from_raw(format.as_raw())
+    }
+}
+
+impl From<VkFormat> for vk::Format {
+    fn from(format: VkFormat) -> Self {
+        vk::Format::from_raw(format.as_raw())
+    }
+}
+
+impl From<vk::ImageLayout> for VkImageLayout {
+    fn from(layout: vk::ImageLayout) -> Self {
+        VkImageLayout::from_raw(layout.as_raw())
+    }
+}
+
+impl From<VkImageLayout> for vk::ImageLayout {
+    fn from(layout: VkImageLayout) -> Self {
+        vk::ImageLayout::from_raw(layout.as_raw())
+    }
+}
+
+impl From<VkAccessFlags2> for vk::AccessFlags2 {
+    fn from(flags: VkAccessFlags2) -> Self {
+        vk::AccessFlags2::from_raw(flags.bits())
+    }
+}
+
+impl From<VkPipelineStageFlags2> for vk::PipelineStageFlags2 {
+    fn from(flags: VkPipelineStageFlags2) -> Self {
+        vk::PipelineStageFlags2::from_raw(flags.bits())
+    }
+}
+
+impl FfxFsr2InstanceFunctionPointerTableVk {
+    /// Builds the function pointer table through the `vkGetInstanceProcAddr` of `entry`, see [`Self::load`] for the
+    /// fallback to `VK_KHR_get_physical_device_properties2` on Vulkan 1.0 instances.
+    ///
+    /// # Safety
+    /// `instance` must have been created from `entry`.
+    pub unsafe fn from_ash(entry: &ash::Entry, instance: &ash::Instance) -> Result<Self, VkFunctionLoadError> {
+        Self::from_static_fn(entry.static_fn(), instance.handle())
+    }
+
+    unsafe fn from_static_fn(static_fn: &vk::StaticFn, instance: vk::Instance) -> Result<Self, VkFunctionLoadError> {
+        // SAFETY: The only difference between the ash signature and ours is that ash spells out the Vulkan types,
+        // which have the same ABI as the types used here.
+        let get_instance_proc_addr = std::mem::transmute::<vk::PFN_vkGetInstanceProcAddr, VkGetInstanceProcAddrFunc>(
+            static_fn.get_instance_proc_addr,
+        );
+        Self::load(instance.into(), get_instance_proc_addr)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::{c_char, CStr};
+
+    #[test]
+    fn handle_round_trip() {
+        let image = vk::Image::from_raw(0xdead_beef);
+        let ours = VkImage::from(image);
+        assert_eq!(ours.as_raw(), 0xdead_beef);
+        assert_eq!(vk::Image::from(ours), image);
+
+        let format = VkFormat::from(vk::Format::R16G16B16A16_SFLOAT);
+        assert_eq!(format, VkFormat::R16G16B16A16_SFLOAT);
+    }
+
+    unsafe extern "system" fn dummy() {}
+
+    /// Behaves like a Vulkan 1.0 instance, where ash would fill in panicking stubs for the 1.1 functions.
+    unsafe extern "system" fn vulkan_1_0_proc_addr(_: vk::Instance, name: *const c_char) -> vk::PFN_vkVoidFunction {
+        match CStr::from_ptr(name).to_str().unwrap() {
+            "vkGetPhysicalDeviceProperties2" | "vkGetPhysicalDeviceFeatures2" => None,
+            _ => Some(dummy),
+        }
+    }
+
+    #[test]
+    fn vulkan_1_0_instance() {
+        let static_fn = vk::StaticFn {
+            get_instance_proc_addr: vulkan_1_0_proc_addr,
+        };
+        let instance = vk::Instance::from_raw(1);
+        assert!(unsafe { FfxFsr2InstanceFunctionPointerTableVk::from_static_fn(&static_fn, instance) }.is_ok());
+    }
+
+    #[test]
+    fn barrier_scopes() {
+        let scope = crate::FfxResourceState::GENERIC_READ.vk_scope();
+        assert_eq!(vk::ImageLayout::from(scope.layout), vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL);
+        assert_eq!(
+            vk::AccessFlags2::from(scope.access),
+            vk::AccessFlags2::SHADER_READ | vk::AccessFlags2::TRANSFER_READ
+        );
+        assert_eq!(
+            vk::PipelineStageFlags2::from(scope.stage),
+            vk::PipelineStageFlags2::COMPUTE_SHADER | vk::PipelineStageFlags2::TRANSFER
+        );
+        assert_eq!(
+            vk::AccessFlags2::from(VkAccessFlags2::SHADER_STORAGE_WRITE),
+            vk::AccessFlags2::SHADER_STORAGE_WRITE
+        );
+        assert_eq!(vk::PipelineStageFlags2::from(VkPipelineStageFlags2::COPY), vk::PipelineStageFlags2::COPY);
+        assert_eq!(vk::ImageLayout::from(VkImageLayout::ATTACHMENT_OPTIMAL), vk::ImageLayout::ATTACHMENT_OPTIMAL);
+    }
+
+    #[test]
+    fn structure_types() {
+        assert_eq!(VkStructureType::PHYSICAL_DEVICE_FEATURES_2.as_raw(), vk::StructureType::PHYSICAL_DEVICE_FEATURES_2.as_raw());
+        assert_eq!(VkStructureType::PHYSICAL_DEVICE_PROPERTIES_2.as_raw(), vk::StructureType::PHYSICAL_DEVICE_PROPERTIES_2.as_raw());
+        assert_eq!(
+            VkStructureType::PHYSICAL_DEVICE_SHADER_FLOAT16_INT8_FEATURES.as_raw(),
+            vk::StructureType::PHYSICAL_DEVICE_SHADER_FLOAT16_INT8_FEATURES.as_raw()
+        );
+        assert_eq!(
+            VkStructureType::PHYSICAL_DEVICE_SUBGROUP_PROPERTIES.as_raw(),
+            vk::StructureType::PHYSICAL_DEVICE_SUBGROUP_PROPERTIES.as_raw()
+        );
+        assert_eq!(
+            VkStructureType::PHYSICAL_DEVICE_SUBGROUP_SIZE_CONTROL_PROPERTIES.as_raw(),
+            vk::StructureType::PHYSICAL_DEVICE_SUBGROUP_SIZE_CONTROL_PROPERTIES.as_raw()
+        );
+        assert_eq!(
+            VkStructureType::PHYSICAL_DEVICE_SUBGROUP_SIZE_CONTROL_FEATURES.as_raw(),
+            vk::StructureType::PHYSICAL_DEVICE_SUBGROUP_SIZE_CONTROL_FEATURES.as_raw()
+        );
+    }
+
+    // ash is generated from the Vulkan registry, which makes it a convenient reference for the Vulkan structs
+    // that does not need libclang.
+    mod layout {
+        use super::*;
+
+        layout_test!(limits: VkPhysicalDeviceLimits => vk::PhysicalDeviceLimits {
+            max_image_dimension_1d => max_image_dimension1_d,
+            max_image_dimension_2d => max_image_dimension2_d,
+            max_image_dimension_3d => max_image_dimension3_d,
+            max_image_dimension_cube => max_image_dimension_cube,
+            max_image_array_layers => max_image_array_layers,
+            max_texel_buffer_elements => max_texel_buffer_elements,
+            max_uniform_buffer_range => max_uniform_buffer_range,
+            max_storage_buffer_range => max_storage_buffer_range,
+            max_push_constants_size => max_push_constants_size,
+            max_memory_allocation_count => max_memory_allocation_count,
+            max_sampler_allocation_count => max_sampler_allocation_count,
+            buffer_image_granularity => buffer_image_granularity,
+            sparse_address_space_size => sparse_address_space_size,
+            max_bound_descriptor_sets => max_bound_descriptor_sets,
+            max_per_stage_descriptor_samplers => max_per_stage_descriptor_samplers,
+            max_per_stage_descriptor_uniform_buffers => max_per_stage_descriptor_uniform_buffers,
+            max_per_stage_descriptor_storage_buffers => max_per_stage_descriptor_storage_buffers,
+            max_per_stage_descriptor_sampled_images => max_per_stage_descriptor_sampled_images,
+            max_per_stage_descriptor_storage_images => max_per_stage_descriptor_storage_images,
+            max_per_stage_descriptor_input_attachments => max_per_stage_descriptor_input_attachments,
+            max_per_stage_resources => max_per_stage_resources,
+            max_descriptor_set_samplers => max_descriptor_set_samplers,
+            max_descriptor_set_uniform_buffers => max_descriptor_set_uniform_buffers,
+            max_descriptor_set_uniform_buffers_dynamic => max_descriptor_set_uniform_buffers_dynamic,
+            max_descriptor_set_storage_buffers => max_descriptor_set_storage_buffers,
+            max_descriptor_set_storage_buffers_dynamic => max_descriptor_set_storage_buffers_dynamic,
+            max_descriptor_set_sampled_images => max_descriptor_set_sampled_images,
+            max_descriptor_set_storage_images => max_descriptor_set_storage_images,
+            max_descriptor_set_input_attachments => max_descriptor_set_input_attachments,
+            max_vertex_input_attributes => max_vertex_input_attributes,
+            max_vertex_input_bindings => max_vertex_input_bindings,
+            max_vertex_input_attribute_offset => max_vertex_input_attribute_offset,
+            max_vertex_input_binding_stride => max_vertex_input_binding_stride,
+            max_vertex_output_components => max_vertex_output_components,
+            max_tessellation_generation_level => max_tessellation_generation_level,
+            max_tessellation_patch_size => max_tessellation_patch_size,
+            max_tessellation_control_per_vertex_input_components => max_tessellation_control_per_vertex_input_components,
+            max_tessellation_control_per_vertex_output_components => max_tessellation_control_per_vertex_output_components,
+            max_tessellation_control_per_patch_output_components => max_tessellation_control_per_patch_output_components,
+            max_tessellation_control_total_output_components => max_tessellation_control_total_output_components,
+            max_tessellation_evaluation_input_components => max_tessellation_evaluation_input_components,
+            max_tessellation_evaluation_output_components => max_tessellation_evaluation_output_components,
+            max_geometry_shader_invocations => max_geometry_shader_invocations,
+            max_geometry_input_components => max_geometry_input_components,
+            max_geometry_output_components => max_geometry_output_components,
+            max_geometry_output_vertices => max_geometry_output_vertices,
+            max_geometry_total_output_components => max_geometry_total_output_components,
+            max_fragment_input_components => max_fragment_input_components,
+            max_fragment_output_attachments => max_fragment_output_attachments,
+            max_fragment_dual_src_attachments => max_fragment_dual_src_attachments,
+            max_fragment_combined_output_resources => max_fragment_combined_output_resources,
+            max_compute_shared_memory_size => max_compute_shared_memory_size,
+            max_compute_work_group_count => max_compute_work_group_count,
+            max_compute_work_group_invocations => max_compute_work_group_invocations,
+            max_compute_work_group_size => max_compute_work_group_size,
+            sub_pixel_precision_bits => sub_pixel_precision_bits,
+            sub_texel_precision_bits => sub_texel_precision_bits,
+            mipmap_precision_bits => mipmap_precision_bits,
+            max_draw_indexed_index_value => max_draw_indexed_index_value,
+            max_draw_indirect_count => max_draw_indirect_count,
+            max_sampler_lod_bias => max_sampler_lod_bias,
+            max_sampler_anisotropy => max_sampler_anisotropy,
+            max_viewports => max_viewports,
+            max_viewport_dimensions => max_viewport_dimensions,
+            viewport_bounds_range => viewport_bounds_range,
+            viewport_sub_pixel_bits => viewport_sub_pixel_bits,
+            min_memory_map_alignment => min_memory_map_alignment,
+            min_texel_buffer_offset_alignment => min_texel_buffer_offset_alignment,
+            min_uniform_buffer_offset_alignment => min_uniform_buffer_offset_alignment,
+            min_storage_buffer_offset_alignment => min_storage_buffer_offset_alignment,
+            min_texel_offset => min_texel_offset,
+            max_texel_offset => max_texel_offset,
+            min_texel_gather_offset => min_texel_gather_offset,
+            max_texel_gather_offset => max_texel_gather_offset,
+            min_interpolation_offset => min_interpolation_offset,
+            max_interpolation_offset => max_interpolation_offset,
+            sub_pixel_interpolation_offset_bits => sub_pixel_interpolation_offset_bits,
+            max_framebuffer_width => max_framebuffer_width,
+            max_framebuffer_height => max_framebuffer_height,
+            max_framebuffer_layers => max_framebuffer_layers,
+            framebuffer_color_sample_counts => framebuffer_color_sample_counts,
+            framebuffer_depth_sample_counts => framebuffer_depth_sample_counts,
+            framebuffer_stencil_sample_counts => framebuffer_stencil_sample_counts,
+            framebuffer_no_attachments_sample_counts => framebuffer_no_attachments_sample_counts,
+            max_color_attachments => max_color_attachments,
+            sampled_image_color_sample_counts => sampled_image_color_sample_counts,
+            sampled_image_integer_sample_counts => sampled_image_integer_sample_counts,
+            sampled_image_depth_sample_counts => sampled_image_depth_sample_counts,
+            sampled_image_stencil_sample_counts => sampled_image_stencil_sample_counts,
+            storage_image_sample_counts => storage_image_sample_counts,
+            max_sample_mask_words => max_sample_mask_words,
+            timestamp_compute_and_graphics => timestamp_compute_and_graphics,
+            timestamp_period => timestamp_period,
+            max_clip_distances => max_clip_distances,
+            max_cull_distances => max_cull_distances,
+            max_combined_clip_and_cull_distances => max_combined_clip_and_cull_distances,
+            discrete_queue_priorities => discrete_queue_priorities,
+            point_size_range => point_size_range,
+            line_width_range => line_width_range,
+            point_size_granularity => point_size_granularity,
+            line_width_granularity => line_width_granularity,
+            strict_lines => strict_lines,
+            standard_sample_locations => standard_sample_locations,
+            optimal_buffer_copy_offset_alignment => optimal_buffer_copy_offset_alignment,
+            optimal_buffer_copy_row_pitch_alignment => optimal_buffer_copy_row_pitch_alignment,
+            non_coherent_atom_size => non_coherent_atom_size,
+        });
+
+        layout_test!(sparse_properties: VkPhysicalDeviceSparseProperties => vk::PhysicalDeviceSparseProperties {
+            residency_standard_2d_block_shape => residency_standard2_d_block_shape,
+            residency_standard_2d_multisample_block_shape => residency_standard2_d_multisample_block_shape,
+            residency_standard_3d_block_shape => residency_standard3_d_block_shape,
+            residency_aligned_mip_size => residency_aligned_mip_size,
+            residency_non_resident_strict => residency_non_resident_strict,
+        });
+
+        layout_test!(features: VkPhysicalDeviceFeatures => vk::PhysicalDeviceFeatures {
+            robust_buffer_access => robust_buffer_access,
+            full_draw_index_uint32 => full_draw_index_uint32,
+            image_cube_array => image_cube_array,
+            independent_blend => independent_blend,
+            geometry_shader => geometry_shader,
+            tessellation_shader => tessellation_shader,
+            sample_rate_shading => sample_rate_shading,
+            dual_src_blend => dual_src_blend,
+            logic_op => logic_op,
+            multi_draw_indirect => multi_draw_indirect,
+            draw_indirect_first_instance => draw_indirect_first_instance,
+            depth_clamp => depth_clamp,
+            depth_bias_clamp => depth_bias_clamp,
+            fill_mode_non_solid => fill_mode_non_solid,
+            depth_bounds => depth_bounds,
+            wide_lines => wide_lines,
+            large_points => large_points,
+            alpha_to_one => alpha_to_one,
+            multi_viewport => multi_viewport,
+            sampler_anisotropy => sampler_anisotropy,
+            texture_compression_etc2 => texture_compression_etc2,
+            texture_compression_astc_ldr => texture_compression_astc_ldr,
+            texture_compression_bc => texture_compression_bc,
+            occlusion_query_precise => occlusion_query_precise,
+            pipeline_statistics_query => pipeline_statistics_query,
+            vertex_pipeline_stores_and_atomics => vertex_pipeline_stores_and_atomics,
+            fragment_stores_and_atomics => fragment_stores_and_atomics,
+            shader_tessellation_and_geometry_point_size => shader_tessellation_and_geometry_point_size,
+            shader_image_gather_extended => shader_image_gather_extended,
+            shader_storage_image_extended_formats => shader_storage_image_extended_formats,
+            shader_storage_image_multisample => shader_storage_image_multisample,
+            shader_storage_image_read_without_format => shader_storage_image_read_without_format,
+            shader_storage_image_write_without_format => shader_storage_image_write_without_format,
+            shader_uniform_buffer_array_dynamic_indexing => shader_uniform_buffer_array_dynamic_indexing,
+            shader_sampled_image_array_dynamic_indexing => shader_sampled_image_array_dynamic_indexing,
+            shader_storage_buffer_array_dynamic_indexing => shader_storage_buffer_array_dynamic_indexing,
+            shader_storage_image_array_dynamic_indexing => shader_storage_image_array_dynamic_indexing,
+            shader_clip_distance => shader_clip_distance,
+            shader_cull_distance => shader_cull_distance,
+            shader_float64 => shader_float64,
+            shader_int64 => shader_int64,
+            shader_int16 => shader_int16,
+            shader_resource_residency => shader_resource_residency,
+            shader_resource_min_lod => shader_resource_min_lod,
+            sparse_binding => sparse_binding,
+            sparse_residency_buffer => sparse_residency_buffer,
+            sparse_residency_image_2d => sparse_residency_image2_d,
+            sparse_residency_image_3d => sparse_residency_image3_d,
+            sparse_residency_2_samples => sparse_residency2_samples,
+            sparse_residency_4_samples => sparse_residency4_samples,
+            sparse_residency_8_samples => sparse_residency8_samples,
+            sparse_residency_16_samples => sparse_residency16_samples,
+            sparse_residency_aliased => sparse_residency_aliased,
+            variable_multisample_rate => variable_multisample_rate,
+            inherited_queries => inherited_queries,
+        });
+
+        layout_test!(properties: VkPhysicalDeviceProperties => vk::PhysicalDeviceProperties {
+            api_version => api_version,
+            driver_version => driver_version,
+            vendor_id => vendor_id,
+            device_id => device_id,
+            device_type => device_type,
+            device_name => device_name,
+            pipeline_cache_uuid => pipeline_cache_uuid,
+            limits => limits,
+            sparse_properties => sparse_properties,
+        });
+
+        layout_test!(properties2: VkPhysicalDeviceProperties2 => vk::PhysicalDeviceProperties2 {
+            s_type => s_type,
+            p_next => p_next,
+            properties => properties,
+        });
+
+        layout_test!(features2: VkPhysicalDeviceFeatures2 => vk::PhysicalDeviceFeatures2 {
+            s_type => s_type,
+            p_next => p_next,
+            features => features,
+        });
+
+        layout_test!(subgroup_properties: VkPhysicalDeviceSubgroupProperties => vk::PhysicalDeviceSubgroupProperties {
+            s_type => s_type,
+            p_next => p_next,
+            subgroup_size => subgroup_size,
+            supported_stages => supported_stages,
+            supported_operations => supported_operations,
+            quad_operations_in_all_stages => quad_operations_in_all_stages,
+        });
+
+        layout_test!(shader_float16_int8_features: VkPhysicalDeviceShaderFloat16Int8Features => vk::PhysicalDeviceShaderFloat16Int8Features {
+            s_type => s_type,
+            p_next => p_next,
+            shader_float16 => shader_float16,
+            shader_int8 => shader_int8,
+        });
+
+        layout_test!(subgroup_size_control_properties: VkPhysicalDeviceSubgroupSizeControlProperties => vk::PhysicalDeviceSubgroupSizeControlProperties {
+            s_type => s_type,
+            p_next => p_next,
+            min_subgroup_size => min_subgroup_size,
+            max_subgroup_size => max_subgroup_size,
+            max_compute_workgroup_subgroups => max_compute_workgroup_subgroups,
+            required_subgroup_size_stages => required_subgroup_size_stages,
+        });
+
+        layout_test!(subgroup_size_control_features: VkPhysicalDeviceSubgroupSizeControlFeatures => vk::PhysicalDeviceSubgroupSizeControlFeatures {
+            s_type => s_type,
+            p_next => p_next,
+            subgroup_size_control => subgroup_size_control,
+            compute_full_subgroups => compute_full_subgroups,
+        });
+
+        layout_test!(extension_properties: VkExtensionProperties => vk::ExtensionProperties {
+            extension_name => extension_name,
+            spec_version => spec_version,
+        });
+
+        layout_test!(memory_properties: VkPhysicalDeviceMemoryProperties => vk::PhysicalDeviceMemoryProperties {
+            memory_type_count => memory_type_count,
+            memory_types => memory_types,
+            memory_heap_count => memory_heap_count,
+            memory_heaps => memory_heaps,
+        });
+    }
+}
@@ -0,0 +1,724 @@
+//! A smoke-test backend that keeps all resources in system memory and runs simplified stand-ins for the FSR2
+//! passes on the CPU. It exercises FSR2's resource, pipeline and job flow without a GPU, but its images are not a
+//! reference for the GPU shaders, see the `passes` module.
+
+mod image;
+mod passes;
+
+pub use image::*;
+
+use crate::{
+    FfxCommandList, FfxComputeJobDescription, FfxCreateResourceDescription, FfxDevice, FfxDeviceCapabilities,
+    FfxErrorCode, FfxFsr2InitializationFlagBits, FfxFsr2Interface, FfxFsr2Pass, FfxGpuJobDescription, FfxJobType,
+    FfxPipeline, FfxPipelineDescription, FfxPipelineState, FfxResource, FfxResourceBinding, FfxResourceDescription,
+    FfxResourceInternal, FfxResourceState, FfxShaderModel, ResourceName, FFX_MAX_NUM_CONST_BUFFERS, FFX_MAX_NUM_SRVS,
+    FFX_MAX_NUM_UAVS,
+};
+use std::ffi::c_void;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+/// Internal indices of registered resources start here, below are the ones FSR2 created.
+const REGISTERED_RESOURCE_BASE: i32 = 1 << 16;
+
+enum RegisteredImage {
+    External(*mut CpuImage),
+    /// Stands in for a registered null resource.
+    Placeholder(CpuImage),
+}
+
+struct CpuPipeline {
+    pass: FfxFsr2Pass,
+    flags: FfxFsr2InitializationFlagBits,
+}
+
+/// State of the CPU backend, see [`ffx_fsr2_get_interface_cpu`].
+#[derive(Default)]
+pub struct CpuBackend {
+    resources: Vec<Option<CpuImage>>,
+    registered: Vec<RegisteredImage>,
+    pipelines: Vec<Option<CpuPipeline>>,
+    jobs: Vec<FfxGpuJobDescription>,
+    executed_passes: Vec<FfxFsr2Pass>,
+}
+
+impl CpuBackend {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A resource created or registered by FSR2, like `ffxGetVkImage` for the Vulkan backend.
+    pub fn resource(&self, resource: FfxResourceInternal) -> Option<&CpuImage> {
+        let index = resource.internal_index;
+        if index >= REGISTERED_RESOURCE_BASE {
+            match self.registered.get((index - REGISTERED_RESOURCE_BASE) as usize)? {
+                // SAFETY: The caller of `fp_register_resource` keeps registered images alive until they are
+                // unregistered.
+                RegisteredImage::External(image) => Some(unsafe { &**image }),
+                RegisteredImage::Placeholder(image) => Some(image),
+            }
+        } else {
+            self.resources.get(usize::try_from(index).ok()?)?.as_ref()
+        }
+    }
+
+    fn resource_mut(&mut self, resource: FfxResourceInternal) -> Option<&mut CpuImage> {
+        let index = resource.internal_index;
+        if index >= REGISTERED_RESOURCE_BASE {
+            match self.registered.get_mut((index - REGISTERED_RESOURCE_BASE) as usize)? {
+                RegisteredImage::External(image) => Some(unsafe { &mut **image }),
+                RegisteredImage::Placeholder(image) => Some(image),
+            }
+        } else {
+            self.resources.get_mut(usize::try_from(index).ok()?)?.as_mut()
+        }
+    }
+
+    /// The passes of the last `fp_execute_gpu_jobs`, in order.
+    pub fn executed_passes(&self) -> &[FfxFsr2Pass] {
+        &self.executed_passes
+    }
+
+    fn execute(&mut self, job: &FfxGpuJobDescription) -> Result<(), FfxErrorCode> {
+        match job.ty {
+            FfxJobType::ClearFloat => {
+                let clear = unsafe { &job.descriptor.clear_job };
+                self.resource_mut(clear.target)
+                    .ok_or(FfxErrorCode::InvalidArgument)?
+                    .fill(clear.color);
+            }
+            FfxJobType::Copy => {
+                let copy = unsafe { &job.descriptor.copy_job };
+                let source = self.resource(copy.src).ok_or(FfxErrorCode::InvalidArgument)?.clone();
+                let destination = self.resource_mut(copy.dst).ok_or(FfxErrorCode::InvalidArgument)?;
+                for (x, y) in (0..source.height() as i32).flat_map(|y| (0..source.width() as i32).map(move |x| (x, y)))
+                {
+                    destination.store(x, y, source.load(x, y));
+                }
+            }
+            FfxJobType::Compute => self.dispatch(unsafe { &job.descriptor.compute_job })?,
+        }
+        Ok(())
+    }
+
+    fn dispatch(&mut self, job: &FfxComputeJobDescription) -> Result<(), FfxErrorCode> {
+        let pipeline = (job.pipeline.pipeline as usize)
+            .checked_sub(1)
+            .and_then(|index| self.pipelines.get(index)?.as_ref())
+            .ok_or(FfxErrorCode::InvalidArgument)?;
+        let (pass, flags) = (pipeline.pass, pipeline.flags);
+        let state = &job.pipeline;
+
+        let mut outputs = passes::PassOutputs::default();
+        for i in 0..(state.uav_count as usize).min(FFX_MAX_NUM_UAVS) {
+            if let Some(image) = self.resource(job.uavs[i]) {
+                let name = state.uav_resource_bindings[i].name().to_string_lossy();
+                outputs.uavs.push((name, job.uavs[i], job.uav_mip[i], image.clone()));
+            }
+        }
+        let inputs = passes::PassInputs {
+            pass,
+            flags,
+            srvs: (0..(state.srv_count as usize).min(FFX_MAX_NUM_SRVS))
+                .filter_map(|i| {
+                    let image = self.resource(job.srvs[i])?;
+                    Some((state.srv_resource_bindings[i].name().to_string_lossy(), image))
+                })
+                .collect(),
+            cbs: (0..(state.const_count as usize).min(FFX_MAX_NUM_CONST_BUFFERS))
+                .map(|i| (state.cv_resource_bindings[i].name().to_string_lossy(), &job.cbs[i]))
+                .collect(),
+            empty: CpuImage::new(1, 1, crate::FfxSurfaceFormat::RGBA32Float),
+        };
+        passes::run(pass, &inputs, &mut outputs);
+
+        for (_, resource, _, image) in outputs.uavs {
+            if let Some(target) = self.resource_mut(resource) {
+                *target = image;
+            }
+        }
+        self.executed_passes.push(pass);
+        Ok(())
+    }
+}
+
+unsafe fn backend<'a>(interface: *mut FfxFsr2Interface) -> Option<&'a mut CpuBackend> {
+    if interface.is_null() {
+        return None;
+    }
+    ((*interface).scratch_buffer as *mut CpuBackend).as_mut()
+}
+
+/// Runs `f` on the backend, callbacks must not unwind into FSR2.
+fn with_backend(
+    interface: *mut FfxFsr2Interface,
+    f: impl FnOnce(&mut CpuBackend) -> Result<(), FfxErrorCode>,
+) -> FfxErrorCode {
+    let Some(backend) = (unsafe { backend(interface) }) else {
+        return FfxErrorCode::InvalidPointer;
+    };
+    match catch_unwind(AssertUnwindSafe(|| f(backend))) {
+        Ok(Ok(())) => FfxErrorCode::Ok,
+        Ok(Err(err)) => err,
+        Err(_) => FfxErrorCode::BackendApiError,
+    }
+}
+
+extern "C" fn create_backend_context(interface: *mut FfxFsr2Interface, _: FfxDevice) -> FfxErrorCode {
+    with_backend(interface, |backend| {
+        *backend = CpuBackend::default();
+        Ok(())
+    })
+}
+
+extern "C" fn get_device_capabilities(
+    _: *mut FfxFsr2Interface,
+    capabilities: *mut FfxDeviceCapabilities,
+    _: FfxDevice,
+) -> FfxErrorCode {
+    if capabilities.is_null() {
+        return FfxErrorCode::InvalidPointer;
+    }
+    unsafe {
+        *capabilities = FfxDeviceCapabilities {
+            minimum_supported_shader_model: FfxShaderModel::ShaderModel_6_6,
+            wave_lane_count_min: 64,
+            wave_lane_count_max: 64,
+            fp16_supported: false,
+            raytracing_supported: false,
+        }
+    };
+    FfxErrorCode::Ok
+}
+
+extern "C" fn destroy_backend_context(interface: *mut FfxFsr2Interface) -> FfxErrorCode {
+    with_backend(interface, |backend| {
+        *backend = CpuBackend::default();
+        Ok(())
+    })
+}
+
+extern "C" fn create_resource(
+    interface: *mut FfxFsr2Interface,
+    description: *const FfxCreateResourceDescription,
+    resource: *mut FfxResourceInternal,
+) -> FfxErrorCode {
+    if description.is_null() || resource.is_null() {
+        return FfxErrorCode::InvalidPointer;
+    }
+    with_backend(interface, |backend| {
+        let description = unsafe { &*description };
+        let init_data = if description.init_data.is_null() {
+            &[][..]
+        } else {
+            unsafe {
+                std::slice::from_raw_parts(description.init_data as *const u8, description.init_data_size as usize)
+            }
+        };
+        let image = CpuImage::from_bytes(description.resource_description, init_data);
+        let index = match backend.resources.iter().position(Option::is_none) {
+            Some(index) => {
+                backend.resources[index] = Some(image);
+                index
+            }
+            None => {
+                backend.resources.push(Some(image));
+                backend.resources.len() - 1
+            }
+        };
+        if index as i32 >= REGISTERED_RESOURCE_BASE {
+            backend.resources.truncate(index);
+            return Err(FfxErrorCode::OutOfMemory);
+        }
+        unsafe { (*resource).internal_index = index as i32 };
+        Ok(())
+    })
+}
+
+extern "C" fn register_resource(
+    interface: *mut FfxFsr2Interface,
+    resource: *const FfxResource,
+    internal: *mut FfxResourceInternal,
+) -> FfxErrorCode {
+    if resource.is_null() || internal.is_null() {
+        return FfxErrorCode::InvalidPointer;
+    }
+    with_backend(interface, |backend| {
+        let resource = unsafe { &*resource };
+        let image = if resource.resource.is_null() {
+            RegisteredImage::Placeholder(CpuImage::from_description(resource.description))
+        } else {
+            RegisteredImage::External(resource.resource as *mut CpuImage)
+        };
+        backend.registered.push(image);
+        unsafe { (*internal).internal_index = REGISTERED_RESOURCE_BASE + backend.registered.len() as i32 - 1 };
+        Ok(())
+    })
+}
+
+extern "C" fn unregister_resources(interface: *mut FfxFsr2Interface) -> FfxErrorCode {
+    with_backend(interface, |backend| {
+        backend.registered.clear();
+        Ok(())
+    })
+}
+
+extern "C" fn get_resource_description(
+    interface: *mut FfxFsr2Interface,
+    resource: FfxResourceInternal,
+) -> FfxResourceDescription {
+    unsafe { backend(interface) }
+        .and_then(|backend| backend.resource(resource))
+        .map_or(FfxResource::NULL.description, |image| *image.description())
+}
+
+extern "C" fn destroy_resource(interface: *mut FfxFsr2Interface, resource: FfxResourceInternal) -> FfxErrorCode {
+    with_backend(interface, |backend| {
+        let slot = usize::try_from(resource.internal_index)
+            .ok()
+            .and_then(|index| backend.resources.get_mut(index))
+            .ok_or(FfxErrorCode::InvalidArgument)?;
+        *slot = None;
+        Ok(())
+    })
+}
+
+extern "C" fn create_pipeline(
+    interface: *mut FfxFsr2Interface,
+    pass: FfxFsr2Pass,
+    description: *const FfxPipelineDescription,
+    pipeline: *mut FfxPipelineState,
+) -> FfxErrorCode {
+    if description.is_null() || pipeline.is_null() {
+        return FfxErrorCode::InvalidPointer;
+    }
+    with_backend(interface, |backend| {
+        let flags = FfxFsr2InitializationFlagBits::from_bits_retain(unsafe { (*description).context_flags } as i32);
+        backend.pipelines.push(Some(CpuPipeline { pass, flags }));
+
+        let bindings = passes::bindings(pass);
+        let state = unsafe { &mut *pipeline };
+        let binding = |(slot_index, name): (usize, &&str)| FfxResourceBinding {
+            slot_index: slot_index as u32,
+            resource_identifier: 0,
+            name: ResourceName::new(name).into_raw(),
+        };
+        *state = unsafe { std::mem::zeroed() };
+        state.pipeline = backend.pipelines.len() as FfxPipeline;
+        state.srv_count = bindings.srvs.len() as u32;
+        state.uav_count = bindings.uavs.len() as u32;
+        state.const_count = bindings.cbs.len() as u32;
+        for (slot, binding) in state
+            .srv_resource_bindings
+            .iter_mut()
+            .zip(bindings.srvs.iter().enumerate().map(binding))
+        {
+            *slot = binding;
+        }
+        for (slot, binding) in state
+            .uav_resource_bindings
+            .iter_mut()
+            .zip(bindings.uavs.iter().enumerate().map(binding))
+        {
+            *slot = binding;
+        }
+        for (slot, binding) in state
+            .cv_resource_bindings
+            .iter_mut()
+            .zip(bindings.cbs.iter().enumerate().map(binding))
+        {
+            *slot = binding;
+        }
+        Ok(())
+    })
+}
+
+extern "C" fn destroy_pipeline(interface: *mut FfxFsr2Interface, pipeline: *mut FfxPipelineState) -> FfxErrorCode {
+    if pipeline.is_null() {
+        return FfxErrorCode::InvalidPointer;
+    }
+    with_backend(interface, |backend| {
+        let pipeline = unsafe { &mut *pipeline };
+        if let Some(slot) = (pipeline.pipeline as usize)
+            .checked_sub(1)
+            .and_then(|index| backend.pipelines.get_mut(index))
+        {
+            *slot = None;
+        }
+        pipeline.pipeline = std::ptr::null_mut();
+        Ok(())
+    })
+}
+
+extern "C" fn schedule_gpu_job(interface: *mut FfxFsr2Interface, job: *const FfxGpuJobDescription) -> FfxErrorCode {
+    if job.is_null() {
+        return FfxErrorCode::InvalidPointer;
+    }
+    with_backend(interface, |backend| {
+        backend.jobs.push(unsafe { *job });
+        Ok(())
+    })
+}
+
+extern "C" fn execute_gpu_jobs(interface: *mut FfxFsr2Interface, _: FfxCommandList) -> FfxErrorCode {
+    with_backend(interface, |backend| {
+        backend.executed_passes.clear();
+        let jobs = std::mem::take(&mut backend.jobs);
+        jobs.iter().try_for_each(|job| backend.execute(job))
+    })
+}
+
+/// An interface that runs FSR2 on the CPU smoke-test backend, with `backend` as its scratch buffer. `backend` must
+/// not move or be dropped while a context uses the interface. The device passed to `ffxFsr2ContextCreate` is unused
+/// and may be null.
+pub fn ffx_fsr2_get_interface_cpu(backend: &mut CpuBackend) -> FfxFsr2Interface {
+    FfxFsr2Interface {
+        fp_create_backend_context: Some(create_backend_context),
+        fp_get_device_capabilities: Some(get_device_capabilities),
+        fp_destroy_backend_context: Some(destroy_backend_context),
+        fp_create_resource: Some(create_resource),
+        fp_register_resource: Some(register_resource),
+        fp_unregister_resources: Some(unregister_resources),
+        fp_get_resource_description: Some(get_resource_description),
+        fp_destroy_resource: Some(destroy_resource),
+        fp_create_pipeline: Some(create_pipeline),
+        fp_destroy_pipeline: Some(destroy_pipeline),
+        fp_schedule_gpu_job: Some(schedule_gpu_job),
+        fp_execute_gpu_jobs: Some(execute_gpu_jobs),
+        scratch_buffer: backend as *mut CpuBackend as *mut c_void,
+        scratch_buffer_size: std::mem::size_of::<CpuBackend>(),
+    }
+}
+
+/// Wraps `image` for a dispatch on the CPU backend, `is_depth` marks the depth input. The image must outlive the
+/// dispatch and must not be accessed while FSR2 executes.
+pub fn ffx_get_texture_resource_cpu(
+    image: &mut CpuImage,
+    name: Option<&str>,
+    state: FfxResourceState,
+    is_depth: bool,
+) -> FfxResource {
+    let mut resource = FfxResource::new(
+        image as *mut CpuImage as *mut c_void,
+        name.unwrap_or_default(),
+        *image.description(),
+        state,
+    );
+    resource.is_depth = is_depth;
+    resource
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        ffxFsr2ContextCreate, ffxFsr2ContextDestroy, ffxFsr2ContextDispatch, FfxConstantBuffer, FfxFsr2Context,
+        FfxFsr2ContextDescription, FfxGpuJob, FfxSurfaceFormat, Fsr2ConstantLayout, Fsr2Constants, Fsr2RcasConstants,
+        SceneDescription, SyntheticScene, FFX_FSR2_CONTEXT_SIZE,
+    };
+    use crate::jitter::halton;
+    use crate::test_util::size;
+    use std::collections::HashMap;
+
+    /// Creates pipelines and dispatches them the way FSR2 does, binding resources by their binding names.
+    struct Driver {
+        backend: Box<CpuBackend>,
+        interface: FfxFsr2Interface,
+        pipelines: HashMap<FfxFsr2Pass, FfxPipelineState>,
+        resources: HashMap<&'static str, FfxResourceInternal>,
+    }
+
+    impl Driver {
+        fn new(flags: FfxFsr2InitializationFlagBits) -> Self {
+            let mut backend = Box::<CpuBackend>::default();
+            let mut interface = ffx_fsr2_get_interface_cpu(&mut backend);
+            let description = FfxPipelineDescription {
+                context_flags: flags.bits() as u32,
+                samplers: std::ptr::null_mut(),
+                sampler_count: 0,
+                root_constant_buffer_size: std::ptr::null(),
+                root_constant_buffer_count: 0,
+            };
+            let pipelines = FfxFsr2Pass::ALL
+                .into_iter()
+                .map(|pass| {
+                    let mut state = unsafe { std::mem::zeroed() };
+                    let result =
+                        (interface.fp_create_pipeline.unwrap())(&mut interface, pass, &description, &mut state);
+                    assert_eq!(result, FfxErrorCode::Ok);
+                    (pass, state)
+                })
+                .collect();
+            Self {
+                backend,
+                interface,
+                pipelines,
+                resources: HashMap::new(),
+            }
+        }
+
+        fn create(&mut self, name: &'static str, width: u32, height: u32, format: FfxSurfaceFormat) {
+            let description = FfxCreateResourceDescription {
+                heap_type: crate::FfxHeapType::Default,
+                resource_description: *CpuImage::new(width, height, format).description(),
+                initial_state: FfxResourceState::UNORDERED_ACCESS,
+                init_data_size: 0,
+                init_data: std::ptr::null_mut(),
+                name: std::ptr::null(),
+                usage: crate::FfxResourceUsage::UAV,
+                id: 0,
+            };
+            let mut resource = FfxResourceInternal { internal_index: -1 };
+            let result = (self.interface.fp_create_resource.unwrap())(&mut self.interface, &description, &mut resource);
+            assert_eq!(result, FfxErrorCode::Ok);
+            self.resources.insert(name, resource);
+        }
+
+        fn register(&mut self, name: &'static str, image: &mut CpuImage) {
+            let is_depth = name == "input_depth";
+            let resource = ffx_get_texture_resource_cpu(image, Some(name), FfxResourceState::COMPUTE_READ, is_depth);
+            let mut internal = FfxResourceInternal { internal_index: -1 };
+            let result = (self.interface.fp_register_resource.unwrap())(&mut self.interface, &resource, &mut internal);
+            assert_eq!(result, FfxErrorCode::Ok);
+            self.resources.insert(name, internal);
+        }
+
+        fn swap(&mut self, a: &'static str, b: &'static str) {
+            let (ra, rb) = (self.resources[a], self.resources[b]);
+            self.resources.insert(a, rb);
+            self.resources.insert(b, ra);
+        }
+
+        /// Binds `r_<name>` and `rw_<name>` to the resource called `<name>`, unless there is one called
+        /// `r_<name>`.
+        fn schedule(&mut self, pass: FfxFsr2Pass, constants: &[(&str, FfxConstantBuffer)]) {
+            let mut job: FfxComputeJobDescription = unsafe { std::mem::zeroed() };
+            job.pipeline = self.pipelines[&pass];
+            let lookup = |binding: &FfxResourceBinding, prefix: &str| {
+                let name = binding.name().to_string_lossy();
+                let resource = self.resources.get(name.as_str());
+                resource
+                    .or_else(|| self.resources.get(name.strip_prefix(prefix).unwrap()))
+                    .copied()
+                    .unwrap_or(FfxResourceInternal { internal_index: -1 })
+            };
+            for i in 0..job.pipeline.srv_count as usize {
+                job.srvs[i] = lookup(&job.pipeline.srv_resource_bindings[i], "r_");
+            }
+            for i in 0..job.pipeline.uav_count as usize {
+                job.uavs[i] = lookup(&job.pipeline.uav_resource_bindings[i], "rw_");
+            }
+            for i in 0..job.pipeline.const_count as usize {
+                let name = job.pipeline.cv_resource_bindings[i].name().to_string_lossy();
+                job.cbs[i] = constants.iter().find(|(cb, _)| *cb == name).unwrap().1;
+            }
+            let job = FfxGpuJobDescription {
+                ty: FfxJobType::Compute,
+                descriptor: FfxGpuJob { compute_job: job },
+            };
+            let result = (self.interface.fp_schedule_gpu_job.unwrap())(&mut self.interface, &job);
+            assert_eq!(result, FfxErrorCode::Ok);
+        }
+
+        fn execute(&mut self) {
+            let result = (self.interface.fp_execute_gpu_jobs.unwrap())(&mut self.interface, std::ptr::null_mut());
+            assert_eq!(result, FfxErrorCode::Ok);
+            let result = (self.interface.fp_unregister_resources.unwrap())(&mut self.interface);
+            assert_eq!(result, FfxErrorCode::Ok);
+        }
+    }
+
+    /// A smooth pattern, sampled at continuous coordinates in [0, 1].
+    fn pattern(u: f32, v: f32) -> [f32; 4] {
+        let value = 0.5 + 0.25 * (u * 6.0).sin() * (v * 4.0).cos();
+        [value, 0.5 * value, 1.0 - value, 1.0]
+    }
+
+    #[test]
+    fn pipelines_declare_bindings() {
+        let driver = Driver::new(FfxFsr2InitializationFlagBits::empty());
+        let accumulate = &driver.pipelines[&FfxFsr2Pass::Accumulate];
+        assert_eq!(accumulate.const_count, 1);
+        assert_eq!(
+            accumulate.cv_resource_bindings[0].name().to_string_lossy(),
+            Fsr2Constants::BINDING_NAME
+        );
+        let uavs: Vec<_> = (0..accumulate.uav_count as usize)
+            .map(|i| accumulate.uav_resource_bindings[i].name().to_string_lossy())
+            .collect();
+        assert!(uavs.contains(&"rw_upscaled_output".to_string()));
+
+        let sharpen = &driver.pipelines[&FfxFsr2Pass::AccumulateSharpen];
+        assert!((0..sharpen.uav_count as usize)
+            .all(|i| sharpen.uav_resource_bindings[i].name().to_string_lossy() != "rw_upscaled_output"));
+        assert_ne!(accumulate.pipeline, sharpen.pipeline);
+    }
+
+    #[test]
+    fn rcas_sharpens_edges() {
+        let mut driver = Driver::new(FfxFsr2InitializationFlagBits::empty());
+        let mut input = CpuImage::new(8, 1, FfxSurfaceFormat::RGBA16Float);
+        for x in 0..8 {
+            let value = if x < 4 { 0.25 } else { 0.75 };
+            input.store(x, 0, [value, value, value, 1.0]);
+        }
+        let mut output = CpuImage::new(8, 1, FfxSurfaceFormat::RGBA16Float);
+        driver.register("rcas_input", &mut input);
+        driver.register("upscaled_output", &mut output);
+        let constants = Fsr2Constants {
+            display_size: [8, 1],
+            pre_exposure: 1.0,
+            ..Default::default()
+        };
+        let rcas = Fsr2RcasConstants {
+            rcas_config: [1.0f32.to_bits(), 0, 0, 0],
+        };
+        driver.schedule(
+            FfxFsr2Pass::Rcas,
+            &[
+                ("cbFSR2", FfxConstantBuffer::new(&constants)),
+                ("cbRCAS", FfxConstantBuffer::new(&rcas)),
+            ],
+        );
+        driver.execute();
+
+        assert_eq!(driver.backend.executed_passes(), [FfxFsr2Pass::Rcas]);
+        assert_eq!(output.load(0, 0)[0], 0.25, "flat areas are unchanged");
+        assert!(output.load(3, 0)[0] < 0.25, "dark side of the edge gets darker");
+        assert!(output.load(4, 0)[0] > 0.75, "bright side of the edge gets brighter");
+    }
+
+    #[test]
+    fn temporal_upscaling_converges() {
+        const RENDER: [i32; 2] = [16, 12];
+        const DISPLAY: [i32; 2] = [32, 24];
+        let mut driver = Driver::new(FfxFsr2InitializationFlagBits::empty());
+        for (name, format) in [
+            ("reconstructed_previous_nearest_depth", FfxSurfaceFormat::R32Uint),
+            ("dilated_motion_vectors", FfxSurfaceFormat::RG16Float),
+            ("dilatedDepth", FfxSurfaceFormat::R32Float),
+            ("lock_input_luma", FfxSurfaceFormat::R16Float),
+            ("dilated_reactive_masks", FfxSurfaceFormat::RG8Unorm),
+            ("prepared_input_color", FfxSurfaceFormat::RGBA16Float),
+        ] {
+            driver.create(name, RENDER[0] as u32, RENDER[1] as u32, format);
+        }
+        for (name, format) in [
+            ("internal_upscaled_color", FfxSurfaceFormat::RGBA16Float),
+            ("history_upscaled_color", FfxSurfaceFormat::RGBA16Float),
+            ("lock_status", FfxSurfaceFormat::RG16Float),
+            ("history_lock_status", FfxSurfaceFormat::RG16Float),
+            ("new_locks", FfxSurfaceFormat::R8Unorm),
+        ] {
+            driver.create(name, DISPLAY[0] as u32, DISPLAY[1] as u32, format);
+        }
+
+        let mut output = CpuImage::new(DISPLAY[0] as u32, DISPLAY[1] as u32, FfxSurfaceFormat::RGBA16Float);
+        let error = |output: &CpuImage| {
+            let mut sum = 0.0;
+            for y in 0..DISPLAY[1] {
+                for x in 0..DISPLAY[0] {
+                    let expected = pattern(
+                        (x as f32 + 0.5) / DISPLAY[0] as f32,
+                        (y as f32 + 0.5) / DISPLAY[1] as f32,
+                    );
+                    sum += (output.load(x, y)[0] - expected[0]).abs();
+                }
+            }
+            sum / (DISPLAY[0] * DISPLAY[1]) as f32
+        };
+
+        let mut errors = Vec::new();
+        for frame in 0..16 {
+            let jitter = [halton(frame + 1, 2) - 0.5, halton(frame + 1, 3) - 0.5];
+            let mut color = CpuImage::new(RENDER[0] as u32, RENDER[1] as u32, FfxSurfaceFormat::RGBA16Float);
+            for y in 0..RENDER[1] {
+                for x in 0..RENDER[0] {
+                    // Jitter translates the projection, so the pixel center shows the scene at `- jitter`.
+                    let u = (x as f32 + 0.5 - jitter[0]) / RENDER[0] as f32;
+                    let v = (y as f32 + 0.5 - jitter[1]) / RENDER[1] as f32;
+                    color.store(x, y, pattern(u, v));
+                }
+            }
+            let mut depth = CpuImage::new(RENDER[0] as u32, RENDER[1] as u32, FfxSurfaceFormat::R32Float);
+            depth.fill([0.5; 4]);
+            let mut motion_vectors = CpuImage::new(RENDER[0] as u32, RENDER[1] as u32, FfxSurfaceFormat::RG16Float);
+
+            // FSR2 ping-pongs the history, the accumulate pass reads last frame's copy.
+            driver.swap("internal_upscaled_color", "history_upscaled_color");
+            driver.swap("lock_status", "history_lock_status");
+            driver
+                .resources
+                .insert("r_internal_upscaled_color", driver.resources["history_upscaled_color"]);
+            driver
+                .resources
+                .insert("r_lock_status", driver.resources["history_lock_status"]);
+            driver.register("input_color_jittered", &mut color);
+            driver.register("input_depth", &mut depth);
+            driver.register("input_motion_vectors", &mut motion_vectors);
+            driver.register("upscaled_output", &mut output);
+
+            let constants = FfxConstantBuffer::new(&Fsr2Constants {
+                render_size: RENDER,
+                max_render_size: RENDER,
+                display_size: DISPLAY,
+                input_color_resource_dimensions: RENDER,
+                frame_index: frame as i32,
+                jitter_offset: jitter,
+                downscale_factor: [0.5, 0.5],
+                pre_exposure: 1.0,
+                jitter_phase_count: 8.0,
+                ..Default::default()
+            });
+            let cb = [("cbFSR2", constants)];
+            driver.schedule(FfxFsr2Pass::ReconstructPreviousDepth, &cb);
+            driver.schedule(FfxFsr2Pass::DepthClip, &cb);
+            driver.schedule(FfxFsr2Pass::Lock, &cb);
+            driver.schedule(FfxFsr2Pass::Accumulate, &cb);
+            driver.execute();
+            errors.push(error(&output));
+        }
+
+        let accumulated = driver
+            .backend
+            .resource(driver.resources["internal_upscaled_color"])
+            .unwrap();
+        assert!(accumulated.load(5, 5)[3] > 4.0, "history accumulates weight");
+        assert!(errors[15] < errors[0], "error {:?} does not decrease", errors);
+        assert!(errors[15] < 0.01, "error {:?} too large", errors);
+    }
+
+    #[test]
+    fn context_dispatches_frames() {
+        let description = SceneDescription::new(size(32, 18), size(64, 36));
+        let flags = description.flags;
+        let display_size = description.display_size;
+        let scene = SyntheticScene::new(description);
+
+        let mut backend = Box::<CpuBackend>::default();
+        let mut context = Box::new(FfxFsr2Context {
+            data: [0; FFX_FSR2_CONTEXT_SIZE],
+        });
+        let context_description = FfxFsr2ContextDescription {
+            flags,
+            max_render_size: scene.description().render_size,
+            display_size,
+            callbacks: ffx_fsr2_get_interface_cpu(&mut backend),
+            device: std::ptr::null_mut(),
+            fp_message: None,
+        };
+        let result = unsafe { ffxFsr2ContextCreate(context.as_mut(), &context_description) };
+        assert_eq!(result, FfxErrorCode::Ok);
+
+        let mut output = CpuImage::new(display_size.width, display_size.height, FfxSurfaceFormat::RGBA16Float);
+        for frame in 0..4 {
+            let mut scene_frame = scene.render(frame);
+            let dispatch = scene_frame.dispatch_description(&mut output);
+            let result = unsafe { ffxFsr2ContextDispatch(context.as_mut(), &dispatch) };
+            assert_eq!(result, FfxErrorCode::Ok, "frame {frame}");
+            assert!(backend.executed_passes().contains(&FfxFsr2Pass::Accumulate), "frame {frame}");
+        }
+        let center = output.load(display_size.width as i32 / 2, display_size.height as i32 / 2);
+        assert!(center[..3].iter().any(|&value| value > 0.0), "output was not written");
+        assert_eq!(unsafe { ffxFsr2ContextDestroy(context.as_mut()) }, FfxErrorCode::Ok);
+    }
+}
@@ -0,0 +1,219 @@
+use crate::format::f16_to_f32;
+use crate::{FfxComponentType, FfxResourceDescription, FfxResourceFlags, FfxResourceType, FfxSurfaceFormat};
+
+/// A texture in system memory. Every format is stored as four `f32` channels per texel, stores clamp to the
+/// range of normalized formats. `R32Uint` textures FSR2 uses to hold depth bits store the depth directly.
+#[derive(Clone, Debug)]
+pub struct CpuImage {
+    description: FfxResourceDescription,
+    mips: Vec<Vec<[f32; 4]>>,
+}
+
+impl CpuImage {
+    /// A single mip 2D texture filled with zeros.
+    pub fn new(width: u32, height: u32, format: FfxSurfaceFormat) -> Self {
+        Self::from_description(FfxResourceDescription {
+            ty: FfxResourceType::Texture2D,
+            surface_format: format,
+            width,
+            height,
+            depth: 1,
+            mip_count: 1,
+            flags: FfxResourceFlags::NONE,
+        })
+    }
+
+    pub fn from_description(description: FfxResourceDescription) -> Self {
+        let mip_count = description.mip_count.max(1);
+        let mips = (0..mip_count)
+            .map(|mip| {
+                let (width, height) = mip_size(&description, mip);
+                vec![[0.0; 4]; (width * height) as usize]
+            })
+            .collect();
+        Self {
+            description: FfxResourceDescription {
+                mip_count,
+                ..description
+            },
+            mips,
+        }
+    }
+
+    /// Decodes `data`, tightly packed texels of mip 0 in the image's format.
+    pub fn from_bytes(description: FfxResourceDescription, data: &[u8]) -> Self {
+        let mut image = Self::from_description(description);
+        let format = description.surface_format.typed();
+        let Some(info) = format.info() else {
+            return image;
+        };
+        if format == FfxSurfaceFormat::R11G11B11Float {
+            return image;
+        }
+        let channel_size = (info.bytes_per_pixel / info.channel_count) as usize;
+        for (texel, bytes) in image.mips[0]
+            .iter_mut()
+            .zip(data.chunks_exact(info.bytes_per_pixel as usize))
+        {
+            for (channel, bytes) in texel.iter_mut().zip(bytes.chunks_exact(channel_size)) {
+                *channel = decode_channel(bytes, info.component_type);
+            }
+        }
+        image
+    }
+
+    pub fn description(&self) -> &FfxResourceDescription {
+        &self.description
+    }
+
+    pub fn format(&self) -> FfxSurfaceFormat {
+        self.description.surface_format
+    }
+
+    pub fn width(&self) -> u32 {
+        self.description.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.description.height
+    }
+
+    pub fn mip_count(&self) -> u32 {
+        self.description.mip_count
+    }
+
+    pub fn mip_size(&self, mip: u32) -> (u32, u32) {
+        mip_size(&self.description, mip)
+    }
+
+    /// Texels of `mip` in rows, `None` if the mip does not exist.
+    pub fn mip(&self, mip: u32) -> Option<&[[f32; 4]]> {
+        self.mips.get(mip as usize).map(Vec::as_slice)
+    }
+
+    pub fn texels(&self) -> &[[f32; 4]] {
+        &self.mips[0]
+    }
+
+    /// Writes to the texels skip the clamping of [`Self::store`].
+    pub fn texels_mut(&mut self) -> &mut [[f32; 4]] {
+        &mut self.mips[0]
+    }
+
+    /// Texel of mip 0, coordinates are clamped to the edge.
+    pub fn load(&self, x: i32, y: i32) -> [f32; 4] {
+        self.load_mip(0, x, y)
+    }
+
+    pub fn load_mip(&self, mip: u32, x: i32, y: i32) -> [f32; 4] {
+        let Some(texels) = self.mips.get(mip as usize) else {
+            return [0.0; 4];
+        };
+        let (width, height) = self.mip_size(mip);
+        if texels.is_empty() {
+            return [0.0; 4];
+        }
+        let x = x.clamp(0, width as i32 - 1) as usize;
+        let y = y.clamp(0, height as i32 - 1) as usize;
+        texels[y * width as usize + x]
+    }
+
+    /// Bilinear sample of mip 0 at normalized coordinates, clamped to the edge.
+    pub fn sample(&self, uv: [f32; 2]) -> [f32; 4] {
+        self.sample_mip(0, uv)
+    }
+
+    pub fn sample_mip(&self, mip: u32, uv: [f32; 2]) -> [f32; 4] {
+        let (width, height) = self.mip_size(mip);
+        let x = uv[0] * width as f32 - 0.5;
+        let y = uv[1] * height as f32 - 0.5;
+        let (x0, y0) = (x.floor(), y.floor());
+        let (fx, fy) = (x - x0, y - y0);
+        let (x0, y0) = (x0 as i32, y0 as i32);
+        let top = lerp4(self.load_mip(mip, x0, y0), self.load_mip(mip, x0 + 1, y0), fx);
+        let bottom = lerp4(self.load_mip(mip, x0, y0 + 1), self.load_mip(mip, x0 + 1, y0 + 1), fx);
+        lerp4(top, bottom, fy)
+    }
+
+    /// Stores a texel of mip 0, out of bounds coordinates are ignored.
+    pub fn store(&mut self, x: i32, y: i32, value: [f32; 4]) {
+        self.store_mip(0, x, y, value)
+    }
+
+    pub fn store_mip(&mut self, mip: u32, x: i32, y: i32, value: [f32; 4]) {
+        let (width, height) = self.mip_size(mip);
+        if x < 0 || y < 0 || x >= width as i32 || y >= height as i32 {
+            return;
+        }
+        let component_type = self.format().info().map(|info| info.component_type);
+        let value = value.map(|channel| match component_type {
+            Some(FfxComponentType::Unorm) => channel.clamp(0.0, 1.0),
+            Some(FfxComponentType::Snorm) => channel.clamp(-1.0, 1.0),
+            _ => channel,
+        });
+        if let Some(texels) = self.mips.get_mut(mip as usize) {
+            texels[y as usize * width as usize + x as usize] = value;
+        }
+    }
+
+    pub fn fill(&mut self, value: [f32; 4]) {
+        for mip in &mut self.mips {
+            mip.fill(value);
+        }
+    }
+}
+
+fn mip_size(description: &FfxResourceDescription, mip: u32) -> (u32, u32) {
+    let width = (description.width >> mip).max(1);
+    let height = match description.ty {
+        FfxResourceType::Buffer | FfxResourceType::Texture1D => 1,
+        _ => (description.height >> mip).max(1),
+    };
+    (width, height)
+}
+
+fn lerp4(a: [f32; 4], b: [f32; 4], t: f32) -> [f32; 4] {
+    std::array::from_fn(|i| a[i] + (b[i] - a[i]) * t)
+}
+
+fn decode_channel(bytes: &[u8], component_type: FfxComponentType) -> f32 {
+    match (component_type, bytes.len()) {
+        (FfxComponentType::Float, 4) => f32::from_le_bytes(bytes.try_into().unwrap()),
+        (FfxComponentType::Float, 2) => f16_to_f32(u16::from_le_bytes(bytes.try_into().unwrap())),
+        (FfxComponentType::Unorm, 1) => bytes[0] as f32 / 255.0,
+        (FfxComponentType::Unorm, 2) => u16::from_le_bytes(bytes.try_into().unwrap()) as f32 / 65535.0,
+        (FfxComponentType::Snorm, 2) => (i16::from_le_bytes(bytes.try_into().unwrap()) as f32 / 32767.0).max(-1.0),
+        (FfxComponentType::Uint, 1) => bytes[0] as f32,
+        (FfxComponentType::Uint, 2) => u16::from_le_bytes(bytes.try_into().unwrap()) as f32,
+        (FfxComponentType::Uint, 4) => u32::from_le_bytes(bytes.try_into().unwrap()) as f32,
+        _ => 0.0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_init_data() {
+        let description = *CpuImage::new(2, 1, FfxSurfaceFormat::R16Snorm).description();
+        let data = [i16::MAX.to_le_bytes(), (-16384i16).to_le_bytes()].concat();
+        let image = CpuImage::from_bytes(description, &data);
+        assert_eq!(image.load(0, 0)[0], 1.0);
+        assert!((image.load(1, 0)[0] + 0.5).abs() < 1e-4);
+    }
+
+    #[test]
+    fn mips_and_sampling() {
+        let mut description = *CpuImage::new(8, 4, FfxSurfaceFormat::R8Unorm).description();
+        description.mip_count = 4;
+        let mut image = CpuImage::from_description(description);
+        assert_eq!(image.mip_size(3), (1, 1));
+        assert_eq!(image.mip(3).unwrap().len(), 1);
+
+        image.store(0, 0, [2.0, -1.0, 0.5, 1.0]);
+        assert_eq!(image.load(-3, -3), [1.0, 0.0, 0.5, 1.0]);
+        let sample = image.sample([1.0 / 8.0, 0.5 / 4.0]);
+        assert_eq!(sample[0], 0.5);
+    }
+}
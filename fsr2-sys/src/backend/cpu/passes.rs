@@ -0,0 +1,763 @@
+//! Simplified stand-ins for the FSR2 passes, reading and writing the resources of the FSR2 2.2 shaders so FSR2's job
+//! flow can be smoke tested. They are not ports of the shader math: depth clip, locking and accumulation use this
+//! crate's own heuristics and tuning constants, accumulation has no luma history, Lanczos LUT or maximum bias LUT,
+//! and the luminance pyramid is a plain average instead of SPD. Their output only needs to be plausible, it is
+//! neither a reference for the GPU backends nor useful for debugging shader behaviour.
+
+use super::CpuImage;
+use crate::filter::lanczos2;
+use crate::rcas::rcas_pixel;
+use crate::{
+    FfxConstantBuffer, FfxFsr2InitializationFlagBits, FfxFsr2Pass, FfxResourceInternal, Fsr2ConstantLayout,
+    Fsr2Constants, Fsr2GenerateReactiveConstants, Fsr2GenerateReactiveConstants2, Fsr2RcasConstants,
+};
+
+/// Shader resource views, unordered access views and constant buffers a pass declares.
+pub(super) struct PassBindings {
+    pub(super) srvs: &'static [&'static str],
+    pub(super) uavs: &'static [&'static str],
+    pub(super) cbs: &'static [&'static str],
+}
+
+const ACCUMULATE_SRVS: &[&str] = &[
+    "r_input_exposure",
+    "r_dilated_reactive_masks",
+    "r_dilated_motion_vectors",
+    "r_internal_upscaled_color",
+    "r_lock_status",
+    "r_prepared_input_color",
+    "r_new_locks",
+];
+
+pub(super) fn bindings(pass: FfxFsr2Pass) -> PassBindings {
+    let (srvs, uavs, cbs): (&[&str], &[&str], &[&str]) = match pass {
+        FfxFsr2Pass::DepthClip => (
+            &[
+                "r_reconstructed_previous_nearest_depth",
+                "r_dilated_motion_vectors",
+                "r_dilatedDepth",
+                "r_reactive_mask",
+                "r_transparency_and_composition_mask",
+                "r_input_color_jittered",
+                "r_input_exposure",
+            ],
+            &["rw_dilated_reactive_masks", "rw_prepared_input_color"],
+            &["cbFSR2"],
+        ),
+        FfxFsr2Pass::ReconstructPreviousDepth => (
+            &[
+                "r_input_motion_vectors",
+                "r_input_depth",
+                "r_input_color_jittered",
+                "r_input_exposure",
+            ],
+            &[
+                "rw_reconstructed_previous_nearest_depth",
+                "rw_dilated_motion_vectors",
+                "rw_dilatedDepth",
+                "rw_lock_input_luma",
+            ],
+            &["cbFSR2"],
+        ),
+        FfxFsr2Pass::Lock => (
+            &["r_lock_input_luma"],
+            &["rw_new_locks", "rw_reconstructed_previous_nearest_depth"],
+            &["cbFSR2"],
+        ),
+        FfxFsr2Pass::Accumulate => (
+            ACCUMULATE_SRVS,
+            &[
+                "rw_internal_upscaled_color",
+                "rw_lock_status",
+                "rw_new_locks",
+                "rw_upscaled_output",
+            ],
+            &["cbFSR2"],
+        ),
+        // Leaves the output to the RCAS pass.
+        FfxFsr2Pass::AccumulateSharpen => (
+            ACCUMULATE_SRVS,
+            &["rw_internal_upscaled_color", "rw_lock_status", "rw_new_locks"],
+            &["cbFSR2"],
+        ),
+        FfxFsr2Pass::Rcas => (
+            &["r_input_exposure", "r_rcas_input"],
+            &["rw_upscaled_output"],
+            &["cbFSR2", "cbRCAS"],
+        ),
+        FfxFsr2Pass::ComputeLuminancePyramid => (
+            &["r_input_color_jittered"],
+            &[
+                "rw_spd_global_atomic",
+                "rw_img_mip_shading_change",
+                "rw_img_mip_5",
+                "rw_auto_exposure",
+            ],
+            &["cbFSR2", "cbSPD"],
+        ),
+        FfxFsr2Pass::GenerateReactive => (
+            &["r_input_opaque_only", "r_input_color_jittered"],
+            &["rw_output_autoreactive"],
+            &["cbGenerateReactive"],
+        ),
+        FfxFsr2Pass::TcrAutogenerate => (
+            &[
+                "r_input_opaque_only",
+                "r_input_color_jittered",
+                "r_input_motion_vectors",
+                "r_input_prev_color_pre_alpha",
+                "r_input_prev_color_post_alpha",
+                "r_reactive_mask",
+                "r_transparency_and_composition_mask",
+            ],
+            &[
+                "rw_output_autoreactive",
+                "rw_output_autocomposition",
+                "rw_output_prev_color_pre_alpha",
+                "rw_output_prev_color_post_alpha",
+            ],
+            &["cbFSR2", "cbGenerateReactive"],
+        ),
+    };
+    PassBindings { srvs, uavs, cbs }
+}
+
+/// The bound SRVs and constant buffers of a dispatch.
+pub(super) struct PassInputs<'a> {
+    pub(super) pass: FfxFsr2Pass,
+    pub(super) flags: FfxFsr2InitializationFlagBits,
+    pub(super) srvs: Vec<(String, &'a CpuImage)>,
+    pub(super) cbs: Vec<(String, &'a FfxConstantBuffer)>,
+    pub(super) empty: CpuImage,
+}
+
+impl PassInputs<'_> {
+    /// The image bound to `name`, a black 1x1 image if nothing is.
+    fn srv(&self, name: &str) -> &CpuImage {
+        self.srvs
+            .iter()
+            .find(|(binding, _)| binding == name)
+            .map_or(&self.empty, |(_, image)| image)
+    }
+
+    /// The constants bound to `T::BINDING_NAME`, the default if the pass does not bind `T`.
+    fn constants<T: Fsr2ConstantLayout + Default>(&self) -> T {
+        if !T::PASSES.contains(&self.pass) {
+            return T::default();
+        }
+        self.cbs
+            .iter()
+            .find(|(binding, _)| binding == T::BINDING_NAME)
+            .and_then(|(_, cb)| cb.cast::<T>())
+            .copied()
+            .unwrap_or_default()
+    }
+
+    fn exposure(&self) -> f32 {
+        match self.srv("r_input_exposure").load(0, 0)[0] {
+            exposure if exposure > 0.0 && exposure.is_finite() => exposure,
+            _ => 1.0,
+        }
+    }
+
+    fn inverted_depth(&self) -> bool {
+        self.flags
+            .contains(FfxFsr2InitializationFlagBits::ENABLE_DEPTH_INVERTED)
+    }
+}
+
+/// Copies of the bound UAVs, written back after the dispatch.
+#[derive(Default)]
+pub(super) struct PassOutputs {
+    pub(super) uavs: Vec<(String, FfxResourceInternal, u32, CpuImage)>,
+}
+
+impl PassOutputs {
+    /// The image bound to `name` and the mip to write.
+    fn uav(&mut self, name: &str) -> Option<(&mut CpuImage, u32)> {
+        self.uavs
+            .iter_mut()
+            .find(|(binding, ..)| binding == name)
+            .map(|(_, _, mip, image)| (image, *mip))
+    }
+
+    fn store(&mut self, name: &str, x: i32, y: i32, value: [f32; 4]) {
+        if let Some((image, mip)) = self.uav(name) {
+            image.store_mip(mip, x, y, value);
+        }
+    }
+}
+
+pub(super) fn run(pass: FfxFsr2Pass, inputs: &PassInputs, outputs: &mut PassOutputs) {
+    match pass {
+        FfxFsr2Pass::DepthClip => depth_clip(inputs, outputs),
+        FfxFsr2Pass::ReconstructPreviousDepth => reconstruct_previous_depth(inputs, outputs),
+        FfxFsr2Pass::Lock => lock(inputs, outputs),
+        FfxFsr2Pass::Accumulate | FfxFsr2Pass::AccumulateSharpen => accumulate(inputs, outputs),
+        FfxFsr2Pass::Rcas => rcas(inputs, outputs),
+        FfxFsr2Pass::ComputeLuminancePyramid => luminance_pyramid(inputs, outputs),
+        FfxFsr2Pass::GenerateReactive => generate_reactive(inputs, outputs),
+        FfxFsr2Pass::TcrAutogenerate => tcr_autogenerate(inputs, outputs),
+    }
+}
+
+// Tuning constants of the simplified passes, not taken from the shaders.
+
+/// Tolerance of the depth clip, as a fraction of the view space depth.
+const DEPTH_CLIP_SEPARATION: f32 = 0.01;
+/// Accumulated history weight at which a pixel is considered converged.
+const MAX_ACCUMULATION_WEIGHT: f32 = 12.0;
+/// Luma difference to the neighbourhood above which a pixel is locked as a thin feature.
+const LOCK_CONTRAST_THRESHOLD: f32 = 0.02;
+/// Relative luma change that releases a lock.
+const LOCK_LUMA_TOLERANCE: f32 = 0.2;
+/// Widens the upsampling kernel so every output pixel is covered by some samples of every frame.
+const KERNEL_WIDTH: f32 = 1.5;
+
+fn pixels(size: [i32; 2]) -> impl Iterator<Item = (i32, i32)> {
+    (0..size[1].max(0)).flat_map(move |y| (0..size[0].max(0)).map(move |x| (x, y)))
+}
+
+fn rgb(value: [f32; 4]) -> [f32; 3] {
+    [value[0], value[1], value[2]]
+}
+
+fn luma(rgb: [f32; 3]) -> f32 {
+    0.2126 * rgb[0] + 0.7152 * rgb[1] + 0.0722 * rgb[2]
+}
+
+fn perceptual_luma(rgb: [f32; 3]) -> f32 {
+    luma(rgb).max(0.0).powf(1.0 / 2.2)
+}
+
+fn max3(rgb: [f32; 3]) -> f32 {
+    rgb[0].max(rgb[1]).max(rgb[2])
+}
+
+fn to_ycocg(rgb: [f32; 3]) -> [f32; 3] {
+    [
+        0.25 * rgb[0] + 0.5 * rgb[1] + 0.25 * rgb[2],
+        0.5 * rgb[0] - 0.5 * rgb[2],
+        -0.25 * rgb[0] + 0.5 * rgb[1] - 0.25 * rgb[2],
+    ]
+}
+
+fn from_ycocg(ycocg: [f32; 3]) -> [f32; 3] {
+    let [y, co, cg] = ycocg;
+    [y + co - cg, y + cg, y - co - cg]
+}
+
+fn lerp3(a: [f32; 3], b: [f32; 3], t: f32) -> [f32; 3] {
+    std::array::from_fn(|i| a[i] + (b[i] - a[i]) * t)
+}
+
+fn saturate(value: f32) -> f32 {
+    value.clamp(0.0, 1.0)
+}
+
+/// Bilinear sample at a position in texels, with texel centers at `+ 0.5`.
+fn sample_at(image: &CpuImage, position: [f32; 2]) -> [f32; 4] {
+    image.sample([position[0] / image.width() as f32, position[1] / image.height() as f32])
+}
+
+fn prepare(rgb: [f32; 3], exposure: f32, pre_exposure: f32) -> [f32; 3] {
+    let scale = exposure / if pre_exposure > 0.0 { pre_exposure } else { 1.0 };
+    rgb.map(|channel| channel * scale)
+}
+
+fn unprepare(rgb: [f32; 3], exposure: f32, pre_exposure: f32) -> [f32; 3] {
+    let scale = if pre_exposure > 0.0 { pre_exposure } else { 1.0 } / exposure;
+    rgb.map(|channel| channel * scale)
+}
+
+fn nearer(depth: f32, than: f32, inverted: bool) -> bool {
+    if inverted {
+        depth > than
+    } else {
+        depth < than
+    }
+}
+
+fn far_depth(inverted: bool) -> f32 {
+    if inverted {
+        0.0
+    } else {
+        1.0
+    }
+}
+
+/// Distance to the camera, from `device_to_view_depth` or linear in device depth if it is not set.
+fn view_depth(depth: f32, constants: &Fsr2Constants, inverted: bool) -> f32 {
+    let [offset, scale, ..] = constants.device_to_view_depth;
+    if scale == 0.0 {
+        return if inverted { 1.0 - depth } else { depth };
+    }
+    let view = scale / (depth - offset);
+    if view.is_finite() {
+        view.abs()
+    } else {
+        f32::MAX
+    }
+}
+
+/// The motion of a render pixel in UV space, from the current to the previous frame.
+fn input_motion(inputs: &PassInputs, constants: &Fsr2Constants, x: i32, y: i32) -> [f32; 2] {
+    let motion_vectors = inputs.srv("r_input_motion_vectors");
+    let motion = if inputs
+        .flags
+        .contains(FfxFsr2InitializationFlagBits::ENABLE_DISPLAY_RESOLUTION_MOTION_VECTORS)
+    {
+        let uv = [
+            (x as f32 + 0.5) / constants.render_size[0] as f32,
+            (y as f32 + 0.5) / constants.render_size[1] as f32,
+        ];
+        let size = constants.display_size;
+        sample_at(motion_vectors, [uv[0] * size[0] as f32, uv[1] * size[1] as f32])
+    } else {
+        motion_vectors.load(x, y)
+    };
+    let mut motion = [
+        motion[0] * constants.motion_vector_scale[0],
+        motion[1] * constants.motion_vector_scale[1],
+    ];
+    if inputs
+        .flags
+        .contains(FfxFsr2InitializationFlagBits::ENABLE_MOTION_VECTORS_JITTER_CANCELLATION)
+    {
+        motion[0] -= constants.motion_vector_jitter_cancellation[0];
+        motion[1] -= constants.motion_vector_jitter_cancellation[1];
+    }
+    motion
+}
+
+fn reconstruct_previous_depth(inputs: &PassInputs, outputs: &mut PassOutputs) {
+    let constants: Fsr2Constants = inputs.constants();
+    let inverted = inputs.inverted_depth();
+    let depth = inputs.srv("r_input_depth");
+    let color = inputs.srv("r_input_color_jittered");
+    let exposure = inputs.exposure();
+    let render_size = constants.render_size;
+
+    for (x, y) in pixels(render_size) {
+        // Dilate to the nearest depth so thin foreground edges keep their motion.
+        let mut nearest = (depth.load(x, y)[0], x, y);
+        for (dx, dy) in pixels([3, 3]) {
+            let (sx, sy) = (x + dx - 1, y + dy - 1);
+            let sample = depth.load(sx, sy)[0];
+            if nearer(sample, nearest.0, inverted) {
+                nearest = (sample, sx, sy);
+            }
+        }
+        let motion = input_motion(inputs, &constants, nearest.1, nearest.2);
+        outputs.store("rw_dilatedDepth", x, y, [nearest.0, 0.0, 0.0, 0.0]);
+        outputs.store("rw_dilated_motion_vectors", x, y, [motion[0], motion[1], 0.0, 0.0]);
+
+        let prepared = prepare(rgb(color.load(x, y)), exposure, constants.pre_exposure);
+        outputs.store("rw_lock_input_luma", x, y, [perceptual_luma(prepared), 0.0, 0.0, 0.0]);
+
+        // Scatter the depth to the bilinear footprint of the pixel in the previous frame, keeping the nearest.
+        let previous = [
+            x as f32 + 0.5 + motion[0] * render_size[0] as f32 - 0.5,
+            y as f32 + 0.5 + motion[1] * render_size[1] as f32 - 0.5,
+        ];
+        let base = [previous[0].floor() as i32, previous[1].floor() as i32];
+        if let Some((target, mip)) = outputs.uav("rw_reconstructed_previous_nearest_depth") {
+            for (dx, dy) in pixels([2, 2]) {
+                let (px, py) = (base[0] + dx, base[1] + dy);
+                if px < 0 || py < 0 || px >= render_size[0] || py >= render_size[1] {
+                    continue;
+                }
+                if nearer(nearest.0, target.load_mip(mip, px, py)[0], inverted) {
+                    target.store_mip(mip, px, py, [nearest.0, 0.0, 0.0, 0.0]);
+                }
+            }
+        }
+    }
+}
+
+fn depth_clip(inputs: &PassInputs, outputs: &mut PassOutputs) {
+    let constants: Fsr2Constants = inputs.constants();
+    let inverted = inputs.inverted_depth();
+    let previous_depth = inputs.srv("r_reconstructed_previous_nearest_depth");
+    let dilated_depth = inputs.srv("r_dilatedDepth");
+    let motion_vectors = inputs.srv("r_dilated_motion_vectors");
+    let reactive = inputs.srv("r_reactive_mask");
+    let transparency = inputs.srv("r_transparency_and_composition_mask");
+    let color = inputs.srv("r_input_color_jittered");
+    let exposure = inputs.exposure();
+    let render_size = constants.render_size;
+
+    for (x, y) in pixels(render_size) {
+        let depth = view_depth(dilated_depth.load(x, y)[0], &constants, inverted);
+        let motion = motion_vectors.load(x, y);
+        let previous = [
+            x as f32 + motion[0] * render_size[0] as f32,
+            y as f32 + motion[1] * render_size[1] as f32,
+        ];
+        let base = [previous[0].floor(), previous[1].floor()];
+        let fraction = [previous[0] - base[0], previous[1] - base[1]];
+
+        // Weighted fraction of the previous footprint that was further away than the current surface.
+        let mut weight_sum = 0.0;
+        let mut kept = 0.0;
+        for (dx, dy) in pixels([2, 2]) {
+            let weight = (if dx == 0 { 1.0 - fraction[0] } else { fraction[0] })
+                * (if dy == 0 { 1.0 - fraction[1] } else { fraction[1] });
+            let (px, py) = (base[0] as i32 + dx, base[1] as i32 + dy);
+            if weight < 0.01 || px < 0 || py < 0 || px >= render_size[0] || py >= render_size[1] {
+                continue;
+            }
+            let previous_depth = view_depth(previous_depth.load(px, py)[0], &constants, inverted);
+            let separation = DEPTH_CLIP_SEPARATION * depth.min(previous_depth);
+            let difference = depth - previous_depth;
+            kept += weight
+                * if difference > separation {
+                    separation / difference
+                } else {
+                    1.0
+                };
+            weight_sum += weight;
+        }
+        let depth_clip = if weight_sum > 0.0 {
+            1.0 - saturate(kept / weight_sum)
+        } else {
+            0.0
+        };
+
+        let mut masks = [0.0f32; 2];
+        for (dx, dy) in pixels([3, 3]) {
+            masks[0] = masks[0].max(reactive.load(x + dx - 1, y + dy - 1)[0]);
+            masks[1] = masks[1].max(transparency.load(x + dx - 1, y + dy - 1)[0]);
+        }
+        outputs.store("rw_dilated_reactive_masks", x, y, [masks[0], masks[1], 0.0, 0.0]);
+
+        let [r, g, b] = prepare(rgb(color.load(x, y)), exposure, constants.pre_exposure);
+        outputs.store("rw_prepared_input_color", x, y, [r, g, b, depth_clip]);
+    }
+}
+
+fn lock(inputs: &PassInputs, outputs: &mut PassOutputs) {
+    let constants: Fsr2Constants = inputs.constants();
+    let luma = inputs.srv("r_lock_input_luma");
+    let render_size = constants.render_size;
+    let display_size = constants.display_size;
+    let far = far_depth(inputs.inverted_depth());
+
+    for (x, y) in pixels(render_size) {
+        // A thin feature is brighter or darker than its whole neighbourhood.
+        let center = luma.load(x, y)[0];
+        let (mut min, mut max) = (f32::MAX, f32::MIN);
+        for (dx, dy) in pixels([3, 3]).filter(|&offset| offset != (1, 1)) {
+            let sample = luma.load(x + dx - 1, y + dy - 1)[0];
+            min = min.min(sample);
+            max = max.max(sample);
+        }
+        if center > max + LOCK_CONTRAST_THRESHOLD || center < min - LOCK_CONTRAST_THRESHOLD {
+            let hr = [
+                ((x as f32 + 0.5 - constants.jitter_offset[0]) / render_size[0] as f32 * display_size[0] as f32).floor()
+                    as i32,
+                ((y as f32 + 0.5 - constants.jitter_offset[1]) / render_size[1] as f32 * display_size[1] as f32).floor()
+                    as i32,
+            ];
+            outputs.store("rw_new_locks", hr[0], hr[1], [1.0, 0.0, 0.0, 0.0]);
+        }
+        // Reset for the next frame's reconstruction.
+        outputs.store("rw_reconstructed_previous_nearest_depth", x, y, [far, 0.0, 0.0, 0.0]);
+    }
+}
+
+fn accumulate(inputs: &PassInputs, outputs: &mut PassOutputs) {
+    let constants: Fsr2Constants = inputs.constants();
+    let exposure = inputs.exposure();
+    let masks = inputs.srv("r_dilated_reactive_masks");
+    let motion_vectors = inputs.srv("r_dilated_motion_vectors");
+    let history = inputs.srv("r_internal_upscaled_color");
+    let lock_status = inputs.srv("r_lock_status");
+    let prepared = inputs.srv("r_prepared_input_color");
+    let new_locks = inputs.srv("r_new_locks");
+    let reset = constants.frame_index == 0;
+    let display_size = constants.display_size;
+    let render_size = constants.render_size;
+    let downscale = [
+        render_size[0] as f32 / display_size[0].max(1) as f32,
+        render_size[1] as f32 / display_size[1].max(1) as f32,
+    ];
+    let lock_decay = 1.0 / (constants.jitter_phase_count.max(1.0) * 2.0);
+
+    for (x, y) in pixels(display_size) {
+        let output_position = [x as f32 + 0.5, y as f32 + 0.5];
+        let source_position = [output_position[0] * downscale[0], output_position[1] * downscale[1]];
+        let source = [source_position[0].floor() as i32, source_position[1].floor() as i32];
+
+        // Lanczos upsampling of the jittered samples around the output pixel, and the neighbourhood bounds
+        // used to rectify the history.
+        let mut sum = [0.0; 3];
+        let mut weight_sum = 0.0;
+        let mut sample_weight = 0.0f32;
+        let mut box_min = [f32::MAX; 3];
+        let mut box_max = [f32::MIN; 3];
+        for (dx, dy) in pixels([4, 4]) {
+            let (sx, sy) = (source[0] + dx - 1, source[1] + dy - 1);
+            let sample_position = [
+                (sx as f32 + 0.5 - constants.jitter_offset[0]).clamp(0.0, render_size[0] as f32),
+                (sy as f32 + 0.5 - constants.jitter_offset[1]).clamp(0.0, render_size[1] as f32),
+            ];
+            let offset = [
+                sample_position[0] - source_position[0],
+                sample_position[1] - source_position[1],
+            ];
+            // Measured in display pixels, so each frame only contributes where its samples landed.
+            let distance = (offset[0] * offset[0] / (downscale[0] * downscale[0])
+                + offset[1] * offset[1] / (downscale[1] * downscale[1]))
+                .sqrt();
+            let weight = lanczos2(distance / KERNEL_WIDTH);
+            let color = rgb(prepared.load(sx, sy));
+            for i in 0..3 {
+                sum[i] += color[i] * weight;
+            }
+            weight_sum += weight;
+            sample_weight = sample_weight.max(weight);
+            if dx <= 2 && dy <= 2 {
+                let ycocg = to_ycocg(color);
+                for i in 0..3 {
+                    box_min[i] = box_min[i].min(ycocg[i]);
+                    box_max[i] = box_max[i].max(ycocg[i]);
+                }
+            }
+        }
+        let upsampled = if weight_sum.abs() > 1e-5 {
+            let ycocg = to_ycocg(sum.map(|channel| channel / weight_sum));
+            from_ycocg(std::array::from_fn(|i| ycocg[i].clamp(box_min[i], box_max[i])))
+        } else {
+            rgb(prepared.load(source[0], source[1]))
+        };
+        let sample_weight = saturate(sample_weight);
+
+        let depth_clip = prepared.load(source[0], source[1])[3];
+        let [reactive, transparency, ..] = masks.load(source[0], source[1]);
+        let motion = sample_at(motion_vectors, source_position);
+        let previous = [
+            output_position[0] + motion[0] * display_size[0] as f32,
+            output_position[1] + motion[1] * display_size[1] as f32,
+        ];
+        let on_screen = previous[0] >= 0.0
+            && previous[1] >= 0.0
+            && previous[0] <= display_size[0] as f32
+            && previous[1] <= display_size[1] as f32;
+
+        let mut lock = if reset || !on_screen {
+            [0.0, 0.0]
+        } else {
+            let lock = lock_status.load(previous[0] as i32, previous[1] as i32);
+            [lock[0], lock[1]]
+        };
+
+        let (color, weight) = if reset || !on_screen {
+            (upsampled, sample_weight)
+        } else {
+            let history = sample_at(history, previous);
+            let history_color = rgb(history);
+            let history_luma = perceptual_luma(history_color);
+
+            if new_locks.load(x, y)[0] > 0.0 {
+                lock = [1.0, history_luma];
+            } else if lock[0] > 0.0 {
+                let luma_change = (history_luma - lock[1]).abs() / lock[1].max(history_luma).max(1e-3);
+                let disoccluded = depth_clip.max(reactive).max(transparency) > 0.5;
+                lock[0] = if disoccluded || luma_change > LOCK_LUMA_TOLERANCE {
+                    0.0
+                } else {
+                    (lock[0] - lock_decay).max(0.0)
+                };
+            }
+
+            let ycocg = to_ycocg(history_color);
+            let clamped = from_ycocg(std::array::from_fn(|i| ycocg[i].clamp(box_min[i], box_max[i])));
+            let rectified = lerp3(clamped, history_color, lock[0]);
+
+            let max_weight = MAX_ACCUMULATION_WEIGHT + (1.0 - MAX_ACCUMULATION_WEIGHT) * saturate(reactive);
+            let history_weight =
+                (history[3] * (1.0 - depth_clip) * (1.0 - 0.5 * saturate(transparency))).clamp(0.0, max_weight);
+            let alpha = if history_weight + sample_weight > 0.0 {
+                sample_weight / (history_weight + sample_weight)
+            } else {
+                1.0
+            };
+            (
+                lerp3(rectified, upsampled, alpha),
+                (history_weight + sample_weight).min(max_weight),
+            )
+        };
+
+        outputs.store(
+            "rw_internal_upscaled_color",
+            x,
+            y,
+            [color[0], color[1], color[2], weight],
+        );
+        outputs.store("rw_lock_status", x, y, [lock[0], lock[1], 0.0, 0.0]);
+        outputs.store("rw_new_locks", x, y, [0.0; 4]);
+        let [r, g, b] = unprepare(color, exposure, constants.pre_exposure);
+        outputs.store("rw_upscaled_output", x, y, [r, g, b, 1.0]);
+    }
+}
+
+fn rcas(inputs: &PassInputs, outputs: &mut PassOutputs) {
+    let constants: Fsr2Constants = inputs.constants();
+    let rcas_constants: Fsr2RcasConstants = inputs.constants();
+    let attenuation = f32::from_bits(rcas_constants.rcas_config[0]);
+    let input = inputs.srv("r_rcas_input");
+    let exposure = inputs.exposure();
+    let load = |x, y| unprepare(rgb(input.load(x, y)), exposure, constants.pre_exposure);
+
+    for (x, y) in pixels(constants.display_size) {
+        let neighbours = [load(x, y - 1), load(x - 1, y), load(x + 1, y), load(x, y + 1)];
+        let [r, g, b] = rcas_pixel(load(x, y), neighbours, attenuation, true);
+        outputs.store("rw_upscaled_output", x, y, [r, g, b, 1.0]);
+    }
+}
+
+/// `ComputeAutoExposureFromLavg` of the FSR2 shaders.
+fn auto_exposure(average_luminance: f32) -> f32 {
+    let iso100 = (average_luminance * 100.0 / 12.5).log2();
+    let max_luminance = 78.0 / (0.65 * 100.0) * iso100.exp2();
+    1.0 / max_luminance
+}
+
+fn luminance_pyramid(inputs: &PassInputs, outputs: &mut PassOutputs) {
+    let constants: Fsr2Constants = inputs.constants();
+    let color = inputs.srv("r_input_color_jittered");
+    let render_size = constants.render_size;
+    let log_luma = |x, y| luma(rgb(color.load(x, y))).max(1e-8).ln();
+
+    let count = (render_size[0].max(0) * render_size[1].max(0)).max(1) as f32;
+    let average = pixels(render_size).map(|(x, y)| log_luma(x, y)).sum::<f32>() / count;
+    outputs.store(
+        "rw_auto_exposure",
+        0,
+        0,
+        [auto_exposure(average.exp()), average, 0.0, 0.0],
+    );
+    outputs.store("rw_spd_global_atomic", 0, 0, [0.0; 4]);
+
+    for name in ["rw_img_mip_shading_change", "rw_img_mip_5"] {
+        let Some((target, mip)) = outputs.uav(name) else {
+            continue;
+        };
+        let (width, height) = target.mip_size(mip);
+        for (x, y) in pixels([width as i32, height as i32]) {
+            let x0 = x * render_size[0] / width as i32;
+            let y0 = y * render_size[1] / height as i32;
+            let x1 = ((x + 1) * render_size[0] / width as i32).max(x0 + 1);
+            let y1 = ((y + 1) * render_size[1] / height as i32).max(y0 + 1);
+            let block = pixels([x1 - x0, y1 - y0]);
+            let average =
+                block.map(|(dx, dy)| log_luma(x0 + dx, y0 + dy)).sum::<f32>() / ((x1 - x0) * (y1 - y0)) as f32;
+            target.store_mip(mip, x, y, [average, 0.0, 0.0, 0.0]);
+        }
+    }
+}
+
+/// Flags of `Fsr2GenerateReactiveConstants::flags`, `FFX_FSR2_AUTOREACTIVEFLAGS_*`.
+const AUTOREACTIVE_APPLY_TONEMAP: u32 = 1 << 0;
+const AUTOREACTIVE_APPLY_INVERSETONEMAP: u32 = 1 << 1;
+const AUTOREACTIVE_APPLY_THRESHOLD: u32 = 1 << 2;
+const AUTOREACTIVE_USE_COMPONENTS_MAX: u32 = 1 << 3;
+
+fn tonemap(rgb: [f32; 3]) -> [f32; 3] {
+    let scale = 1.0 / (max3(rgb).max(0.0) + 1.0);
+    rgb.map(|channel| channel * scale)
+}
+
+fn inverse_tonemap(rgb: [f32; 3]) -> [f32; 3] {
+    let scale = 1.0 / (1.0 - max3(rgb)).max(1.0 / 65504.0);
+    rgb.map(|channel| channel * scale)
+}
+
+fn generate_reactive(inputs: &PassInputs, outputs: &mut PassOutputs) {
+    let constants: Fsr2GenerateReactiveConstants = inputs.constants();
+    let opaque = inputs.srv("r_input_opaque_only");
+    let color = inputs.srv("r_input_color_jittered");
+    let Some((target, mip)) = outputs.uav("rw_output_autoreactive") else {
+        return;
+    };
+    let (width, height) = target.mip_size(mip);
+
+    for (x, y) in pixels([width as i32, height as i32]) {
+        let mut pre = rgb(opaque.load(x, y));
+        let mut post = rgb(color.load(x, y));
+        if constants.flags & AUTOREACTIVE_APPLY_TONEMAP != 0 {
+            pre = tonemap(pre);
+            post = tonemap(post);
+        }
+        if constants.flags & AUTOREACTIVE_APPLY_INVERSETONEMAP != 0 {
+            pre = inverse_tonemap(pre);
+            post = inverse_tonemap(post);
+        }
+        let delta: [f32; 3] = std::array::from_fn(|i| (post[i] - pre[i]).abs());
+        let mut reactive = if constants.flags & AUTOREACTIVE_USE_COMPONENTS_MAX != 0 {
+            max3(delta)
+        } else {
+            delta.iter().map(|channel| channel * channel).sum::<f32>().sqrt()
+        } * constants.scale;
+        if constants.flags & AUTOREACTIVE_APPLY_THRESHOLD != 0 {
+            reactive = if reactive < constants.threshold {
+                0.0
+            } else {
+                constants.binary_value
+            };
+        }
+        target.store_mip(mip, x, y, [reactive, 0.0, 0.0, 0.0]);
+    }
+}
+
+fn tcr_autogenerate(inputs: &PassInputs, outputs: &mut PassOutputs) {
+    let constants: Fsr2Constants = inputs.constants();
+    let autogen: Fsr2GenerateReactiveConstants2 = inputs.constants();
+    let opaque = inputs.srv("r_input_opaque_only");
+    let color = inputs.srv("r_input_color_jittered");
+    let previous_pre_alpha = inputs.srv("r_input_prev_color_pre_alpha");
+    let previous_post_alpha = inputs.srv("r_input_prev_color_post_alpha");
+    let reactive_mask = inputs.srv("r_reactive_mask");
+    let transparency_mask = inputs.srv("r_transparency_and_composition_mask");
+    let render_size = constants.render_size;
+    let reset = constants.frame_index == 0;
+
+    for (x, y) in pixels(render_size) {
+        let pre = tonemap(rgb(opaque.load(x, y)));
+        let post = tonemap(rgb(color.load(x, y)));
+        let delta = max3(std::array::from_fn(|i| (post[i] - pre[i]).abs()));
+
+        let mut reactive = reactive_mask.load(x, y)[0];
+        let mut transparency = transparency_mask.load(x, y)[0];
+        if delta > autogen.auto_tc_threshold {
+            // Translucency changed the opaque color: reactive where the result changed over time more than the
+            // scene behind it did, composition everywhere else.
+            let motion = input_motion(inputs, &constants, x, y);
+            let previous = [
+                x as f32 + 0.5 + motion[0] * render_size[0] as f32,
+                y as f32 + 0.5 + motion[1] * render_size[1] as f32,
+            ];
+            let (pre_change, post_change) = if reset {
+                (0.0, 0.0)
+            } else {
+                let previous_pre = tonemap(rgb(sample_at(previous_pre_alpha, previous)));
+                let previous_post = tonemap(rgb(sample_at(previous_post_alpha, previous)));
+                (
+                    max3(std::array::from_fn(|i| (pre[i] - previous_pre[i]).abs())),
+                    max3(std::array::from_fn(|i| (post[i] - previous_post[i]).abs())),
+                )
+            };
+            let temporal = saturate(post_change - pre_change);
+            reactive = reactive.max((temporal * autogen.auto_reactive_scale).min(autogen.auto_reactive_max));
+            transparency = transparency.max(saturate(delta * autogen.auto_tc_scale));
+        }
+
+        outputs.store("rw_output_autoreactive", x, y, [reactive, 0.0, 0.0, 0.0]);
+        outputs.store("rw_output_autocomposition", x, y, [transparency, 0.0, 0.0, 0.0]);
+        let opaque = opaque.load(x, y);
+        let color = color.load(x, y);
+        outputs.store("rw_output_prev_color_pre_alpha", x, y, opaque);
+        outputs.store("rw_output_prev_color_post_alpha", x, y, color);
+    }
+}
@@ -1,9 +1,16 @@
+mod cpu;
+
+pub use cpu::*;
+
 #[cfg(feature = "vk")]
 mod vk;
 
 #[cfg(feature = "vk")]
 pub use vk::*;
 
+#[cfg(feature = "ash")]
+mod ash_interop;
+
 #[cfg(feature = "dx12")]
 mod dx12;
 
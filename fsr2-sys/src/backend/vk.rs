@@ -1,10 +1,22 @@
 use crate::{
     FfxCommandList, FfxDevice, FfxErrorCode, FfxFsr2Context, FfxFsr2Interface, FfxResource,
-    FfxResourceState,
+    FfxResourceState, Fsr2Resource, ResourceName,
 };
 use std::ffi::{c_char, c_void};
 use widestring::WideChar as wchar_t;
 
+mod barrier;
+mod device;
+mod format;
+mod loader;
+mod properties;
+
+pub use barrier::*;
+pub use device::*;
+pub use format::*;
+pub use loader::*;
+pub use properties::*;
+
 #[derive(Copy, Clone)]
 #[repr(transparent)]
 pub struct VkPhysicalDevice(u64);
@@ -13,6 +25,10 @@ impl VkPhysicalDevice {
     pub fn from_raw(value: u64) -> Self {
         Self(value)
     }
+
+    pub fn as_raw(self) -> u64 {
+        self.0
+    }
 }
 
 #[derive(Copy, Clone)]
@@ -23,6 +39,10 @@ impl VkInstance {
     pub fn from_raw(value: u64) -> Self {
         Self(value)
     }
+
+    pub fn as_raw(self) -> u64 {
+        self.0
+    }
 }
 
 #[derive(Copy, Clone)]
@@ -33,6 +53,10 @@ impl VkDevice {
     pub fn from_raw(value: u64) -> Self {
         Self(value)
     }
+
+    pub fn as_raw(self) -> u64 {
+        self.0
+    }
 }
 
 #[derive(Copy, Clone)]
@@ -43,6 +67,10 @@ impl VkCommandBuffer {
     pub fn from_raw(value: u64) -> Self {
         Self(value)
     }
+
+    pub fn as_raw(self) -> u64 {
+        self.0
+    }
 }
 
 #[derive(Copy, Clone)]
@@ -53,6 +81,10 @@ impl VkImage {
     pub fn from_raw(value: u64) -> Self {
         Self(value)
     }
+
+    pub fn as_raw(self) -> u64 {
+        self.0
+    }
 }
 
 #[derive(Copy, Clone)]
@@ -63,6 +95,10 @@ impl VkImageView {
     pub fn from_raw(value: u64) -> Self {
         Self(value)
     }
+
+    pub fn as_raw(self) -> u64 {
+        self.0
+    }
 }
 
 #[derive(Copy, Clone)]
@@ -73,10 +109,98 @@ impl VkBuffer {
     pub fn from_raw(value: u64) -> Self {
         Self(value)
     }
+
+    pub fn as_raw(self) -> u64 {
+        self.0
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[repr(transparent)]
+pub struct VkFormat(i32);
+
+impl VkFormat {
+    pub const UNDEFINED: Self = Self(0);
+    pub const R8_UNORM: Self = Self(9);
+    pub const R8_SNORM: Self = Self(10);
+    pub const R8_UINT: Self = Self(13);
+    pub const R8_SRGB: Self = Self(15);
+    pub const R8G8_UNORM: Self = Self(16);
+    pub const R8G8_SNORM: Self = Self(17);
+    pub const R8G8_UINT: Self = Self(20);
+    pub const R8G8_SRGB: Self = Self(22);
+    pub const R8G8B8A8_UNORM: Self = Self(37);
+    pub const R8G8B8A8_SNORM: Self = Self(38);
+    pub const R8G8B8A8_UINT: Self = Self(41);
+    pub const R8G8B8A8_SRGB: Self = Self(43);
+    pub const B8G8R8A8_UNORM: Self = Self(44);
+    pub const B8G8R8A8_SRGB: Self = Self(50);
+    pub const A2B10G10R10_UNORM_PACK32: Self = Self(64);
+    pub const R16_UNORM: Self = Self(70);
+    pub const R16_SNORM: Self = Self(71);
+    pub const R16_UINT: Self = Self(74);
+    pub const R16_SFLOAT: Self = Self(76);
+    pub const R16G16_UNORM: Self = Self(77);
+    pub const R16G16_SNORM: Self = Self(78);
+    pub const R16G16_UINT: Self = Self(81);
+    pub const R16G16_SFLOAT: Self = Self(83);
+    pub const R16G16B16A16_UNORM: Self = Self(91);
+    pub const R16G16B16A16_SNORM: Self = Self(92);
+    pub const R16G16B16A16_UINT: Self = Self(95);
+    pub const R16G16B16A16_SFLOAT: Self = Self(97);
+    pub const R32_UINT: Self = Self(98);
+    pub const R32_SFLOAT: Self = Self(100);
+    pub const R32G32_UINT: Self = Self(101);
+    pub const R32G32_SFLOAT: Self = Self(103);
+    pub const R32G32B32A32_UINT: Self = Self(107);
+    pub const R32G32B32A32_SFLOAT: Self = Self(109);
+    pub const B10G11R11_UFLOAT_PACK32: Self = Self(122);
+    pub const D16_UNORM: Self = Self(124);
+    pub const X8_D24_UNORM_PACK32: Self = Self(125);
+    pub const D32_SFLOAT: Self = Self(126);
+    pub const D16_UNORM_S8_UINT: Self = Self(128);
+    pub const D24_UNORM_S8_UINT: Self = Self(129);
+    pub const D32_SFLOAT_S8_UINT: Self = Self(130);
+
+    pub fn from_raw(value: i32) -> Self {
+        Self(value)
+    }
+
+    pub fn as_raw(self) -> i32 {
+        self.0
+    }
 }
 
-pub type VkFormat = i32;
-pub type VkImageLayout = i32;
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[repr(transparent)]
+pub struct VkImageLayout(i32);
+
+impl VkImageLayout {
+    pub const UNDEFINED: Self = Self(0);
+    pub const GENERAL: Self = Self(1);
+    pub const COLOR_ATTACHMENT_OPTIMAL: Self = Self(2);
+    pub const DEPTH_STENCIL_ATTACHMENT_OPTIMAL: Self = Self(3);
+    pub const DEPTH_STENCIL_READ_ONLY_OPTIMAL: Self = Self(4);
+    pub const SHADER_READ_ONLY_OPTIMAL: Self = Self(5);
+    pub const TRANSFER_SRC_OPTIMAL: Self = Self(6);
+    pub const TRANSFER_DST_OPTIMAL: Self = Self(7);
+    pub const PREINITIALIZED: Self = Self(8);
+    pub const PRESENT_SRC_KHR: Self = Self(1000001002);
+    pub const READ_ONLY_OPTIMAL: Self = Self(1000314000);
+    pub const ATTACHMENT_OPTIMAL: Self = Self(1000314001);
+
+    pub fn from_raw(value: i32) -> Self {
+        Self(value)
+    }
+
+    pub fn as_raw(self) -> i32 {
+        self.0
+    }
+}
+
+pub type VkResult = i32;
+pub const VK_SUCCESS: VkResult = 0;
+pub const VK_INCOMPLETE: VkResult = 5;
 pub type VkFlags = u32;
 pub type VkMemoryPropertyFlags = VkFlags;
 pub type VkMemoryHeapFlags = VkFlags;
@@ -85,11 +209,11 @@ pub type VkPhysicalDeviceType = i32;
 
 pub const VK_MAX_EXTENSION_NAME_SIZE: usize = 256;
 pub const VK_MAX_MEMORY_TYPES: usize = 32;
-pub const VK_MAX_MEMORY_HEAPS: usize = 32;
+pub const VK_MAX_MEMORY_HEAPS: usize = 16;
 pub const VK_MAX_PHYSICAL_DEVICE_NAME_SIZE: usize = 256;
 pub const VK_UUID_SIZE: usize = 16;
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug)]
 #[repr(C)]
 pub struct VkExtensionProperties {
     pub extension_name: [c_char; VK_MAX_EXTENSION_NAME_SIZE],
@@ -126,7 +250,7 @@ pub type VkEnumerateDeviceExtensionPropertiesFunc = unsafe extern "system" fn(
     *const c_char,
     *mut u32,
     *mut VkExtensionProperties,
-);
+) -> VkResult;
 
 pub type VkGetDeviceProcAddrFunc =
     unsafe extern "system" fn(VkDevice, *const c_char) -> VoidFunctionPtr;
@@ -134,20 +258,14 @@ pub type VkGetDeviceProcAddrFunc =
 pub type VkGetPhysicalDeviceMemoryPropertiesFunc =
     unsafe extern "system" fn(VkPhysicalDevice, *mut VkPhysicalDeviceMemoryProperties);
 
-/// fn(VkPhysicalDevice, *mut VkPhysicalDeviceProperties)
-/// This type is not fully spelled out to avoid depending on ash or another vulkan loader for the vulkan types
 pub type VkGetPhysicalDevicePropertiesFunc =
-    unsafe extern "system" fn(VkPhysicalDevice, *mut c_void);
+    unsafe extern "system" fn(VkPhysicalDevice, *mut VkPhysicalDeviceProperties);
 
-/// fn(VkPhysicalDevice, *mut VkPhysicalDeviceProperties2)
-/// This type is not fully spelled out to avoid depending on ash or another vulkan loader for the vulkan types
 pub type VkGetPhysicalDeviceProperties2Func =
-    unsafe extern "system" fn(VkPhysicalDevice, *mut c_void);
+    unsafe extern "system" fn(VkPhysicalDevice, *mut VkPhysicalDeviceProperties2);
 
-/// fn(VkPhysicalDevice, *mut VkPhysicalDeviceFeatures2)
-/// This type is not fully spelled out to avoid depending on ash or another vulkan loader for the vulkan types
 pub type VkGetPhysicalDeviceFeatures2Func =
-    unsafe extern "system" fn(VkPhysicalDevice, *mut c_void);
+    unsafe extern "system" fn(VkPhysicalDevice, *mut VkPhysicalDeviceFeatures2);
 
 #[derive(Copy, Clone)]
 #[repr(C)]
@@ -205,3 +323,121 @@ extern "C" {
 
     pub fn ffxGetVkImageLayout(context: *mut FfxFsr2Context, id: u32) -> VkImageLayout;
 }
+
+/// Same as [`ffxGetTextureResourceVK`], but takes the name as a `&str`.
+///
+/// # Safety
+/// `context` must point to a valid FSR2 context, and `image` and `view` must be valid Vulkan handles.
+#[allow(clippy::too_many_arguments)]
+pub unsafe fn ffx_get_texture_resource_vk(
+    context: *mut FfxFsr2Context,
+    image: VkImage,
+    view: VkImageView,
+    width: u32,
+    height: u32,
+    format: VkFormat,
+    name: Option<&str>,
+    state: FfxResourceState,
+) -> FfxResource {
+    let name = name.map(ResourceName::new);
+    let mut resource = ffxGetTextureResourceVK(
+        context,
+        image,
+        view,
+        width,
+        height,
+        format,
+        name.as_ref().map_or(std::ptr::null(), ResourceName::as_ptr),
+        state,
+    );
+    // FSR2 only copies the name in debug builds
+    if let Some(name) = name {
+        resource.name = name.into_raw();
+    }
+    resource
+}
+
+/// Same as [`ffxGetBufferResourceVK`], but takes the name as a `&str`.
+///
+/// # Safety
+/// `context` must point to a valid FSR2 context, and `buffer` must be a valid Vulkan handle.
+pub unsafe fn ffx_get_buffer_resource_vk(
+    context: *mut FfxFsr2Context,
+    buffer: VkBuffer,
+    size: u32,
+    name: Option<&str>,
+    state: FfxResourceState,
+) -> FfxResource {
+    let name = name.map(ResourceName::new);
+    let mut resource = ffxGetBufferResourceVK(
+        context,
+        buffer,
+        size,
+        name.as_ref().map_or(std::ptr::null(), ResourceName::as_ptr),
+        state,
+    );
+    if let Some(name) = name {
+        resource.name = name.into_raw();
+    }
+    resource
+}
+
+#[derive(Copy, Clone)]
+pub struct VkTextureDesc<'a> {
+    pub image: VkImage,
+    pub view: VkImageView,
+    pub width: u32,
+    pub height: u32,
+    pub format: VkFormat,
+    pub name: Option<&'a str>,
+    /// State the image is in when FSR2 accesses it. FSR2 expects the resource back in this state afterwards.
+    pub state: FfxResourceState,
+}
+
+#[derive(Copy, Clone)]
+pub struct VkBufferDesc<'a> {
+    pub buffer: VkBuffer,
+    pub size: u32,
+    pub name: Option<&'a str>,
+    pub state: FfxResourceState,
+}
+
+/// Wraps a Vulkan image for use with `context`. The handles are only used once the resource is passed to a dispatch.
+///
+/// # Safety
+/// `ffxGetTextureResourceVK` takes a mutable context pointer, which is derived from the shared `context` here. The
+/// linked FSR2 must not write through it, which holds for the vendored FSR2 2.2 as it ignores the context.
+pub unsafe fn get_texture_resource_vk<'ctx>(
+    context: &'ctx FfxFsr2Context,
+    desc: &VkTextureDesc,
+) -> Fsr2Resource<'ctx> {
+    let resource = ffx_get_texture_resource_vk(
+        context as *const FfxFsr2Context as *mut FfxFsr2Context,
+        desc.image,
+        desc.view,
+        desc.width,
+        desc.height,
+        desc.format,
+        desc.name,
+        desc.state,
+    );
+    Fsr2Resource::from_raw(resource)
+}
+
+/// Wraps a Vulkan buffer for use with `context`. The handle is only used once the resource is passed to a dispatch.
+///
+/// # Safety
+/// See [`get_texture_resource_vk`], the same holds for `ffxGetBufferResourceVK`.
+pub unsafe fn get_buffer_resource_vk<'ctx>(
+    context: &'ctx FfxFsr2Context,
+    desc: &VkBufferDesc,
+) -> Fsr2Resource<'ctx> {
+    let resource = ffx_get_buffer_resource_vk(
+        context as *const FfxFsr2Context as *mut FfxFsr2Context,
+        desc.buffer,
+        desc.size,
+        desc.name,
+        desc.state,
+    );
+    Fsr2Resource::from_raw(resource)
+}
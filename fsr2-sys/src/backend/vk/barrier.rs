@@ -0,0 +1,151 @@
+use super::*;
+use bitflags::bitflags;
+
+bitflags! {
+    #[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+    #[repr(transparent)]
+    pub struct VkAccessFlags2: u64 {
+        const NONE = 0;
+        const SHADER_READ = 0x0000_0020;
+        const SHADER_WRITE = 0x0000_0040;
+        const TRANSFER_READ = 0x0000_0800;
+        const TRANSFER_WRITE = 0x0000_1000;
+        const MEMORY_READ = 0x0000_8000;
+        const MEMORY_WRITE = 0x0001_0000;
+        const SHADER_SAMPLED_READ = 0x1_0000_0000;
+        const SHADER_STORAGE_READ = 0x2_0000_0000;
+        const SHADER_STORAGE_WRITE = 0x4_0000_0000;
+    }
+}
+
+bitflags! {
+    #[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+    #[repr(transparent)]
+    pub struct VkPipelineStageFlags2: u64 {
+        const NONE = 0;
+        const TOP_OF_PIPE = 0x0000_0001;
+        const COMPUTE_SHADER = 0x0000_0800;
+        const TRANSFER = 0x0000_1000;
+        const BOTTOM_OF_PIPE = 0x0000_2000;
+        const ALL_COMMANDS = 0x0001_0000;
+        const COPY = 0x1_0000_0000;
+    }
+}
+
+/// The synchronization scope of a resource in a given [`FfxResourceState`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct VkResourceStateScope {
+    /// Only meaningful for images.
+    pub layout: VkImageLayout,
+    pub access: VkAccessFlags2,
+    pub stage: VkPipelineStageFlags2,
+}
+
+impl FfxResourceState {
+    /// The layout FSR2's Vulkan backend assumes an image in this state is in. Images passed to
+    /// `ffxGetTextureResourceVK` must be in this layout when `ffxFsr2ContextDispatch` is recorded.
+    pub fn vk_image_layout(self) -> VkImageLayout {
+        match self {
+            FfxResourceState::GENERIC_READ | FfxResourceState::COMPUTE_READ => VkImageLayout::SHADER_READ_ONLY_OPTIMAL,
+            FfxResourceState::UNORDERED_ACCESS => VkImageLayout::GENERAL,
+            FfxResourceState::COPY_SRC => VkImageLayout::TRANSFER_SRC_OPTIMAL,
+            FfxResourceState::COPY_DEST => VkImageLayout::TRANSFER_DST_OPTIMAL,
+            _ => VkImageLayout::GENERAL,
+        }
+    }
+
+    pub fn vk_access_flags2(self) -> VkAccessFlags2 {
+        match self {
+            FfxResourceState::GENERIC_READ => VkAccessFlags2::SHADER_READ | VkAccessFlags2::TRANSFER_READ,
+            FfxResourceState::COMPUTE_READ => VkAccessFlags2::SHADER_READ,
+            FfxResourceState::COPY_SRC => VkAccessFlags2::TRANSFER_READ,
+            FfxResourceState::COPY_DEST => VkAccessFlags2::TRANSFER_WRITE,
+            _ => VkAccessFlags2::SHADER_READ | VkAccessFlags2::SHADER_WRITE,
+        }
+    }
+
+    pub fn vk_pipeline_stage_flags2(self) -> VkPipelineStageFlags2 {
+        match self {
+            FfxResourceState::COPY_SRC | FfxResourceState::COPY_DEST => VkPipelineStageFlags2::TRANSFER,
+            // Generic reads include copies, and TRANSFER_READ access is only valid with a transfer stage.
+            FfxResourceState::GENERIC_READ => VkPipelineStageFlags2::COMPUTE_SHADER | VkPipelineStageFlags2::TRANSFER,
+            _ => VkPipelineStageFlags2::COMPUTE_SHADER,
+        }
+    }
+
+    /// Layout, access and stage to use as the destination of the barrier before `ffxFsr2ContextDispatch`,
+    /// and as the source of the barrier after it.
+    pub fn vk_scope(self) -> VkResourceStateScope {
+        VkResourceStateScope {
+            layout: self.vk_image_layout(),
+            access: self.vk_access_flags2(),
+            stage: self.vk_pipeline_stage_flags2(),
+        }
+    }
+}
+
+impl VkImageLayout {
+    /// The state to pass to `ffxGetTextureResourceVK` for an image in this layout, `None` if FSR2 can
+    /// not consume images in this layout.
+    pub fn ffx_resource_state(self) -> Option<FfxResourceState> {
+        match self {
+            VkImageLayout::SHADER_READ_ONLY_OPTIMAL => Some(FfxResourceState::COMPUTE_READ),
+            VkImageLayout::GENERAL => Some(FfxResourceState::UNORDERED_ACCESS),
+            VkImageLayout::TRANSFER_SRC_OPTIMAL => Some(FfxResourceState::COPY_SRC),
+            VkImageLayout::TRANSFER_DST_OPTIMAL => Some(FfxResourceState::COPY_DEST),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STATES: [FfxResourceState; 5] = [
+        FfxResourceState::UNORDERED_ACCESS,
+        FfxResourceState::COMPUTE_READ,
+        FfxResourceState::COPY_SRC,
+        FfxResourceState::COPY_DEST,
+        FfxResourceState::GENERIC_READ,
+    ];
+
+    #[test]
+    fn layout_round_trip() {
+        for state in STATES {
+            let layout = state.vk_image_layout();
+            let back = layout.ffx_resource_state().unwrap();
+            assert_eq!(back.vk_image_layout(), layout, "{state:?}");
+        }
+        assert_eq!(VkImageLayout::UNDEFINED.ffx_resource_state(), None);
+    }
+
+    #[test]
+    fn scopes() {
+        let uav = FfxResourceState::UNORDERED_ACCESS.vk_scope();
+        assert_eq!(uav.layout, VkImageLayout::GENERAL);
+        assert!(uav.access.contains(VkAccessFlags2::SHADER_WRITE));
+        assert_eq!(uav.stage, VkPipelineStageFlags2::COMPUTE_SHADER);
+
+        let copy = FfxResourceState::COPY_DEST.vk_scope();
+        assert_eq!(copy.layout, VkImageLayout::TRANSFER_DST_OPTIMAL);
+        assert_eq!(copy.access, VkAccessFlags2::TRANSFER_WRITE);
+        assert_eq!(copy.stage, VkPipelineStageFlags2::TRANSFER);
+
+        let read = FfxResourceState::GENERIC_READ.vk_scope();
+        assert_eq!(read.layout, VkImageLayout::SHADER_READ_ONLY_OPTIMAL);
+        assert!(read.access.contains(VkAccessFlags2::TRANSFER_READ));
+        assert_eq!(read.stage, VkPipelineStageFlags2::COMPUTE_SHADER | VkPipelineStageFlags2::TRANSFER);
+
+        // Every access needs a stage that can perform it.
+        for state in STATES {
+            let scope = state.vk_scope();
+            if scope.access.intersects(VkAccessFlags2::TRANSFER_READ | VkAccessFlags2::TRANSFER_WRITE) {
+                assert!(scope.stage.contains(VkPipelineStageFlags2::TRANSFER), "{state:?}");
+            }
+            if scope.access.intersects(VkAccessFlags2::SHADER_READ | VkAccessFlags2::SHADER_WRITE) {
+                assert!(scope.stage.contains(VkPipelineStageFlags2::COMPUTE_SHADER), "{state:?}");
+            }
+        }
+    }
+}
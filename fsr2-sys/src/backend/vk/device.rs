@@ -0,0 +1,424 @@
+use super::*;
+use std::ffi::CStr;
+
+pub const VK_KHR_SHADER_FLOAT16_INT8_EXTENSION_NAME: &CStr = c"VK_KHR_shader_float16_int8";
+pub const VK_EXT_SUBGROUP_SIZE_CONTROL_EXTENSION_NAME: &CStr = c"VK_EXT_subgroup_size_control";
+
+impl VkExtensionProperties {
+    /// Panics if `name` does not fit `VK_MAX_EXTENSION_NAME_SIZE`.
+    pub fn new(name: &CStr, spec_version: u32) -> Self {
+        let bytes = name.to_bytes_with_nul();
+        assert!(bytes.len() <= VK_MAX_EXTENSION_NAME_SIZE, "extension name too long");
+        let mut extension_name = [0; VK_MAX_EXTENSION_NAME_SIZE];
+        for (dst, src) in extension_name.iter_mut().zip(bytes) {
+            *dst = *src as c_char;
+        }
+        Self {
+            extension_name,
+            spec_version,
+        }
+    }
+
+    /// `None` if the name is not NUL terminated.
+    pub fn name(&self) -> Option<&CStr> {
+        let bytes: &[u8] = bytemuck::cast_slice(&self.extension_name);
+        CStr::from_bytes_until_nul(bytes).ok()
+    }
+}
+
+/// The extensions and features a physical device supports, as far as FSR2 cares about them.
+#[derive(Clone, Debug, Default)]
+pub struct VkDeviceDescription {
+    pub extensions: Vec<VkExtensionProperties>,
+    pub features: VkPhysicalDeviceFeatures,
+    /// Only filled in if `VK_KHR_shader_float16_int8` is supported.
+    pub shader_float16_int8: VkPhysicalDeviceShaderFloat16Int8Features,
+    /// Only filled in if `VK_EXT_subgroup_size_control` is supported.
+    pub subgroup_size_control: VkPhysicalDeviceSubgroupSizeControlFeatures,
+}
+
+impl VkDeviceDescription {
+    /// Queries the description through the same function table FSR2 uses. Fails with the `VkResult` of
+    /// `vkEnumerateDeviceExtensionProperties` if it is neither `VK_SUCCESS` nor `VK_INCOMPLETE`, the latter retries.
+    ///
+    /// # Safety
+    /// `physical_device` must be a valid handle of the instance `fp_table` was loaded for.
+    pub unsafe fn query(
+        physical_device: VkPhysicalDevice,
+        fp_table: &FfxFsr2InstanceFunctionPointerTableVk,
+    ) -> Result<Self, VkResult> {
+        let enumerate = fp_table.fp_enumerate_device_extension_properties;
+        // The extension count can change between the two calls, e.g. when a layer gets enabled.
+        let extensions = loop {
+            let mut count = 0;
+            let result = enumerate(physical_device, std::ptr::null(), &mut count, std::ptr::null_mut());
+            if result != VK_SUCCESS {
+                return Err(result);
+            }
+            let mut extensions = vec![VkExtensionProperties::new(c"", 0); count as usize];
+            match enumerate(physical_device, std::ptr::null(), &mut count, extensions.as_mut_ptr()) {
+                VK_SUCCESS => {
+                    extensions.truncate(count as usize);
+                    break extensions;
+                }
+                VK_INCOMPLETE => continue,
+                result => return Err(result),
+            }
+        };
+
+        let mut description = Self {
+            extensions,
+            ..Default::default()
+        };
+        let mut features2 = VkPhysicalDeviceFeatures2::default();
+        // Only chain structs of supported extensions, drivers are free to reject anything else.
+        if description.supports(VK_KHR_SHADER_FLOAT16_INT8_EXTENSION_NAME) {
+            description.shader_float16_int8.p_next = features2.p_next;
+            features2.p_next = &mut description.shader_float16_int8 as *mut _ as *mut c_void;
+        }
+        if description.supports(VK_EXT_SUBGROUP_SIZE_CONTROL_EXTENSION_NAME) {
+            description.subgroup_size_control.p_next = features2.p_next;
+            features2.p_next = &mut description.subgroup_size_control as *mut _ as *mut c_void;
+        }
+        (fp_table.fp_get_physical_device_features2)(physical_device, &mut features2);
+
+        description.features = features2.features;
+        description.shader_float16_int8.p_next = std::ptr::null_mut();
+        description.subgroup_size_control.p_next = std::ptr::null_mut();
+        Ok(description)
+    }
+
+    pub fn supports(&self, extension: &CStr) -> bool {
+        self.extensions.iter().any(|properties| properties.name() == Some(extension))
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum VkRequirement {
+    /// FSR2 does not work without it.
+    Required,
+    /// Enables faster shader permutations.
+    Optional,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct VkExtensionRequirement {
+    pub name: &'static CStr,
+    pub requirement: VkRequirement,
+    pub supported: bool,
+}
+
+/// The device features FSR2 uses.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum VkFeature {
+    ShaderStorageImageExtendedFormats,
+    ShaderFloat16,
+    SubgroupSizeControl,
+    ComputeFullSubgroups,
+}
+
+impl VkFeature {
+    /// Name of the feature member in the Vulkan spec, e.g. `shaderFloat16`.
+    pub fn name(self) -> &'static str {
+        match self {
+            VkFeature::ShaderStorageImageExtendedFormats => "shaderStorageImageExtendedFormats",
+            VkFeature::ShaderFloat16 => "shaderFloat16",
+            VkFeature::SubgroupSizeControl => "subgroupSizeControl",
+            VkFeature::ComputeFullSubgroups => "computeFullSubgroups",
+        }
+    }
+
+    /// The extension that introduces the feature struct, `None` for `VkPhysicalDeviceFeatures`.
+    pub fn extension(self) -> Option<&'static CStr> {
+        match self {
+            VkFeature::ShaderStorageImageExtendedFormats => None,
+            VkFeature::ShaderFloat16 => Some(VK_KHR_SHADER_FLOAT16_INT8_EXTENSION_NAME),
+            VkFeature::SubgroupSizeControl | VkFeature::ComputeFullSubgroups => {
+                Some(VK_EXT_SUBGROUP_SIZE_CONTROL_EXTENSION_NAME)
+            }
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct VkFeatureRequirement {
+    pub feature: VkFeature,
+    pub requirement: VkRequirement,
+    pub supported: bool,
+}
+
+/// Returned by [`query_device_requirements_vk`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VkDeviceRequirements {
+    pub extensions: Vec<VkExtensionRequirement>,
+    pub features: Vec<VkFeatureRequirement>,
+}
+
+/// Feature structs to pass to `vkCreateDevice`, with only the features FSR2 uses enabled.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct VkEnabledFeatures {
+    pub features: VkPhysicalDeviceFeatures,
+    pub shader_float16_int8: Option<VkPhysicalDeviceShaderFloat16Int8Features>,
+    pub subgroup_size_control: Option<VkPhysicalDeviceSubgroupSizeControlFeatures>,
+}
+
+impl VkEnabledFeatures {
+    /// Links the extension structs into a `VkPhysicalDeviceFeatures2` chain. The returned struct points into
+    /// `self`, which must not be moved or dropped while it is in use.
+    pub fn features2(&mut self) -> VkPhysicalDeviceFeatures2 {
+        let mut features2 = VkPhysicalDeviceFeatures2 {
+            features: self.features,
+            ..Default::default()
+        };
+        if let Some(features) = &mut self.shader_float16_int8 {
+            features.p_next = features2.p_next;
+            features2.p_next = features as *mut _ as *mut c_void;
+        }
+        if let Some(features) = &mut self.subgroup_size_control {
+            features.p_next = features2.p_next;
+            features2.p_next = features as *mut _ as *mut c_void;
+        }
+        features2
+    }
+}
+
+impl VkDeviceRequirements {
+    /// True if every required extension and feature is supported.
+    pub fn is_supported(&self) -> bool {
+        self.missing_required().is_empty()
+    }
+
+    pub fn missing_required(&self) -> Vec<&'static str> {
+        let extensions = self
+            .extensions
+            .iter()
+            .filter(|extension| extension.requirement == VkRequirement::Required && !extension.supported)
+            .map(|extension| extension.name.to_str().unwrap());
+        let features = self
+            .features
+            .iter()
+            .filter(|feature| feature.requirement == VkRequirement::Required && !feature.supported)
+            .map(|feature| feature.feature.name());
+        extensions.chain(features).collect()
+    }
+
+    /// The supported extensions, to be enabled at device creation.
+    pub fn enabled_extensions(&self) -> Vec<&'static CStr> {
+        self.extensions
+            .iter()
+            .filter(|extension| extension.supported)
+            .map(|extension| extension.name)
+            .collect()
+    }
+
+    pub fn enabled_features(&self) -> VkEnabledFeatures {
+        let mut enabled = VkEnabledFeatures::default();
+        for feature in self.features.iter().filter(|feature| feature.supported) {
+            match feature.feature {
+                VkFeature::ShaderStorageImageExtendedFormats => {
+                    enabled.features.shader_storage_image_extended_formats = VK_TRUE
+                }
+                VkFeature::ShaderFloat16 => {
+                    enabled.shader_float16_int8.get_or_insert_with(Default::default).shader_float16 = VK_TRUE
+                }
+                VkFeature::SubgroupSizeControl => {
+                    enabled.subgroup_size_control.get_or_insert_with(Default::default).subgroup_size_control = VK_TRUE
+                }
+                VkFeature::ComputeFullSubgroups => {
+                    enabled.subgroup_size_control.get_or_insert_with(Default::default).compute_full_subgroups = VK_TRUE
+                }
+            }
+        }
+        enabled
+    }
+}
+
+/// Lists the device extensions and features FSR2 can use on `device`, and whether the device supports them.
+///
+/// FSR2 writes to storage images of formats outside the mandatory set, which makes
+/// `shaderStorageImageExtendedFormats` required. FP16 permutations need `shaderFloat16`, wave64
+/// permutations need subgroup size control.
+pub fn query_device_requirements_vk(device: &VkDeviceDescription) -> VkDeviceRequirements {
+    let float16_int8 = device.supports(VK_KHR_SHADER_FLOAT16_INT8_EXTENSION_NAME);
+    let subgroup_size_control = device.supports(VK_EXT_SUBGROUP_SIZE_CONTROL_EXTENSION_NAME);
+
+    let extensions = vec![
+        VkExtensionRequirement {
+            name: VK_KHR_SHADER_FLOAT16_INT8_EXTENSION_NAME,
+            requirement: VkRequirement::Optional,
+            supported: float16_int8,
+        },
+        VkExtensionRequirement {
+            name: VK_EXT_SUBGROUP_SIZE_CONTROL_EXTENSION_NAME,
+            requirement: VkRequirement::Optional,
+            supported: subgroup_size_control,
+        },
+    ];
+    let features = vec![
+        VkFeatureRequirement {
+            feature: VkFeature::ShaderStorageImageExtendedFormats,
+            requirement: VkRequirement::Required,
+            supported: device.features.shader_storage_image_extended_formats == VK_TRUE,
+        },
+        VkFeatureRequirement {
+            feature: VkFeature::ShaderFloat16,
+            requirement: VkRequirement::Optional,
+            supported: float16_int8 && device.shader_float16_int8.shader_float16 == VK_TRUE,
+        },
+        VkFeatureRequirement {
+            feature: VkFeature::SubgroupSizeControl,
+            requirement: VkRequirement::Optional,
+            supported: subgroup_size_control && device.subgroup_size_control.subgroup_size_control == VK_TRUE,
+        },
+        VkFeatureRequirement {
+            feature: VkFeature::ComputeFullSubgroups,
+            requirement: VkRequirement::Optional,
+            supported: subgroup_size_control && device.subgroup_size_control.compute_full_subgroups == VK_TRUE,
+        },
+    ];
+    VkDeviceRequirements { extensions, features }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    fn device(extensions: &[&CStr]) -> VkDeviceDescription {
+        let mut device = VkDeviceDescription {
+            extensions: extensions.iter().map(|name| VkExtensionProperties::new(name, 1)).collect(),
+            ..Default::default()
+        };
+        device.features.shader_storage_image_extended_formats = VK_TRUE;
+        device
+    }
+
+    #[test]
+    fn extension_name_round_trip() {
+        let properties = VkExtensionProperties::new(VK_EXT_SUBGROUP_SIZE_CONTROL_EXTENSION_NAME, 2);
+        assert_eq!(properties.name(), Some(VK_EXT_SUBGROUP_SIZE_CONTROL_EXTENSION_NAME));
+
+        let mut unterminated = properties;
+        unterminated.extension_name = [b'x' as c_char; VK_MAX_EXTENSION_NAME_SIZE];
+        assert_eq!(unterminated.name(), None);
+    }
+
+    #[test]
+    fn minimal_device() {
+        let requirements = query_device_requirements_vk(&device(&[]));
+        assert!(requirements.is_supported());
+        assert!(requirements.enabled_extensions().is_empty());
+        let enabled = requirements.enabled_features();
+        assert_eq!(enabled.features.shader_storage_image_extended_formats, VK_TRUE);
+        assert!(enabled.shader_float16_int8.is_none());
+        assert!(enabled.subgroup_size_control.is_none());
+    }
+
+    #[test]
+    fn missing_required_feature() {
+        let requirements = query_device_requirements_vk(&VkDeviceDescription::default());
+        assert!(!requirements.is_supported());
+        assert_eq!(requirements.missing_required(), ["shaderStorageImageExtendedFormats"]);
+    }
+
+    #[test]
+    fn optional_features() {
+        let mut device = device(&[
+            VK_KHR_SHADER_FLOAT16_INT8_EXTENSION_NAME,
+            VK_EXT_SUBGROUP_SIZE_CONTROL_EXTENSION_NAME,
+        ]);
+        device.shader_float16_int8.shader_float16 = VK_TRUE;
+        device.subgroup_size_control.subgroup_size_control = VK_TRUE;
+
+        let requirements = query_device_requirements_vk(&device);
+        assert_eq!(
+            requirements.enabled_extensions(),
+            [VK_KHR_SHADER_FLOAT16_INT8_EXTENSION_NAME, VK_EXT_SUBGROUP_SIZE_CONTROL_EXTENSION_NAME]
+        );
+        let mut enabled = requirements.enabled_features();
+        assert_eq!(enabled.shader_float16_int8.unwrap().shader_float16, VK_TRUE);
+        let subgroup_size_control = enabled.subgroup_size_control.unwrap();
+        assert_eq!(subgroup_size_control.subgroup_size_control, VK_TRUE);
+        assert_eq!(subgroup_size_control.compute_full_subgroups, VK_FALSE);
+
+        let features2 = enabled.features2();
+        assert_eq!(features2.s_type, VkStructureType::PHYSICAL_DEVICE_FEATURES_2);
+        let first = features2.p_next as *const VkPhysicalDeviceSubgroupSizeControlFeatures;
+        let second = unsafe { (*first).p_next } as *const VkPhysicalDeviceShaderFloat16Int8Features;
+        unsafe {
+            assert_eq!((*first).s_type, VkStructureType::PHYSICAL_DEVICE_SUBGROUP_SIZE_CONTROL_FEATURES);
+            assert_eq!((*second).s_type, VkStructureType::PHYSICAL_DEVICE_SHADER_FLOAT16_INT8_FEATURES);
+            assert!((*second).p_next.is_null());
+        }
+    }
+
+    static FILL_CALLS: AtomicU32 = AtomicU32::new(0);
+
+    /// Reports one extension, but a second one shows up before the first fill call.
+    unsafe extern "system" fn growing_extensions(
+        _: VkPhysicalDevice,
+        _: *const c_char,
+        count: *mut u32,
+        properties: *mut VkExtensionProperties,
+    ) -> VkResult {
+        let names = [VK_KHR_SHADER_FLOAT16_INT8_EXTENSION_NAME, VK_EXT_SUBGROUP_SIZE_CONTROL_EXTENSION_NAME];
+        if properties.is_null() {
+            *count = if FILL_CALLS.load(Ordering::Relaxed) == 0 { 1 } else { 2 };
+            return VK_SUCCESS;
+        }
+        FILL_CALLS.fetch_add(1, Ordering::Relaxed);
+        *count = (*count).min(2);
+        for (i, name) in names.iter().take(*count as usize).enumerate() {
+            *properties.add(i) = VkExtensionProperties::new(name, 1);
+        }
+        if *count < 2 { VK_INCOMPLETE } else { VK_SUCCESS }
+    }
+
+    unsafe extern "system" fn failing_extensions(
+        _: VkPhysicalDevice,
+        _: *const c_char,
+        _: *mut u32,
+        _: *mut VkExtensionProperties,
+    ) -> VkResult {
+        -1 // VK_ERROR_OUT_OF_HOST_MEMORY
+    }
+
+    unsafe extern "system" fn no_proc_addr(_: VkDevice, _: *const c_char) -> VoidFunctionPtr {
+        None
+    }
+    unsafe extern "system" fn no_memory_properties(_: VkPhysicalDevice, _: *mut VkPhysicalDeviceMemoryProperties) {}
+    unsafe extern "system" fn no_properties(_: VkPhysicalDevice, _: *mut VkPhysicalDeviceProperties) {}
+    unsafe extern "system" fn no_properties2(_: VkPhysicalDevice, _: *mut VkPhysicalDeviceProperties2) {}
+    unsafe extern "system" fn no_features2(_: VkPhysicalDevice, _: *mut VkPhysicalDeviceFeatures2) {}
+
+    fn fp_table(enumerate: VkEnumerateDeviceExtensionPropertiesFunc) -> FfxFsr2InstanceFunctionPointerTableVk {
+        FfxFsr2InstanceFunctionPointerTableVk {
+            fp_enumerate_device_extension_properties: enumerate,
+            fp_get_device_proc_addr: no_proc_addr,
+            fp_get_physical_device_memory_properties: no_memory_properties,
+            fp_get_physical_device_properties: no_properties,
+            fp_get_physical_device_properties2: no_properties2,
+            fp_get_physical_device_features2: no_features2,
+        }
+    }
+
+    #[test]
+    fn query_retries_incomplete_enumeration() {
+        let physical_device = VkPhysicalDevice::from_raw(0);
+        let device = unsafe { VkDeviceDescription::query(physical_device, &fp_table(growing_extensions)) }.unwrap();
+        assert_eq!(FILL_CALLS.load(Ordering::Relaxed), 2);
+        assert!(device.supports(VK_KHR_SHADER_FLOAT16_INT8_EXTENSION_NAME));
+        assert!(device.supports(VK_EXT_SUBGROUP_SIZE_CONTROL_EXTENSION_NAME));
+
+        let error = unsafe { VkDeviceDescription::query(physical_device, &fp_table(failing_extensions)) };
+        assert_eq!(error.unwrap_err(), -1);
+    }
+
+    #[test]
+    fn feature_without_extension_is_unsupported() {
+        let mut device = device(&[]);
+        device.shader_float16_int8.shader_float16 = VK_TRUE;
+        let requirements = query_device_requirements_vk(&device);
+        assert!(requirements.enabled_features().shader_float16_int8.is_none());
+    }
+}
@@ -0,0 +1,226 @@
+use super::*;
+use crate::{FfxComponentType, FfxSurfaceFormat};
+
+/// Whether a format can be used for storage images, which FSR2 needs for every resource it writes.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum VkStorageImageSupport {
+    /// Support is required by the Vulkan specification.
+    Required,
+    /// Supported if the `shaderStorageImageExtendedFormats` feature is enabled.
+    ExtendedFormats,
+    /// Not guaranteed, query `vkGetPhysicalDeviceFormatProperties`.
+    Optional,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct VkFormatInfo {
+    pub bytes_per_pixel: u32,
+    pub channel_count: u32,
+    pub component_type: FfxComponentType,
+    pub srgb: bool,
+    pub depth: bool,
+    pub storage_image: VkStorageImageSupport,
+}
+
+impl VkFormat {
+    /// Size, component layout and storage image support of the format. `None` for formats this crate
+    /// does not know about.
+    pub fn info(self) -> Option<VkFormatInfo> {
+        use FfxComponentType::*;
+        use VkStorageImageSupport::*;
+        let (bytes_per_pixel, channel_count, component_type, storage_image) = match self {
+            VkFormat::R8_UNORM => (1, 1, Unorm, ExtendedFormats),
+            VkFormat::R8_SNORM => (1, 1, Snorm, ExtendedFormats),
+            VkFormat::R8_UINT => (1, 1, Uint, ExtendedFormats),
+            VkFormat::R8_SRGB => (1, 1, Unorm, Optional),
+            VkFormat::R8G8_UNORM => (2, 2, Unorm, ExtendedFormats),
+            VkFormat::R8G8_SNORM => (2, 2, Snorm, ExtendedFormats),
+            VkFormat::R8G8_UINT => (2, 2, Uint, ExtendedFormats),
+            VkFormat::R8G8_SRGB => (2, 2, Unorm, Optional),
+            VkFormat::R8G8B8A8_UNORM => (4, 4, Unorm, Required),
+            VkFormat::R8G8B8A8_SNORM => (4, 4, Snorm, Required),
+            VkFormat::R8G8B8A8_UINT => (4, 4, Uint, Required),
+            VkFormat::R8G8B8A8_SRGB => (4, 4, Unorm, Optional),
+            VkFormat::B8G8R8A8_UNORM => (4, 4, Unorm, Optional),
+            VkFormat::B8G8R8A8_SRGB => (4, 4, Unorm, Optional),
+            VkFormat::A2B10G10R10_UNORM_PACK32 => (4, 4, Unorm, ExtendedFormats),
+            VkFormat::R16_UNORM => (2, 1, Unorm, ExtendedFormats),
+            VkFormat::R16_SNORM => (2, 1, Snorm, ExtendedFormats),
+            VkFormat::R16_UINT => (2, 1, Uint, ExtendedFormats),
+            VkFormat::R16_SFLOAT => (2, 1, Float, ExtendedFormats),
+            VkFormat::R16G16_UNORM => (4, 2, Unorm, ExtendedFormats),
+            VkFormat::R16G16_SNORM => (4, 2, Snorm, ExtendedFormats),
+            VkFormat::R16G16_UINT => (4, 2, Uint, ExtendedFormats),
+            VkFormat::R16G16_SFLOAT => (4, 2, Float, ExtendedFormats),
+            VkFormat::R16G16B16A16_UNORM => (8, 4, Unorm, ExtendedFormats),
+            VkFormat::R16G16B16A16_SNORM => (8, 4, Snorm, ExtendedFormats),
+            VkFormat::R16G16B16A16_UINT => (8, 4, Uint, Required),
+            VkFormat::R16G16B16A16_SFLOAT => (8, 4, Float, Required),
+            VkFormat::R32_UINT => (4, 1, Uint, Required),
+            VkFormat::R32_SFLOAT => (4, 1, Float, Required),
+            VkFormat::R32G32_UINT => (8, 2, Uint, Required),
+            VkFormat::R32G32_SFLOAT => (8, 2, Float, Required),
+            VkFormat::R32G32B32A32_UINT => (16, 4, Uint, Required),
+            VkFormat::R32G32B32A32_SFLOAT => (16, 4, Float, Required),
+            VkFormat::B10G11R11_UFLOAT_PACK32 => (4, 3, Float, ExtendedFormats),
+            VkFormat::D16_UNORM => (2, 1, Unorm, Optional),
+            VkFormat::X8_D24_UNORM_PACK32 => (4, 1, Unorm, Optional),
+            VkFormat::D32_SFLOAT => (4, 1, Float, Optional),
+            VkFormat::D16_UNORM_S8_UINT => (3, 2, Unorm, Optional),
+            VkFormat::D24_UNORM_S8_UINT => (4, 2, Unorm, Optional),
+            VkFormat::D32_SFLOAT_S8_UINT => (5, 2, Float, Optional),
+            _ => return None,
+        };
+        Some(VkFormatInfo {
+            bytes_per_pixel,
+            channel_count,
+            component_type,
+            srgb: self.is_srgb(),
+            depth: self.is_depth(),
+            storage_image,
+        })
+    }
+
+    pub fn is_srgb(self) -> bool {
+        matches!(
+            self,
+            VkFormat::R8_SRGB | VkFormat::R8G8_SRGB | VkFormat::R8G8B8A8_SRGB | VkFormat::B8G8R8A8_SRGB
+        )
+    }
+
+    /// Matches the formats for which `ffxGetTextureResourceVK` sets `FfxResource::is_depth`.
+    pub fn is_depth(self) -> bool {
+        matches!(
+            self,
+            VkFormat::D16_UNORM
+                | VkFormat::X8_D24_UNORM_PACK32
+                | VkFormat::D32_SFLOAT
+                | VkFormat::D16_UNORM_S8_UINT
+                | VkFormat::D24_UNORM_S8_UINT
+                | VkFormat::D32_SFLOAT_S8_UINT
+        )
+    }
+
+    /// The UNORM format with the same memory layout as this sRGB format. Other formats are returned unchanged.
+    pub fn to_unorm(self) -> Self {
+        match self {
+            VkFormat::R8_SRGB => VkFormat::R8_UNORM,
+            VkFormat::R8G8_SRGB => VkFormat::R8G8_UNORM,
+            VkFormat::R8G8B8A8_SRGB => VkFormat::R8G8B8A8_UNORM,
+            VkFormat::B8G8R8A8_SRGB => VkFormat::B8G8R8A8_UNORM,
+            format => format,
+        }
+    }
+
+    /// The sRGB format with the same memory layout as this UNORM format, if there is one.
+    pub fn to_srgb(self) -> Option<Self> {
+        match self {
+            VkFormat::R8_UNORM => Some(VkFormat::R8_SRGB),
+            VkFormat::R8G8_UNORM => Some(VkFormat::R8G8_SRGB),
+            VkFormat::R8G8B8A8_UNORM => Some(VkFormat::R8G8B8A8_SRGB),
+            VkFormat::B8G8R8A8_UNORM => Some(VkFormat::B8G8R8A8_SRGB),
+            format if format.is_srgb() => Some(format),
+            _ => None,
+        }
+    }
+}
+
+/// The surface format FSR2 reads a Vulkan format as. sRGB formats alias their UNORM counterpart,
+/// depth formats map to the format of their depth aspect. Returns `FfxSurfaceFormat::Unknown` for
+/// formats FSR2 can not read.
+pub fn ffx_surface_format_from_vk(format: VkFormat) -> FfxSurfaceFormat {
+    match format.to_unorm() {
+        VkFormat::R32G32B32A32_SFLOAT => FfxSurfaceFormat::RGBA32Float,
+        VkFormat::R16G16B16A16_SFLOAT => FfxSurfaceFormat::RGBA16Float,
+        VkFormat::R16G16B16A16_UNORM => FfxSurfaceFormat::RGBA16Unorm,
+        VkFormat::R32G32_SFLOAT => FfxSurfaceFormat::RG32Float,
+        VkFormat::R32_UINT => FfxSurfaceFormat::R32Uint,
+        VkFormat::R8G8B8A8_UNORM => FfxSurfaceFormat::RGBA8Unorm,
+        VkFormat::B10G11R11_UFLOAT_PACK32 => FfxSurfaceFormat::R11G11B11Float,
+        VkFormat::R16G16_SFLOAT => FfxSurfaceFormat::RG16Float,
+        VkFormat::R16G16_UINT => FfxSurfaceFormat::RG16Uint,
+        VkFormat::R16_SFLOAT => FfxSurfaceFormat::R16Float,
+        VkFormat::R16_UINT => FfxSurfaceFormat::R16Uint,
+        VkFormat::R16_UNORM | VkFormat::D16_UNORM => FfxSurfaceFormat::R16Unorm,
+        VkFormat::R16_SNORM => FfxSurfaceFormat::R16Snorm,
+        VkFormat::R8_UNORM => FfxSurfaceFormat::R8Unorm,
+        VkFormat::R8_UINT => FfxSurfaceFormat::R8Uint,
+        VkFormat::R8G8_UNORM => FfxSurfaceFormat::RG8Unorm,
+        VkFormat::R32_SFLOAT | VkFormat::D32_SFLOAT => FfxSurfaceFormat::R32Float,
+        _ => FfxSurfaceFormat::Unknown,
+    }
+}
+
+/// The Vulkan format FSR2 creates images of `format` with. Typeless formats map to their typed equivalent,
+/// `Unknown` maps to `VkFormat::UNDEFINED`.
+pub fn vk_format_from_ffx_surface_format(format: FfxSurfaceFormat) -> VkFormat {
+    match format.typed() {
+        FfxSurfaceFormat::RGBA32Float => VkFormat::R32G32B32A32_SFLOAT,
+        FfxSurfaceFormat::RGBA16Float => VkFormat::R16G16B16A16_SFLOAT,
+        FfxSurfaceFormat::RGBA16Unorm => VkFormat::R16G16B16A16_UNORM,
+        FfxSurfaceFormat::RG32Float => VkFormat::R32G32_SFLOAT,
+        FfxSurfaceFormat::R32Uint => VkFormat::R32_UINT,
+        FfxSurfaceFormat::RGBA8Unorm => VkFormat::R8G8B8A8_UNORM,
+        FfxSurfaceFormat::R11G11B11Float => VkFormat::B10G11R11_UFLOAT_PACK32,
+        FfxSurfaceFormat::RG16Float => VkFormat::R16G16_SFLOAT,
+        FfxSurfaceFormat::RG16Uint => VkFormat::R16G16_UINT,
+        FfxSurfaceFormat::R16Float => VkFormat::R16_SFLOAT,
+        FfxSurfaceFormat::R16Uint => VkFormat::R16_UINT,
+        FfxSurfaceFormat::R16Unorm => VkFormat::R16_UNORM,
+        FfxSurfaceFormat::R16Snorm => VkFormat::R16_SNORM,
+        FfxSurfaceFormat::R8Unorm => VkFormat::R8_UNORM,
+        FfxSurfaceFormat::R8Uint => VkFormat::R8_UINT,
+        FfxSurfaceFormat::RG8Unorm => VkFormat::R8G8_UNORM,
+        FfxSurfaceFormat::R32Float => VkFormat::R32_SFLOAT,
+        FfxSurfaceFormat::Unknown | FfxSurfaceFormat::RGBA32Typeless | FfxSurfaceFormat::RGBA8Typeless => {
+            VkFormat::UNDEFINED
+        }
+    }
+}
+
+impl From<VkFormat> for FfxSurfaceFormat {
+    fn from(format: VkFormat) -> Self {
+        ffx_surface_format_from_vk(format)
+    }
+}
+
+impl From<FfxSurfaceFormat> for VkFormat {
+    fn from(format: FfxSurfaceFormat) -> Self {
+        vk_format_from_ffx_surface_format(format)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        for format in FfxSurfaceFormat::ALL {
+            let vk = VkFormat::from(format);
+            assert_eq!(FfxSurfaceFormat::from(vk), format.typed(), "{format:?}");
+            if format != FfxSurfaceFormat::Unknown {
+                let ffx_info = format.typed().info().unwrap();
+                let vk_info = vk.info().unwrap();
+                assert_eq!(ffx_info.bytes_per_pixel, vk_info.bytes_per_pixel, "{format:?}");
+                assert_eq!(ffx_info.channel_count, vk_info.channel_count, "{format:?}");
+                assert_eq!(ffx_info.component_type, vk_info.component_type, "{format:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn srgb_aliasing() {
+        assert_eq!(FfxSurfaceFormat::from(VkFormat::R8G8B8A8_SRGB), FfxSurfaceFormat::RGBA8Unorm);
+        assert_eq!(VkFormat::R8G8B8A8_UNORM.to_srgb(), Some(VkFormat::R8G8B8A8_SRGB));
+        assert_eq!(VkFormat::R16G16B16A16_SFLOAT.to_srgb(), None);
+        assert!(VkFormat::B8G8R8A8_SRGB.info().unwrap().srgb);
+    }
+
+    #[test]
+    fn depth_formats() {
+        assert_eq!(FfxSurfaceFormat::from(VkFormat::D32_SFLOAT), FfxSurfaceFormat::R32Float);
+        assert!(VkFormat::D32_SFLOAT.info().unwrap().depth);
+        assert_eq!(FfxSurfaceFormat::from(VkFormat::D24_UNORM_S8_UINT), FfxSurfaceFormat::Unknown);
+    }
+}
@@ -0,0 +1,170 @@
+use super::*;
+use std::ffi::CStr;
+use std::fmt;
+
+pub type VkGetInstanceProcAddrFunc =
+    unsafe extern "system" fn(VkInstance, *const c_char) -> VoidFunctionPtr;
+
+/// Returned by [`FfxFsr2InstanceFunctionPointerTableVk::load`], lists every function that could not be resolved.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VkFunctionLoadError {
+    pub missing: Vec<&'static str>,
+}
+
+impl fmt::Display for VkFunctionLoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "failed to resolve Vulkan functions: {}", self.missing.join(", "))
+    }
+}
+
+impl std::error::Error for VkFunctionLoadError {}
+
+/// Looks up the first of `names` that resolves. Records the first name as missing if none do.
+unsafe fn resolve(
+    get_instance_proc_addr: VkGetInstanceProcAddrFunc,
+    instance: VkInstance,
+    names: &[&'static CStr],
+    missing: &mut Vec<&'static str>,
+) -> VoidFunctionPtr {
+    let function = names
+        .iter()
+        .find_map(|name| get_instance_proc_addr(instance, name.as_ptr()));
+    if function.is_none() {
+        missing.push(names[0].to_str().unwrap());
+    }
+    function
+}
+
+impl FfxFsr2InstanceFunctionPointerTableVk {
+    /// Resolves every entry of the table through `vkGetInstanceProcAddr`, so any loader can be used.
+    /// `vkGetPhysicalDeviceProperties2` and `vkGetPhysicalDeviceFeatures2` fall back to their
+    /// `VK_KHR_get_physical_device_properties2` variants on Vulkan 1.0 instances.
+    ///
+    /// # Safety
+    /// `instance` must be a valid Vulkan instance and `get_instance_proc_addr` must be the matching
+    /// `vkGetInstanceProcAddr`.
+    pub unsafe fn load(
+        instance: VkInstance,
+        get_instance_proc_addr: VkGetInstanceProcAddrFunc,
+    ) -> Result<Self, VkFunctionLoadError> {
+        let mut missing = Vec::new();
+        let mut load = |names: &[&'static CStr]| resolve(get_instance_proc_addr, instance, names, &mut missing);
+
+        let enumerate_device_extension_properties = load(&[c"vkEnumerateDeviceExtensionProperties"]);
+        let get_device_proc_addr = load(&[c"vkGetDeviceProcAddr"]);
+        let get_physical_device_memory_properties = load(&[c"vkGetPhysicalDeviceMemoryProperties"]);
+        let get_physical_device_properties = load(&[c"vkGetPhysicalDeviceProperties"]);
+        let get_physical_device_properties2 = load(&[
+            c"vkGetPhysicalDeviceProperties2",
+            c"vkGetPhysicalDeviceProperties2KHR",
+        ]);
+        let get_physical_device_features2 = load(&[
+            c"vkGetPhysicalDeviceFeatures2",
+            c"vkGetPhysicalDeviceFeatures2KHR",
+        ]);
+
+        match (
+            enumerate_device_extension_properties,
+            get_device_proc_addr,
+            get_physical_device_memory_properties,
+            get_physical_device_properties,
+            get_physical_device_properties2,
+            get_physical_device_features2,
+        ) {
+            (Some(a), Some(b), Some(c), Some(d), Some(e), Some(f)) => Ok(Self {
+                fp_enumerate_device_extension_properties: std::mem::transmute::<
+                    unsafe extern "system" fn(),
+                    VkEnumerateDeviceExtensionPropertiesFunc,
+                >(a),
+                fp_get_device_proc_addr: std::mem::transmute::<unsafe extern "system" fn(), VkGetDeviceProcAddrFunc>(
+                    b,
+                ),
+                fp_get_physical_device_memory_properties: std::mem::transmute::<
+                    unsafe extern "system" fn(),
+                    VkGetPhysicalDeviceMemoryPropertiesFunc,
+                >(c),
+                fp_get_physical_device_properties: std::mem::transmute::<
+                    unsafe extern "system" fn(),
+                    VkGetPhysicalDevicePropertiesFunc,
+                >(d),
+                fp_get_physical_device_properties2: std::mem::transmute::<
+                    unsafe extern "system" fn(),
+                    VkGetPhysicalDeviceProperties2Func,
+                >(e),
+                fp_get_physical_device_features2: std::mem::transmute::<
+                    unsafe extern "system" fn(),
+                    VkGetPhysicalDeviceFeatures2Func,
+                >(f),
+            }),
+            _ => Err(VkFunctionLoadError { missing }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    unsafe extern "system" fn dummy() {}
+
+    // Distinct bodies, so the stubs can not be merged into one function.
+    static KHR_CALLS: AtomicU32 = AtomicU32::new(0);
+    unsafe extern "system" fn properties2_khr() {
+        KHR_CALLS.fetch_add(1, Ordering::Relaxed);
+    }
+    unsafe extern "system" fn features2_khr() {
+        KHR_CALLS.fetch_add(2, Ordering::Relaxed);
+    }
+
+    thread_local! {
+        static REQUESTED: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+    }
+
+    /// Behaves like a Vulkan 1.0 instance with VK_KHR_get_physical_device_properties2.
+    unsafe extern "system" fn vulkan_1_0_proc_addr(_: VkInstance, name: *const c_char) -> VoidFunctionPtr {
+        let name = CStr::from_ptr(name).to_str().unwrap();
+        REQUESTED.with(|requested| requested.borrow_mut().push(name.to_owned()));
+        match name {
+            "vkGetPhysicalDeviceProperties2" | "vkGetPhysicalDeviceFeatures2" => None,
+            "vkGetPhysicalDeviceProperties2KHR" => Some(properties2_khr),
+            "vkGetPhysicalDeviceFeatures2KHR" => Some(features2_khr),
+            _ => Some(dummy),
+        }
+    }
+
+    unsafe extern "system" fn incomplete_proc_addr(_: VkInstance, name: *const c_char) -> VoidFunctionPtr {
+        let name = CStr::from_ptr(name).to_str().unwrap();
+        if name.starts_with("vkGetPhysicalDeviceFeatures2") || name == "vkGetDeviceProcAddr" {
+            None
+        } else {
+            Some(dummy)
+        }
+    }
+
+    #[test]
+    fn falls_back_to_khr() {
+        let table = unsafe { FfxFsr2InstanceFunctionPointerTableVk::load(VkInstance::from_raw(1), vulkan_1_0_proc_addr) }
+            .unwrap();
+        assert_eq!(table.fp_get_physical_device_properties2 as *const (), properties2_khr as *const ());
+        assert_eq!(table.fp_get_physical_device_features2 as *const (), features2_khr as *const ());
+        let requested = REQUESTED.with(|requested| requested.take());
+        for name in [
+            "vkGetPhysicalDeviceProperties2",
+            "vkGetPhysicalDeviceProperties2KHR",
+            "vkGetPhysicalDeviceFeatures2",
+            "vkGetPhysicalDeviceFeatures2KHR",
+        ] {
+            assert!(requested.iter().any(|requested| requested == name), "{name} was not requested");
+        }
+    }
+
+    #[test]
+    fn reports_missing_functions() {
+        let err = unsafe { FfxFsr2InstanceFunctionPointerTableVk::load(VkInstance::from_raw(1), incomplete_proc_addr) }
+            .err()
+            .unwrap();
+        assert_eq!(err.missing, vec!["vkGetDeviceProcAddr", "vkGetPhysicalDeviceFeatures2"]);
+    }
+}
@@ -0,0 +1,503 @@
+//! Camera helpers for engines that only have matrices: motion vectors caused by camera movement alone, reconstructed
+//! from depth and the view projection matrices, and the FSR2 camera parameters of a projection matrix.
+//!
+//! Matrices are column major (`m[column][row]`) and transform column vectors, as in GLSL, glam or HLSL with
+//! `column_major` packing. Clip space has depth from 0 to 1, its y axis points up as in D3D or down as in Vulkan,
+//! see [`ClipSpaceY`].
+
+use crate::{CpuImage, FfxDimensions2D, FfxFloatCoords2D, FfxFsr2InitializationFlagBits, FfxSurfaceFormat};
+use bytemuck::{Pod, Zeroable};
+use std::fmt;
+
+/// A column major 4x4 matrix.
+pub type Matrix4 = [[f32; 4]; 4];
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct SingularMatrixError;
+
+impl fmt::Display for SingularMatrixError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "the view projection matrix can not be inverted")
+    }
+}
+
+impl std::error::Error for SingularMatrixError {}
+
+/// Direction of the y axis of clip space. Motion vectors are in pixels with y pointing down either way.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ClipSpaceY {
+    /// D3D and OpenGL.
+    Up,
+    /// Vulkan, without a negative viewport height.
+    Down,
+}
+
+/// Constants of a camera motion vector shader. For the pixel at `p = pixel + 0.5 - jitter_offset`, the current clip
+/// position is `c = (ndc(p), depth, 1)` and the previous one `reprojection * c`, with the y of `ndc` negated if
+/// `y_down` is set. Pixels at `far_depth` are moved to infinity if `far_to_infinity` is set: their previous clip
+/// position is `reprojection * f / dot(world_w, f) - reprojection * n / dot(world_w, n)`, with `f` and `n` being `c`
+/// at `far_depth` and `near_depth`.
+/// The motion vector is the previous minus the current position, in render pixels.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+#[repr(C)]
+pub struct CameraMotionConstants {
+    /// Current clip space to previous clip space.
+    pub reprojection: Matrix4,
+    /// The last row of the inverse current view projection, the world space w of a clip position.
+    pub world_w: [f32; 4],
+    pub render_size: [f32; 2],
+    pub jitter_offset: [f32; 2],
+    pub near_depth: f32,
+    pub far_depth: f32,
+    pub far_to_infinity: u32,
+    /// Set for [`ClipSpaceY::Down`].
+    pub y_down: u32,
+}
+
+// SAFETY: Only 4 byte floats and integers, no padding.
+unsafe impl Zeroable for CameraMotionConstants {}
+unsafe impl Pod for CameraMotionConstants {}
+
+/// Generates motion vectors for passes that don't write their own, like skyboxes or static geometry.
+///
+/// The matrices must not contain the jitter. Depth is in the convention of `ENABLE_DEPTH_INVERTED` and
+/// `ENABLE_DEPTH_INFINITE`; without `ENABLE_DEPTH_INFINITE`, pixels at the far plane are treated as infinitely far
+/// away, so skyboxes drawn there only move with the camera's rotation.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct CameraMotion {
+    constants: CameraMotionConstants,
+}
+
+impl CameraMotion {
+    pub fn new(
+        view_projection: &Matrix4,
+        previous_view_projection: &Matrix4,
+        jitter_offset: FfxFloatCoords2D,
+        render_size: FfxDimensions2D,
+        flags: FfxFsr2InitializationFlagBits,
+        clip_space_y: ClipSpaceY,
+    ) -> Result<Self, SingularMatrixError> {
+        let inverse = invert(&to_f64(view_projection)).ok_or(SingularMatrixError)?;
+        let reprojection = multiply(&to_f64(previous_view_projection), &inverse);
+        let inverted = flags.contains(FfxFsr2InitializationFlagBits::ENABLE_DEPTH_INVERTED);
+        Ok(Self {
+            constants: CameraMotionConstants {
+                reprojection: reprojection.map(|column| column.map(|value| value as f32)),
+                world_w: inverse.map(|column| column[3] as f32),
+                render_size: [render_size.width as f32, render_size.height as f32],
+                jitter_offset: [jitter_offset.x, jitter_offset.y],
+                near_depth: if inverted { 1.0 } else { 0.0 },
+                far_depth: if inverted { 0.0 } else { 1.0 },
+                far_to_infinity: !flags.contains(FfxFsr2InitializationFlagBits::ENABLE_DEPTH_INFINITE) as u32,
+                y_down: (clip_space_y == ClipSpaceY::Down) as u32,
+            },
+        })
+    }
+
+    pub fn from_constants(constants: &CameraMotionConstants) -> Self {
+        Self { constants: *constants }
+    }
+
+    pub fn constants(&self) -> &CameraMotionConstants {
+        &self.constants
+    }
+
+    /// Motion of the pixel at `x`, `y` to the previous frame in render pixels, zero if it was behind the camera.
+    pub fn motion_vector(&self, x: u32, y: u32, depth: f32) -> [f32; 2] {
+        let constants = &self.constants;
+        let size = constants.render_size;
+        let position = [
+            x as f32 + 0.5 - constants.jitter_offset[0],
+            y as f32 + 0.5 - constants.jitter_offset[1],
+        ];
+        let y_sign = if constants.y_down != 0 { -1.0 } else { 1.0 };
+        let ndc = [position[0] / size[0] * 2.0 - 1.0, y_sign * (1.0 - position[1] / size[1] * 2.0)];
+        let clip = |depth| [ndc[0], ndc[1], depth, 1.0];
+
+        let previous = if constants.far_to_infinity != 0 && depth == constants.far_depth {
+            let (far, near) = (clip(constants.far_depth), clip(constants.near_depth));
+            let far_w = dot(constants.world_w, far);
+            let near_w = dot(constants.world_w, near);
+            let (far, near) = (
+                transform(&constants.reprojection, far),
+                transform(&constants.reprojection, near),
+            );
+            [0, 1, 2, 3].map(|i| far[i] / far_w - near[i] / near_w)
+        } else {
+            transform(&constants.reprojection, clip(depth))
+        };
+        if previous[3] <= 0.0 {
+            return [0.0; 2];
+        }
+        let previous_position = [
+            (previous[0] / previous[3] + 1.0) * 0.5 * size[0],
+            (1.0 - y_sign * previous[1] / previous[3]) * 0.5 * size[1],
+        ];
+        [previous_position[0] - position[0], previous_position[1] - position[1]]
+    }
+
+    /// `RG16Float` motion vectors for a render size depth buffer, use a `motion_vector_scale` of (1, 1).
+    pub fn motion_vectors(&self, depth: &CpuImage) -> CpuImage {
+        let (width, height) = (depth.width(), depth.height());
+        let mut motion_vectors = CpuImage::new(width, height, FfxSurfaceFormat::RG16Float);
+        for y in 0..height {
+            for x in 0..width {
+                let motion = self.motion_vector(x, y, depth.load(x as i32, y as i32)[0]);
+                motion_vectors.store(x as i32, y as i32, [motion[0], motion[1], 0.0, 0.0]);
+            }
+        }
+        motion_vectors
+    }
+}
+
+/// Depth range of clip space.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum DepthRange {
+    /// D3D and Vulkan.
+    ZeroToOne,
+    /// OpenGL without `glClipControl`.
+    MinusOneToOne,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ProjectionError {
+    Orthographic,
+    /// Not a perspective projection, or one with a near plane behind the camera or a far plane before the near one.
+    Malformed,
+}
+
+impl fmt::Display for ProjectionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProjectionError::Orthographic => write!(f, "orthographic projections are not supported"),
+            ProjectionError::Malformed => write!(f, "the matrix is not a valid perspective projection"),
+        }
+    }
+}
+
+impl std::error::Error for ProjectionError {}
+
+/// The camera description FSR2 needs, see [`CameraParameters::from_projection`].
+#[derive(Copy, Clone, Debug)]
+pub struct CameraParameters {
+    pub camera_near: f32,
+    /// `f32::MAX` for infinite projections.
+    pub camera_far: f32,
+    pub camera_vertical_fov: f32,
+    /// `ENABLE_DEPTH_INVERTED` and `ENABLE_DEPTH_INFINITE` as needed for the depth buffer.
+    pub flags: FfxFsr2InitializationFlagBits,
+}
+
+impl CameraParameters {
+    /// Decomposes a left or right handed, possibly off-centre perspective projection. Far planes more than a million
+    /// times further away than the near plane are treated as infinite.
+    pub fn from_projection(projection: &Matrix4, depth_range: DepthRange) -> Result<Self, ProjectionError> {
+        let m = to_f64(projection);
+        if m.iter().flatten().any(|value| !value.is_finite()) {
+            return Err(ProjectionError::Malformed);
+        }
+        // The w row has to be (0, 0, w, 0), orthographic projections have (0, 0, 0, w) instead.
+        let w = m[2][3];
+        if w == 0.0 && m[3][3] != 0.0 {
+            return Err(ProjectionError::Orthographic);
+        }
+        if w == 0.0 || m[0][3] != 0.0 || m[1][3] != 0.0 || m[3][3] != 0.0 || m[0][2] != 0.0 || m[1][2] != 0.0 {
+            return Err(ProjectionError::Malformed);
+        }
+
+        // With the distance d = w / |w| * view z, the depth is a + b / d.
+        let (mut a, mut b) = (m[2][2] / w, m[3][2] / w.abs());
+        if depth_range == DepthRange::MinusOneToOne {
+            (a, b) = ((a + 1.0) * 0.5, b * 0.5);
+        }
+        let inverted = b > 0.0;
+        let (near, far) = if inverted {
+            (b / (1.0 - a), -b / a)
+        } else {
+            (-b / a, b / (1.0 - a))
+        };
+        // A far plane beyond infinity comes from projections that keep infinitely far away points inside the range.
+        let infinite = !(far > 0.0 && far <= near * 1e6);
+        let y_scale = m[1][1].abs() / w.abs();
+        if !(near.is_finite() && near > 0.0 && (infinite || far > near) && y_scale > 0.0 && m[0][0] != 0.0) {
+            return Err(ProjectionError::Malformed);
+        }
+
+        let mut flags = FfxFsr2InitializationFlagBits::empty();
+        flags.set(FfxFsr2InitializationFlagBits::ENABLE_DEPTH_INVERTED, inverted);
+        flags.set(FfxFsr2InitializationFlagBits::ENABLE_DEPTH_INFINITE, infinite);
+        Ok(Self {
+            camera_near: near as f32,
+            camera_far: if infinite { f32::MAX } else { far as f32 },
+            camera_vertical_fov: (2.0 * (1.0 / y_scale).atan()) as f32,
+            flags,
+        })
+    }
+}
+
+fn dot(a: [f32; 4], b: [f32; 4]) -> f32 {
+    a.iter().zip(b).map(|(a, b)| a * b).sum()
+}
+
+fn transform(matrix: &Matrix4, vector: [f32; 4]) -> [f32; 4] {
+    [0, 1, 2, 3].map(|row| (0..4).map(|column| matrix[column][row] * vector[column]).sum())
+}
+
+fn to_f64(matrix: &Matrix4) -> [[f64; 4]; 4] {
+    matrix.map(|column| column.map(f64::from))
+}
+
+fn multiply(a: &[[f64; 4]; 4], b: &[[f64; 4]; 4]) -> [[f64; 4]; 4] {
+    [0, 1, 2, 3].map(|column| [0, 1, 2, 3].map(|row| (0..4).map(|i| a[i][row] * b[column][i]).sum()))
+}
+
+/// Gauss-Jordan elimination with partial pivoting.
+fn invert(matrix: &[[f64; 4]; 4]) -> Option<[[f64; 4]; 4]> {
+    // Rows of [matrix | identity].
+    let mut rows = [[0.0; 8]; 4];
+    for (row, values) in rows.iter_mut().enumerate() {
+        for column in 0..4 {
+            values[column] = matrix[column][row];
+        }
+        values[4 + row] = 1.0;
+    }
+    let scale = matrix.iter().flatten().fold(0.0f64, |max, value| max.max(value.abs()));
+    for column in 0..4 {
+        let pivot = (column..4).max_by(|&a, &b| rows[a][column].abs().total_cmp(&rows[b][column].abs()))?;
+        if rows[pivot][column].abs() <= scale * 1e-12 {
+            return None;
+        }
+        rows.swap(column, pivot);
+        let divisor = rows[column][column];
+        rows[column].iter_mut().for_each(|value| *value /= divisor);
+        for row in 0..4 {
+            if row != column {
+                let factor = rows[row][column];
+                let pivot_row = rows[column];
+                rows[row]
+                    .iter_mut()
+                    .zip(pivot_row)
+                    .for_each(|(value, pivot)| *value -= factor * pivot);
+            }
+        }
+    }
+    Some([0, 1, 2, 3].map(|column| [0, 1, 2, 3].map(|row| rows[row][4 + column])))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::size;
+    use crate::{SceneDescription, SceneShape, SyntheticScene};
+
+    fn depth_flags() -> [FfxFsr2InitializationFlagBits; 4] {
+        let inverted = FfxFsr2InitializationFlagBits::ENABLE_DEPTH_INVERTED;
+        let infinite = FfxFsr2InitializationFlagBits::ENABLE_DEPTH_INFINITE;
+        [
+            FfxFsr2InitializationFlagBits::empty(),
+            inverted,
+            infinite,
+            inverted | infinite,
+        ]
+    }
+
+    /// Left handed perspective looking along +z, translated to `camera`.
+    fn view_projection(description: &SceneDescription, camera: [f32; 2]) -> Matrix4 {
+        let (near, far) = (description.camera_near, description.camera_far);
+        let y_scale = 1.0 / (description.camera_vertical_fov * 0.5).tan();
+        let x_scale = y_scale * description.render_size.height as f32 / description.render_size.width as f32;
+        let flags = description.flags;
+        let (z_scale, z_offset) = match (
+            flags.contains(FfxFsr2InitializationFlagBits::ENABLE_DEPTH_INVERTED),
+            flags.contains(FfxFsr2InitializationFlagBits::ENABLE_DEPTH_INFINITE),
+        ) {
+            (false, false) => (far / (far - near), -near * far / (far - near)),
+            (true, false) => (-near / (far - near), near * far / (far - near)),
+            (false, true) => (1.0, -near),
+            (true, true) => (0.0, near),
+        };
+        [
+            [x_scale, 0.0, 0.0, 0.0],
+            [0.0, y_scale, 0.0, 0.0],
+            [0.0, 0.0, z_scale, 1.0],
+            [-camera[0] * x_scale, -camera[1] * y_scale, z_offset, 0.0],
+        ]
+    }
+
+    /// The Vulkan convention of `matrix`, negating clip space y.
+    fn y_down(mut matrix: Matrix4) -> Matrix4 {
+        matrix.iter_mut().for_each(|column| column[1] = -column[1]);
+        matrix
+    }
+
+    #[test]
+    fn static_camera() {
+        let matrix = view_projection(&SceneDescription::new(size(8, 8), size(16, 16)), [1.0, 2.0]);
+        let jitter = FfxFloatCoords2D { x: 0.3, y: -0.2 };
+        let flags = FfxFsr2InitializationFlagBits::empty();
+        let motion = CameraMotion::new(&matrix, &matrix, jitter, size(8, 8), flags, ClipSpaceY::Up).unwrap();
+        for depth in [0.0, 0.5, 0.999, 1.0] {
+            let [x, y] = motion.motion_vector(3, 5, depth);
+            assert!(x.abs() < 1e-4 && y.abs() < 1e-4, "{x} {y} at {depth}");
+        }
+        assert_eq!(std::mem::size_of::<CameraMotionConstants>(), 112);
+
+        let singular = [[0.0; 4]; 4];
+        assert_eq!(
+            CameraMotion::new(&singular, &matrix, jitter, size(8, 8), flags, ClipSpaceY::Up),
+            Err(SingularMatrixError)
+        );
+    }
+
+    #[test]
+    fn matches_scene_motion() {
+        for (flags, clip_space_y) in depth_flags()
+            .into_iter()
+            .flat_map(|flags| [(flags, ClipSpaceY::Up), (flags, ClipSpaceY::Down)])
+        {
+            let convention = |matrix| match clip_space_y {
+                ClipSpaceY::Up => matrix,
+                ClipSpaceY::Down => y_down(matrix),
+            };
+            let mut description = SceneDescription::new(size(32, 18), size(64, 36));
+            description.flags = flags;
+            description.camera_pan = [0.4, -0.3];
+            // Only a static wall in front of the sky is left.
+            description.objects.truncate(1);
+            description.objects[0].shape = SceneShape::Rect {
+                half_extent: [20.0, 10.0],
+            };
+            let scene = SyntheticScene::new(description.clone());
+            let frame = scene.render(3);
+            let motion = CameraMotion::new(
+                &convention(view_projection(&description, scene.camera_position(3))),
+                &convention(view_projection(&description, scene.camera_position(2))),
+                frame.jitter_offset,
+                description.render_size,
+                flags,
+                clip_space_y,
+            )
+            .unwrap();
+            let motion_vectors = motion.motion_vectors(&frame.depth);
+
+            let far_depth = motion.constants().far_depth;
+            let (mut wall_pixels, mut sky_pixels) = (0, 0);
+            for (index, (depth, (camera, scene))) in frame
+                .depth
+                .texels()
+                .iter()
+                .zip(motion_vectors.texels().iter().zip(frame.motion_vectors.texels()))
+                .enumerate()
+            {
+                if depth[0] == far_depth {
+                    sky_pixels += 1;
+                    assert!(
+                        camera[0].abs() < 1e-4 && camera[1].abs() < 1e-4,
+                        "sky at {index} with {flags:?} {clip_space_y:?}"
+                    );
+                } else {
+                    wall_pixels += 1;
+                    for (camera, scene) in camera[..2].iter().zip(&scene[..2]) {
+                        assert!(
+                            (camera - scene).abs() < 2e-3,
+                            "{camera} != {scene} at {index} with {flags:?} {clip_space_y:?}"
+                        );
+                    }
+                }
+            }
+            // Infinite projections put the scene's far plane in front of the far depth.
+            let infinite = flags.contains(FfxFsr2InitializationFlagBits::ENABLE_DEPTH_INFINITE);
+            assert!(wall_pixels > 0 && (sky_pixels > 0) != infinite);
+        }
+    }
+
+    #[test]
+    fn sky_follows_rotation() {
+        let description = SceneDescription::new(size(16, 16), size(32, 32));
+        let matrix = view_projection(&description, [0.0, 0.0]);
+        // Yaw the previous camera, moving the sky by the same angle at the centre of the view.
+        let angle = 0.05f32;
+        let yaw = [
+            [angle.cos(), 0.0, angle.sin(), 0.0],
+            [0.0, 1.0, 0.0, 0.0],
+            [-angle.sin(), 0.0, angle.cos(), 0.0],
+            [10.0, 0.0, 0.0, 1.0],
+        ];
+        let previous = multiply(&to_f64(&matrix), &to_f64(&yaw)).map(|column| column.map(|value| value as f32));
+        let jitter = FfxFloatCoords2D { x: 0.5, y: 0.5 };
+        let flags = FfxFsr2InitializationFlagBits::empty();
+        let motion = CameraMotion::new(&matrix, &previous, jitter, size(16, 16), flags, ClipSpaceY::Up).unwrap();
+        let [x, y] = motion.motion_vector(8, 8, 1.0);
+        let x_scale = 1.0 / (description.camera_vertical_fov * 0.5).tan();
+        let expected = -angle.tan() * x_scale * 8.0;
+        assert!(
+            (x - expected).abs() < 1e-3 && y.abs() < 1e-4,
+            "{x} {y}, expected {expected}"
+        );
+    }
+
+    #[test]
+    fn projection_parameters() {
+        for flags in depth_flags() {
+            let mut description = SceneDescription::new(size(16, 9), size(32, 18));
+            description.flags = flags;
+            let projection = view_projection(&description, [0.0, 0.0]);
+            let right_handed = {
+                let mut matrix = projection;
+                matrix[2] = matrix[2].map(|value| -value);
+                matrix
+            };
+            let gl = projection.map(|column| [column[0], column[1], column[2] * 2.0 - column[3], column[3]]);
+            let scaled = projection.map(|column| column.map(|value| value * 2.0));
+            for (matrix, depth_range) in [
+                (projection, DepthRange::ZeroToOne),
+                (right_handed, DepthRange::ZeroToOne),
+                (gl, DepthRange::MinusOneToOne),
+                (scaled, DepthRange::ZeroToOne),
+            ] {
+                let parameters = CameraParameters::from_projection(&matrix, depth_range).unwrap();
+                assert_eq!(parameters.flags.bits(), flags.bits());
+                assert!((parameters.camera_near - 0.1).abs() < 1e-5, "{parameters:?}");
+                assert!((parameters.camera_vertical_fov - 60f32.to_radians()).abs() < 1e-5);
+                if flags.contains(FfxFsr2InitializationFlagBits::ENABLE_DEPTH_INFINITE) {
+                    assert_eq!(parameters.camera_far, f32::MAX);
+                } else {
+                    assert!((parameters.camera_far - 100.0).abs() < 1e-2, "{parameters:?}");
+                }
+            }
+        }
+
+        // Infinite projections with an epsilon keep the far depth slightly inside the range.
+        let mut projection = view_projection(&SceneDescription::new(size(16, 9), size(32, 18)), [0.0, 0.0]);
+        projection[2][2] = 1.0 - 1e-6;
+        let parameters = CameraParameters::from_projection(&projection, DepthRange::ZeroToOne).unwrap();
+        assert_eq!(
+            parameters.flags.bits(),
+            FfxFsr2InitializationFlagBits::ENABLE_DEPTH_INFINITE.bits()
+        );
+    }
+
+    #[test]
+    fn invalid_projections() {
+        let orthographic = [
+            [0.1, 0.0, 0.0, 0.0],
+            [0.0, 0.1, 0.0, 0.0],
+            [0.0, 0.0, 0.01, 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ];
+        let result = CameraParameters::from_projection(&orthographic, DepthRange::ZeroToOne);
+        assert!(matches!(result, Err(ProjectionError::Orthographic)));
+
+        let projection = view_projection(&SceneDescription::new(size(16, 9), size(32, 18)), [0.0, 0.0]);
+        let mut behind = projection;
+        behind[3][2] = -behind[3][2];
+        let mut not_a_number = projection;
+        not_a_number[0][0] = f32::NAN;
+        let mut skewed_w = projection;
+        skewed_w[0][3] = 0.5;
+        for matrix in [[[0.0; 4]; 4], behind, not_a_number, skewed_w] {
+            let result = CameraParameters::from_projection(&matrix, DepthRange::ZeroToOne);
+            assert!(matches!(result, Err(ProjectionError::Malformed)), "{matrix:?}");
+        }
+    }
+}
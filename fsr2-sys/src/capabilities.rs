@@ -0,0 +1,355 @@
+use crate::{
+    FfxDevice, FfxDeviceCapabilities, FfxErrorCode, FfxFsr2CreatePipelineFunc, FfxFsr2DestroyBackendContextFunc,
+    FfxFsr2GetDeviceCapabilitiesFunc, FfxFsr2InitializationFlagBits, FfxFsr2Interface, FfxFsr2Pass,
+    FfxPipelineDescription, FfxPipelineState, FfxShaderModel,
+};
+use bitflags::bitflags;
+use std::fmt;
+use crate::intercept::{lock, InterceptRegistry};
+use std::sync::{Arc, Mutex};
+
+bitflags! {
+    /// Shader permutation options, `FSR2_SHADER_PERMUTATION_*` in the FSR2 backends.
+    #[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+    #[repr(transparent)]
+    pub struct Fsr2ShaderPermutation: u32 {
+        const REPROJECT_USE_LANCZOS_TYPE = 1 << 0;
+        const HDR_COLOR_INPUT = 1 << 1;
+        const LOW_RES_MOTION_VECTORS = 1 << 2;
+        const JITTER_MOTION_VECTORS = 1 << 3;
+        const DEPTH_INVERTED = 1 << 4;
+        const ENABLE_SHARPENING = 1 << 5;
+        const FORCE_WAVE64 = 1 << 6;
+        const ALLOW_FP16 = 1 << 7;
+    }
+}
+
+/// PCI vendor id of NVIDIA, whose accumulate passes never use FP16.
+pub const FFX_VENDOR_ID_NVIDIA: u32 = 0x10DE;
+
+impl Fsr2ShaderPermutation {
+    /// Estimates the permutation the enabled backend loads in `CreatePipeline` for `pass`, by following the
+    /// selection rules of `ffx_fsr2_vk.cpp` or `ffx_fsr2_dx12.cpp`. FSR2 does not expose the permutation it
+    /// loads, so this is a re-implementation that has to be kept in sync with the vendored backends.
+    /// `vendor_id` is only needed for the FP16 workaround on NVIDIA, the backends read it from the device.
+    pub fn estimate(
+        pass: FfxFsr2Pass,
+        context_flags: FfxFsr2InitializationFlagBits,
+        capabilities: &FfxDeviceCapabilities,
+        vendor_id: Option<u32>,
+    ) -> Self {
+        let mut permutation = Self::empty();
+
+        if capabilities.wave_lane_count_min == 32 && capabilities.wave_lane_count_max == 64 {
+            permutation |= Self::REPROJECT_USE_LANCZOS_TYPE;
+            // DX12 needs shader model 6.6 for wave64. The Vulkan backend always reports shader model 5.1 and only
+            // depends on the subgroup sizes.
+            if cfg!(feature = "vk") || capabilities.minimum_supported_shader_model >= FfxShaderModel::ShaderModel_6_6 {
+                permutation |= Self::FORCE_WAVE64;
+            }
+        }
+
+        let accumulate = matches!(pass, FfxFsr2Pass::Accumulate | FfxFsr2Pass::AccumulateSharpen);
+        let fp16 = capabilities.fp16_supported
+            && pass != FfxFsr2Pass::Rcas
+            && !(accumulate && vendor_id == Some(FFX_VENDOR_ID_NVIDIA));
+        permutation.set(Self::ALLOW_FP16, fp16);
+
+        permutation.set(
+            Self::HDR_COLOR_INPUT,
+            context_flags.contains(FfxFsr2InitializationFlagBits::ENABLE_HIGH_DYNAMIC_RANGE),
+        );
+        permutation.set(
+            Self::LOW_RES_MOTION_VECTORS,
+            !context_flags.contains(FfxFsr2InitializationFlagBits::ENABLE_DISPLAY_RESOLUTION_MOTION_VECTORS),
+        );
+        permutation.set(
+            Self::JITTER_MOTION_VECTORS,
+            context_flags.contains(FfxFsr2InitializationFlagBits::ENABLE_MOTION_VECTORS_JITTER_CANCELLATION),
+        );
+        permutation.set(
+            Self::DEPTH_INVERTED,
+            context_flags.contains(FfxFsr2InitializationFlagBits::ENABLE_DEPTH_INVERTED),
+        );
+        permutation.set(Self::ENABLE_SHARPENING, pass == FfxFsr2Pass::AccumulateSharpen);
+        permutation
+    }
+}
+
+impl FfxShaderModel {
+    pub const ALL: [FfxShaderModel; 9] = [
+        FfxShaderModel::ShaderModel_5_1,
+        FfxShaderModel::ShaderModel_6_0,
+        FfxShaderModel::ShaderModel_6_1,
+        FfxShaderModel::ShaderModel_6_2,
+        FfxShaderModel::ShaderModel_6_3,
+        FfxShaderModel::ShaderModel_6_4,
+        FfxShaderModel::ShaderModel_6_5,
+        FfxShaderModel::ShaderModel_6_6,
+        FfxShaderModel::ShaderModel_6_7,
+    ];
+
+    /// Major and minor version, e.g. `(6, 6)`.
+    pub fn version(self) -> (u32, u32) {
+        match self {
+            FfxShaderModel::ShaderModel_5_1 => (5, 1),
+            model => (6, model as u32 - FfxShaderModel::ShaderModel_6_0 as u32),
+        }
+    }
+
+    pub fn from_version(major: u32, minor: u32) -> Option<Self> {
+        Self::ALL.into_iter().find(|model| model.version() == (major, minor))
+    }
+}
+
+/// A pipeline FSR2 created through an intercepted interface.
+#[derive(Copy, Clone, Debug)]
+pub struct Fsr2PipelineReport {
+    pub pass: FfxFsr2Pass,
+    pub context_flags: FfxFsr2InitializationFlagBits,
+    /// See [`Fsr2ShaderPermutation::estimate`].
+    pub estimated_permutation: Fsr2ShaderPermutation,
+}
+
+/// Capabilities and permutations recorded by a [`Fsr2CapabilityReporter`].
+#[derive(Clone, Debug, Default)]
+pub struct Fsr2CapabilityReport {
+    pub vendor_id: Option<u32>,
+    /// What the backend detected. `None` until FSR2 queried the capabilities, which happens in
+    /// `ffxFsr2ContextCreate`.
+    pub detected: Option<FfxDeviceCapabilities>,
+    pub pipelines: Vec<Fsr2PipelineReport>,
+}
+
+impl fmt::Display for Fsr2CapabilityReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.vendor_id {
+            Some(vendor_id) => writeln!(f, "vendor: {vendor_id:#06x}")?,
+            None => writeln!(f, "vendor: unknown")?,
+        }
+        match &self.detected {
+            Some(capabilities) => {
+                let (major, minor) = capabilities.minimum_supported_shader_model.version();
+                writeln!(
+                    f,
+                    "shader model: {major}.{minor}, wave lanes: {}-{}, fp16: {}, raytracing: {}",
+                    capabilities.wave_lane_count_min,
+                    capabilities.wave_lane_count_max,
+                    capabilities.fp16_supported,
+                    capabilities.raytracing_supported,
+                )?;
+            }
+            None => writeln!(f, "capabilities: not queried")?,
+        }
+        for pipeline in &self.pipelines {
+            let names: Vec<_> = pipeline.estimated_permutation.iter_names().map(|(name, _)| name).collect();
+            writeln!(f, "{:?} (estimated): {}", pipeline.pass, names.join(" | "))?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+struct ReporterState {
+    vendor_id: Option<u32>,
+    detected: Option<FfxDeviceCapabilities>,
+    pipelines: Vec<(FfxFsr2Pass, FfxFsr2InitializationFlagBits)>,
+}
+
+#[derive(Clone)]
+struct Intercept {
+    get_device_capabilities: FfxFsr2GetDeviceCapabilitiesFunc,
+    create_pipeline: FfxFsr2CreatePipelineFunc,
+    destroy_backend_context: FfxFsr2DestroyBackendContextFunc,
+    state: Arc<Mutex<ReporterState>>,
+}
+
+static INTERCEPTS: InterceptRegistry<Intercept> = InterceptRegistry::new();
+
+extern "C" fn get_device_capabilities(
+    interface: *mut FfxFsr2Interface,
+    capabilities: *mut FfxDeviceCapabilities,
+    device: FfxDevice,
+) -> FfxErrorCode {
+    let Some(intercept) = INTERCEPTS.find(interface) else {
+        return FfxErrorCode::InvalidPointer;
+    };
+    let result = (intercept.get_device_capabilities)(interface, capabilities, device);
+    if result == FfxErrorCode::Ok && !capabilities.is_null() {
+        lock(&intercept.state).detected = Some(unsafe { *capabilities });
+    }
+    result
+}
+
+extern "C" fn create_pipeline(
+    interface: *mut FfxFsr2Interface,
+    pass: FfxFsr2Pass,
+    description: *const FfxPipelineDescription,
+    pipeline: *mut FfxPipelineState,
+) -> FfxErrorCode {
+    let Some(intercept) = INTERCEPTS.find(interface) else {
+        return FfxErrorCode::InvalidPointer;
+    };
+    let result = (intercept.create_pipeline)(interface, pass, description, pipeline);
+    if result == FfxErrorCode::Ok && !description.is_null() {
+        let flags = FfxFsr2InitializationFlagBits::from_bits_retain(unsafe { (*description).context_flags } as i32);
+        lock(&intercept.state).pipelines.push((pass, flags));
+    }
+    result
+}
+
+extern "C" fn destroy_backend_context(interface: *mut FfxFsr2Interface) -> FfxErrorCode {
+    let Some(intercept) = INTERCEPTS.find(interface) else {
+        return FfxErrorCode::InvalidPointer;
+    };
+    let result = (intercept.destroy_backend_context)(interface);
+    INTERCEPTS.remove(interface);
+    result
+}
+
+/// Reads back what an intercepted [`FfxFsr2Interface`] observed. Cheap to clone, and stays readable after
+/// the context is destroyed.
+#[derive(Clone)]
+pub struct Fsr2CapabilityReporter {
+    state: Arc<Mutex<ReporterState>>,
+}
+
+impl Fsr2CapabilityReporter {
+    /// Enables the vendor specific parts of the permutation estimate, see [`Fsr2ShaderPermutation::estimate`].
+    pub fn set_vendor_id(&self, vendor_id: u32) {
+        lock(&self.state).vendor_id = Some(vendor_id);
+    }
+
+    pub fn report(&self) -> Fsr2CapabilityReport {
+        let state = lock(&self.state);
+        let pipelines = match &state.detected {
+            Some(capabilities) => state
+                .pipelines
+                .iter()
+                .map(|&(pass, context_flags)| Fsr2PipelineReport {
+                    pass,
+                    context_flags,
+                    estimated_permutation: Fsr2ShaderPermutation::estimate(
+                        pass,
+                        context_flags,
+                        capabilities,
+                        state.vendor_id,
+                    ),
+                })
+                .collect(),
+            None => Vec::new(),
+        };
+        Fsr2CapabilityReport {
+            vendor_id: state.vendor_id,
+            detected: state.detected,
+            pipelines,
+        }
+    }
+}
+
+impl FfxFsr2Interface {
+    /// Wraps `fp_get_device_capabilities` and `fp_create_pipeline` to record the detected device
+    /// capabilities and the pipelines FSR2 creates. Must be called before the interface is passed to
+    /// `ffxFsr2ContextCreate`. Intercepting an interface twice returns a reporter for the same state.
+    ///
+    /// Fails if the interface is incomplete or has no scratch buffer.
+    pub fn intercept_capabilities(&mut self) -> Result<Fsr2CapabilityReporter, FfxErrorCode> {
+        self.check_complete()?;
+        if self.scratch_buffer.is_null() {
+            return Err(FfxErrorCode::InvalidPointer);
+        }
+
+        let (intercept, inserted) = INTERCEPTS.insert_with(self, || Intercept {
+            get_device_capabilities: self.fp_get_device_capabilities.unwrap(),
+            create_pipeline: self.fp_create_pipeline.unwrap(),
+            destroy_backend_context: self.fp_destroy_backend_context.unwrap(),
+            state: Arc::new(Mutex::new(ReporterState::default())),
+        });
+        if inserted {
+            self.fp_get_device_capabilities = Some(get_device_capabilities);
+            self.fp_create_pipeline = Some(create_pipeline);
+            self.fp_destroy_backend_context = Some(destroy_backend_context);
+        }
+        Ok(Fsr2CapabilityReporter { state: intercept.state })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::intercept::fake::{fake_interface, FakeBackend};
+
+    /// Calls the interface the way `ffxFsr2ContextCreate` does, on a copy of it.
+    fn create_context(interface: &FfxFsr2Interface, flags: FfxFsr2InitializationFlagBits) {
+        let mut copy = *interface;
+        let mut capabilities: FfxDeviceCapabilities = unsafe { std::mem::zeroed() };
+        let result = (copy.fp_get_device_capabilities.unwrap())(&mut copy, &mut capabilities, std::ptr::null_mut());
+        assert_eq!(result, FfxErrorCode::Ok);
+        let description = FfxPipelineDescription {
+            context_flags: flags.bits() as u32,
+            samplers: std::ptr::null_mut(),
+            sampler_count: 0,
+            root_constant_buffer_size: std::ptr::null(),
+            root_constant_buffer_count: 0,
+        };
+        for pass in [FfxFsr2Pass::Accumulate, FfxFsr2Pass::AccumulateSharpen, FfxFsr2Pass::Rcas] {
+            let mut pipeline: FfxPipelineState = unsafe { std::mem::zeroed() };
+            let result = (copy.fp_create_pipeline.unwrap())(&mut copy, pass, &description, &mut pipeline);
+            assert_eq!(result, FfxErrorCode::Ok);
+        }
+    }
+
+    #[test]
+    fn estimate_permutation() {
+        let mut capabilities: FfxDeviceCapabilities = unsafe { std::mem::zeroed() };
+        capabilities.wave_lane_count_min = 32;
+        capabilities.wave_lane_count_max = 32;
+        capabilities.fp16_supported = true;
+        let flags = FfxFsr2InitializationFlagBits::ENABLE_DEPTH_INVERTED;
+
+        let accumulate = Fsr2ShaderPermutation::estimate(FfxFsr2Pass::AccumulateSharpen, flags, &capabilities, None);
+        assert_eq!(
+            accumulate,
+            Fsr2ShaderPermutation::ALLOW_FP16
+                | Fsr2ShaderPermutation::LOW_RES_MOTION_VECTORS
+                | Fsr2ShaderPermutation::DEPTH_INVERTED
+                | Fsr2ShaderPermutation::ENABLE_SHARPENING
+        );
+        let nvidia =
+            Fsr2ShaderPermutation::estimate(FfxFsr2Pass::Accumulate, flags, &capabilities, Some(FFX_VENDOR_ID_NVIDIA));
+        assert!(!nvidia.contains(Fsr2ShaderPermutation::ALLOW_FP16));
+        let rcas = Fsr2ShaderPermutation::estimate(FfxFsr2Pass::Rcas, flags, &capabilities, None);
+        assert!(!rcas.contains(Fsr2ShaderPermutation::ALLOW_FP16));
+
+        // GetDeviceCapabilitiesVK reports shader model 5.1 and the subgroup size range of VK_EXT_subgroup_size_control.
+        capabilities.minimum_supported_shader_model = FfxShaderModel::ShaderModel_5_1;
+        capabilities.wave_lane_count_max = 64;
+        let wave64 = Fsr2ShaderPermutation::estimate(FfxFsr2Pass::Accumulate, flags, &capabilities, None);
+        assert!(wave64.contains(Fsr2ShaderPermutation::REPROJECT_USE_LANCZOS_TYPE));
+        assert_eq!(wave64.contains(Fsr2ShaderPermutation::FORCE_WAVE64), cfg!(feature = "vk"));
+    }
+
+    #[test]
+    fn reports_capabilities_and_pipelines() {
+        let mut backend = FakeBackend::default();
+        let mut interface = fake_interface(&mut backend);
+        let reporter = interface.intercept_capabilities().unwrap();
+        assert!(reporter.report().detected.is_none());
+
+        create_context(&interface, FfxFsr2InitializationFlagBits::ENABLE_HIGH_DYNAMIC_RANGE);
+        let report = reporter.report();
+        assert_eq!(report.detected.unwrap().wave_lane_count_max, 64);
+        assert_eq!(report.pipelines.len(), 3);
+        let accumulate = report.pipelines[0].estimated_permutation;
+        assert!(accumulate.contains(Fsr2ShaderPermutation::HDR_COLOR_INPUT | Fsr2ShaderPermutation::FORCE_WAVE64));
+        assert!(report.to_string().contains("shader model: 6.6, wave lanes: 32-64"));
+
+        // Intercepting again must not wrap the wrappers.
+        let again = interface.intercept_capabilities().unwrap();
+        assert_eq!(again.report().pipelines.len(), 3);
+
+        (interface.fp_destroy_backend_context.unwrap())(&mut interface);
+        assert!(INTERCEPTS.find(&interface).is_none());
+        assert_eq!(reporter.report().pipelines.len(), 3);
+    }
+}
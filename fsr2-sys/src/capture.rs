@@ -0,0 +1,864 @@
+use crate::intercept::{lock, InterceptRegistry};
+use crate::{
+    DecodedPipelineState, FfxCommandList, FfxComputeJobDescription, FfxCreateResourceDescription,
+    FfxErrorCode, FfxFilterType, FfxFsr2CreatePipelineFunc, FfxFsr2CreateResourceFunc,
+    FfxFsr2DestroyBackendContextFunc, FfxFsr2ExecuteGpuJobsFunc, FfxFsr2Interface, FfxFsr2Pass,
+    FfxFsr2ScheduleGpuJobFunc, FfxFsr2UnregisterResourcesFunc, FfxGpuJob, FfxGpuJobDescription, FfxHeapType,
+    FfxJobType, FfxPipelineDescription, FfxPipelineState, FfxResource, FfxResourceDescription, FfxResourceInternal,
+    FfxResourceState, FfxResourceUsage, FFxFsr2RegisterResourceFunc, ResourceName, FFX_MAX_CONST_SIZE,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{Read, Write};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+/// Identifies capture files, stored in [`Fsr2Capture::format`].
+pub const FSR2_CAPTURE_FORMAT: &str = "fsr2-capture";
+/// Version of the capture format written by this crate. Captures of older versions can still be read.
+pub const FSR2_CAPTURE_VERSION: u32 = 1;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CapturedResource {
+    pub heap_type: FfxHeapType,
+    pub description: FfxResourceDescription,
+    pub initial_state: FfxResourceState,
+    pub usage: FfxResourceUsage,
+    pub name: String,
+    pub id: u32,
+    pub init_data: Vec<u8>,
+    /// The internal index the backend assigned, referenced by later jobs.
+    pub resource: i32,
+}
+
+/// A resource FSR2 registered for a dispatch. Only its description is captured, [`Fsr2Capture::replay_with`] binds
+/// the actual resource.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CapturedRegisteredResource {
+    pub description: FfxResourceDescription,
+    pub state: FfxResourceState,
+    pub is_depth: bool,
+    pub name: String,
+    pub resource: i32,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CapturedPipeline {
+    pub pass: FfxFsr2Pass,
+    pub context_flags: u32,
+    pub samplers: Vec<FfxFilterType>,
+    pub root_constant_buffer_sizes: Vec<u32>,
+    /// The bindings the backend reported, informational only.
+    pub bindings: DecodedPipelineState,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CapturedSlot {
+    pub resource: i32,
+    pub name: String,
+    /// Only set for UAVs.
+    pub mip: Option<u32>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CapturedConstantBuffer {
+    pub slot_index: u32,
+    pub name: String,
+    pub data: Vec<u32>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CapturedJob {
+    ClearFloat {
+        target: i32,
+        color: [f32; 4],
+    },
+    Copy {
+        src: i32,
+        dst: i32,
+    },
+    Compute {
+        /// Index of the pipeline in the order of the capture's `CreatePipeline` events, `None` if the job
+        /// used a pipeline that was created before the capture started.
+        pipeline: Option<usize>,
+        dimensions: [u32; 3],
+        srvs: Vec<CapturedSlot>,
+        uavs: Vec<CapturedSlot>,
+        cbs: Vec<CapturedConstantBuffer>,
+    },
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "event", content = "data", rename_all = "snake_case")]
+pub enum CaptureEvent {
+    CreateResource(CapturedResource),
+    RegisterResource(CapturedRegisteredResource),
+    UnregisterResources,
+    CreatePipeline(CapturedPipeline),
+    ScheduleGpuJob(CapturedJob),
+    /// Ends a frame.
+    ExecuteGpuJobs,
+}
+
+/// A recorded job stream, stored as JSON.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Fsr2Capture {
+    pub format: String,
+    pub version: u32,
+    pub events: Vec<CaptureEvent>,
+}
+
+#[derive(Debug)]
+pub enum CaptureError {
+    Io(std::io::Error),
+    Json(serde_json::Error),
+    UnknownFormat(String),
+    UnsupportedVersion(u32),
+}
+
+impl fmt::Display for CaptureError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CaptureError::Io(err) => write!(f, "failed to access capture: {err}"),
+            CaptureError::Json(err) => write!(f, "malformed capture: {err}"),
+            CaptureError::UnknownFormat(format) => write!(f, "not an FSR2 capture: {format:?}"),
+            CaptureError::UnsupportedVersion(version) => write!(
+                f,
+                "capture version {version} is newer than the supported version {FSR2_CAPTURE_VERSION}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for CaptureError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CaptureError::Io(err) => Some(err),
+            CaptureError::Json(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for CaptureError {
+    fn from(err: std::io::Error) -> Self {
+        CaptureError::Io(err)
+    }
+}
+
+impl From<serde_json::Error> for CaptureError {
+    fn from(err: serde_json::Error) -> Self {
+        CaptureError::Json(err)
+    }
+}
+
+impl Default for Fsr2Capture {
+    fn default() -> Self {
+        Self {
+            format: FSR2_CAPTURE_FORMAT.into(),
+            version: FSR2_CAPTURE_VERSION,
+            events: Vec::new(),
+        }
+    }
+}
+
+impl Fsr2Capture {
+    pub fn frame_count(&self) -> usize {
+        self.events
+            .iter()
+            .filter(|event| matches!(event, CaptureEvent::ExecuteGpuJobs))
+            .count()
+    }
+
+    pub fn write_to(&self, writer: impl Write) -> Result<(), CaptureError> {
+        serde_json::to_writer_pretty(writer, self)?;
+        Ok(())
+    }
+
+    pub fn read_from(reader: impl Read) -> Result<Self, CaptureError> {
+        #[derive(Deserialize)]
+        struct Header {
+            format: String,
+            version: u32,
+        }
+
+        let value: serde_json::Value = serde_json::from_reader(reader)?;
+        let header = Header::deserialize(&value)?;
+        if header.format != FSR2_CAPTURE_FORMAT {
+            return Err(CaptureError::UnknownFormat(header.format));
+        }
+        if header.version > FSR2_CAPTURE_VERSION {
+            return Err(CaptureError::UnsupportedVersion(header.version));
+        }
+        Ok(Self::deserialize(value)?)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), CaptureError> {
+        let file = std::fs::File::create(path)?;
+        self.write_to(std::io::BufWriter::new(file))
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, CaptureError> {
+        let file = std::fs::File::open(path)?;
+        Self::read_from(std::io::BufReader::new(file))
+    }
+
+    /// Issues every captured call on `interface`, registering every resource with a null resource pointer.
+    /// Only the CPU backend and mock interfaces accept those, the GPU backends need [`Self::replay_with`].
+    ///
+    /// # Safety
+    /// See [`Self::replay_with`].
+    pub unsafe fn replay(
+        &self,
+        interface: &mut FfxFsr2Interface,
+        command_list: FfxCommandList,
+    ) -> Result<Fsr2Replay, FfxErrorCode> {
+        self.replay_with(interface, command_list, |_, resource| {
+            let mut ffx_resource =
+                FfxResource::new(std::ptr::null_mut(), &resource.name, resource.description, resource.state);
+            ffx_resource.is_depth = resource.is_depth;
+            ffx_resource
+        })
+    }
+
+    /// Issues every captured call on `interface`. The backend context must have been created. `bind` supplies
+    /// the resource to register for each captured registration, given the index of the frame it belongs to.
+    ///
+    /// # Safety
+    /// `interface` must be a valid interface whose backend context is alive, `command_list` must be valid
+    /// for its `fp_execute_gpu_jobs`, and the resources `bind` returns must be valid for the backend.
+    pub unsafe fn replay_with(
+        &self,
+        interface: &mut FfxFsr2Interface,
+        command_list: FfxCommandList,
+        mut bind: impl FnMut(usize, &CapturedRegisteredResource) -> FfxResource,
+    ) -> Result<Fsr2Replay, FfxErrorCode> {
+        interface.check_complete()?;
+        let mut replay = Fsr2Replay::default();
+        for event in &self.events {
+            match event {
+                CaptureEvent::CreateResource(resource) => {
+                    let name = ResourceName::new(&resource.name);
+                    let mut init_data = resource.init_data.clone();
+                    let description = FfxCreateResourceDescription {
+                        heap_type: resource.heap_type,
+                        resource_description: resource.description,
+                        initial_state: resource.initial_state,
+                        init_data_size: init_data.len() as u32,
+                        init_data: if init_data.is_empty() {
+                            std::ptr::null_mut()
+                        } else {
+                            init_data.as_mut_ptr().cast()
+                        },
+                        name: name.as_ptr(),
+                        usage: resource.usage,
+                        id: resource.id,
+                    };
+                    let mut internal = FfxResourceInternal { internal_index: -1 };
+                    check((interface.fp_create_resource.unwrap())(interface, &description, &mut internal))?;
+                    replay.resources.insert(resource.resource, internal);
+                    replay.created.push(internal);
+                }
+                CaptureEvent::RegisterResource(resource) => {
+                    let ffx_resource = bind(replay.frames, resource);
+                    let mut internal = FfxResourceInternal { internal_index: -1 };
+                    check((interface.fp_register_resource.unwrap())(interface, &ffx_resource, &mut internal))?;
+                    replay.resources.insert(resource.resource, internal);
+                }
+                CaptureEvent::UnregisterResources => {
+                    check((interface.fp_unregister_resources.unwrap())(interface))?;
+                }
+                CaptureEvent::CreatePipeline(pipeline) => {
+                    let mut samplers = pipeline.samplers.clone();
+                    let description = FfxPipelineDescription {
+                        context_flags: pipeline.context_flags,
+                        samplers: samplers.as_mut_ptr(),
+                        sampler_count: samplers.len(),
+                        root_constant_buffer_size: pipeline.root_constant_buffer_sizes.as_ptr(),
+                        root_constant_buffer_count: pipeline.root_constant_buffer_sizes.len() as u32,
+                    };
+                    let mut state: FfxPipelineState = std::mem::zeroed();
+                    check((interface.fp_create_pipeline.unwrap())(
+                        interface,
+                        pipeline.pass,
+                        &description,
+                        &mut state,
+                    ))?;
+                    replay.pipelines.push(state);
+                }
+                CaptureEvent::ScheduleGpuJob(job) => {
+                    let job = replay.job(job)?;
+                    check((interface.fp_schedule_gpu_job.unwrap())(interface, &job))?;
+                }
+                CaptureEvent::ExecuteGpuJobs => {
+                    check((interface.fp_execute_gpu_jobs.unwrap())(interface, command_list))?;
+                    replay.frames += 1;
+                }
+            }
+        }
+        Ok(replay)
+    }
+}
+
+fn check(result: FfxErrorCode) -> Result<(), FfxErrorCode> {
+    match result {
+        FfxErrorCode::Ok => Ok(()),
+        err => Err(err),
+    }
+}
+
+/// The objects a replay created on the target interface.
+#[derive(Default)]
+pub struct Fsr2Replay {
+    resources: HashMap<i32, FfxResourceInternal>,
+    created: Vec<FfxResourceInternal>,
+    pipelines: Vec<FfxPipelineState>,
+    frames: usize,
+}
+
+impl Fsr2Replay {
+    /// The replayed counterpart of a resource index of the capture.
+    pub fn resource(&self, captured: i32) -> Option<FfxResourceInternal> {
+        self.resources.get(&captured).copied()
+    }
+
+    pub fn pipelines(&self) -> &[FfxPipelineState] {
+        &self.pipelines
+    }
+
+    pub fn frames(&self) -> usize {
+        self.frames
+    }
+
+    fn map(&self, captured: i32) -> FfxResourceInternal {
+        // Indices that were never created nor registered, like FSR2's null resource, are passed through.
+        self.resource(captured).unwrap_or(FfxResourceInternal { internal_index: captured })
+    }
+
+    fn job(&self, job: &CapturedJob) -> Result<FfxGpuJobDescription, FfxErrorCode> {
+        Ok(match job {
+            CapturedJob::ClearFloat { target, color } => FfxGpuJobDescription {
+                ty: FfxJobType::ClearFloat,
+                descriptor: FfxGpuJob {
+                    clear_job: crate::FfxClearFloatJobDescription {
+                        color: *color,
+                        target: self.map(*target),
+                    },
+                },
+            },
+            CapturedJob::Copy { src, dst } => FfxGpuJobDescription {
+                ty: FfxJobType::Copy,
+                descriptor: FfxGpuJob {
+                    copy_job: crate::FfxCopyJobDescription {
+                        src: self.map(*src),
+                        dst: self.map(*dst),
+                    },
+                },
+            },
+            CapturedJob::Compute {
+                pipeline,
+                dimensions,
+                srvs,
+                uavs,
+                cbs,
+            } => {
+                let pipeline = pipeline
+                    .and_then(|pipeline| self.pipelines.get(pipeline))
+                    .ok_or(FfxErrorCode::InvalidArgument)?;
+                // SAFETY: Plain data and null pointers.
+                let mut compute: FfxComputeJobDescription = unsafe { std::mem::zeroed() };
+                if srvs.len() > compute.srvs.len() || uavs.len() > compute.uavs.len() || cbs.len() > compute.cbs.len() {
+                    return Err(FfxErrorCode::OutOfRange);
+                }
+                compute.pipeline = *pipeline;
+                compute.dimensions = *dimensions;
+                for (i, srv) in srvs.iter().enumerate() {
+                    compute.srvs[i] = self.map(srv.resource);
+                    compute.srv_names[i] = ResourceName::new(&srv.name).into_raw();
+                }
+                for (i, uav) in uavs.iter().enumerate() {
+                    compute.uavs[i] = self.map(uav.resource);
+                    compute.uav_mip[i] = uav.mip.unwrap_or(0);
+                    compute.uav_names[i] = ResourceName::new(&uav.name).into_raw();
+                }
+                for (i, cb) in cbs.iter().enumerate() {
+                    if cb.data.len() > FFX_MAX_CONST_SIZE {
+                        return Err(FfxErrorCode::OutOfRange);
+                    }
+                    compute.cbs[i].uint32_size = cb.data.len() as u32;
+                    compute.cbs[i].data[..cb.data.len()].copy_from_slice(&cb.data);
+                    compute.cb_names[i] = ResourceName::new(&cb.name).into_raw();
+                    compute.cb_slot_index[i] = cb.slot_index;
+                }
+                FfxGpuJobDescription {
+                    ty: FfxJobType::Compute,
+                    descriptor: FfxGpuJob { compute_job: compute },
+                }
+            }
+        })
+    }
+
+    /// Destroys the pipelines and resources the replay created.
+    ///
+    /// # Safety
+    /// `interface` must be the interface the replay ran on, and its backend context must still be alive.
+    pub unsafe fn destroy(mut self, interface: &mut FfxFsr2Interface) -> Result<(), FfxErrorCode> {
+        for pipeline in &mut self.pipelines {
+            check((interface.fp_destroy_pipeline.unwrap())(interface, pipeline))?;
+        }
+        for resource in self.created {
+            check((interface.fp_destroy_resource.unwrap())(interface, resource))?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+struct RecorderState {
+    events: Vec<CaptureEvent>,
+    /// `pipeline` and `root_signature` of every created pipeline, and its bindings, to identify the pipeline
+    /// of compute jobs.
+    pipelines: Vec<(usize, usize, DecodedPipelineState)>,
+}
+
+#[derive(Clone)]
+struct Intercept {
+    create_resource: FfxFsr2CreateResourceFunc,
+    register_resource: FFxFsr2RegisterResourceFunc,
+    unregister_resources: FfxFsr2UnregisterResourcesFunc,
+    create_pipeline: FfxFsr2CreatePipelineFunc,
+    schedule_gpu_job: FfxFsr2ScheduleGpuJobFunc,
+    execute_gpu_jobs: FfxFsr2ExecuteGpuJobsFunc,
+    destroy_backend_context: FfxFsr2DestroyBackendContextFunc,
+    state: Arc<Mutex<RecorderState>>,
+}
+
+static INTERCEPTS: InterceptRegistry<Intercept> = InterceptRegistry::new();
+
+unsafe fn slice<'a, T>(data: *const T, len: usize) -> &'a [T] {
+    if data.is_null() || len == 0 {
+        &[]
+    } else {
+        std::slice::from_raw_parts(data, len)
+    }
+}
+
+extern "C" fn create_resource(
+    interface: *mut FfxFsr2Interface,
+    description: *const FfxCreateResourceDescription,
+    resource: *mut FfxResourceInternal,
+) -> FfxErrorCode {
+    let Some(intercept) = INTERCEPTS.find(interface) else {
+        return FfxErrorCode::InvalidPointer;
+    };
+    let result = (intercept.create_resource)(interface, description, resource);
+    if result == FfxErrorCode::Ok {
+        let (description, resource) = unsafe { (&*description, *resource) };
+        let init_data = unsafe { slice(description.init_data as *const u8, description.init_data_size as usize) };
+        lock(&intercept.state).events.push(CaptureEvent::CreateResource(CapturedResource {
+            heap_type: description.heap_type,
+            description: description.resource_description,
+            initial_state: description.initial_state,
+            usage: description.usage,
+            name: unsafe { description.name() }.unwrap_or_default(),
+            id: description.id,
+            init_data: init_data.to_vec(),
+            resource: resource.internal_index,
+        }));
+    }
+    result
+}
+
+extern "C" fn register_resource(
+    interface: *mut FfxFsr2Interface,
+    resource: *const FfxResource,
+    internal: *mut FfxResourceInternal,
+) -> FfxErrorCode {
+    let Some(intercept) = INTERCEPTS.find(interface) else {
+        return FfxErrorCode::InvalidPointer;
+    };
+    let result = (intercept.register_resource)(interface, resource, internal);
+    if result == FfxErrorCode::Ok {
+        let (resource, internal) = unsafe { (&*resource, *internal) };
+        lock(&intercept.state)
+            .events
+            .push(CaptureEvent::RegisterResource(CapturedRegisteredResource {
+                description: resource.description,
+                state: resource.state,
+                is_depth: resource.is_depth,
+                name: resource.name().to_string_lossy(),
+                resource: internal.internal_index,
+            }));
+    }
+    result
+}
+
+extern "C" fn unregister_resources(interface: *mut FfxFsr2Interface) -> FfxErrorCode {
+    let Some(intercept) = INTERCEPTS.find(interface) else {
+        return FfxErrorCode::InvalidPointer;
+    };
+    let result = (intercept.unregister_resources)(interface);
+    if result == FfxErrorCode::Ok {
+        lock(&intercept.state).events.push(CaptureEvent::UnregisterResources);
+    }
+    result
+}
+
+extern "C" fn create_pipeline(
+    interface: *mut FfxFsr2Interface,
+    pass: FfxFsr2Pass,
+    description: *const FfxPipelineDescription,
+    pipeline: *mut FfxPipelineState,
+) -> FfxErrorCode {
+    let Some(intercept) = INTERCEPTS.find(interface) else {
+        return FfxErrorCode::InvalidPointer;
+    };
+    let result = (intercept.create_pipeline)(interface, pass, description, pipeline);
+    if result == FfxErrorCode::Ok {
+        let (description, pipeline) = unsafe { (&*description, &*pipeline) };
+        let bindings = pipeline.decode();
+        let mut state = lock(&intercept.state);
+        state.pipelines.push((
+            pipeline.pipeline as usize,
+            pipeline.root_signature as usize,
+            bindings.clone(),
+        ));
+        state.events.push(CaptureEvent::CreatePipeline(CapturedPipeline {
+            pass,
+            context_flags: description.context_flags,
+            samplers: unsafe { slice(description.samplers, description.sampler_count) }.to_vec(),
+            root_constant_buffer_sizes: unsafe {
+                slice(
+                    description.root_constant_buffer_size,
+                    description.root_constant_buffer_count as usize,
+                )
+            }
+            .to_vec(),
+            bindings,
+        }));
+    }
+    result
+}
+
+fn capture_job(state: &RecorderState, job: &FfxGpuJobDescription) -> CapturedJob {
+    match job.ty {
+        FfxJobType::ClearFloat => {
+            let clear = unsafe { &job.descriptor.clear_job };
+            CapturedJob::ClearFloat {
+                target: clear.target.internal_index,
+                color: clear.color,
+            }
+        }
+        FfxJobType::Copy => {
+            let copy = unsafe { &job.descriptor.copy_job };
+            CapturedJob::Copy {
+                src: copy.src.internal_index,
+                dst: copy.dst.internal_index,
+            }
+        }
+        FfxJobType::Compute => {
+            let compute = unsafe { &job.descriptor.compute_job };
+            let key = (
+                compute.pipeline.pipeline as usize,
+                compute.pipeline.root_signature as usize,
+                compute.pipeline.decode(),
+            );
+            let pipeline = state.pipelines.iter().rposition(|pipeline| *pipeline == key);
+            let slot = |resource: FfxResourceInternal, name, mip| CapturedSlot {
+                resource: resource.internal_index,
+                name: ResourceName::from_raw(name).to_string_lossy(),
+                mip,
+            };
+            let srv_count = (compute.pipeline.srv_count as usize).min(compute.srvs.len());
+            let uav_count = (compute.pipeline.uav_count as usize).min(compute.uavs.len());
+            let cb_count = (compute.pipeline.const_count as usize).min(compute.cbs.len());
+            CapturedJob::Compute {
+                pipeline,
+                dimensions: compute.dimensions,
+                srvs: (0..srv_count)
+                    .map(|i| slot(compute.srvs[i], compute.srv_names[i], None))
+                    .collect(),
+                uavs: (0..uav_count)
+                    .map(|i| slot(compute.uavs[i], compute.uav_names[i], Some(compute.uav_mip[i])))
+                    .collect(),
+                cbs: (0..cb_count)
+                    .map(|i| CapturedConstantBuffer {
+                        slot_index: compute.cb_slot_index[i],
+                        name: ResourceName::from_raw(compute.cb_names[i]).to_string_lossy(),
+                        data: compute.cbs[i].words().to_vec(),
+                    })
+                    .collect(),
+            }
+        }
+    }
+}
+
+extern "C" fn schedule_gpu_job(interface: *mut FfxFsr2Interface, job: *const FfxGpuJobDescription) -> FfxErrorCode {
+    let Some(intercept) = INTERCEPTS.find(interface) else {
+        return FfxErrorCode::InvalidPointer;
+    };
+    let result = (intercept.schedule_gpu_job)(interface, job);
+    if result == FfxErrorCode::Ok {
+        let mut state = lock(&intercept.state);
+        let job = capture_job(&state, unsafe { &*job });
+        state.events.push(CaptureEvent::ScheduleGpuJob(job));
+    }
+    result
+}
+
+extern "C" fn execute_gpu_jobs(interface: *mut FfxFsr2Interface, command_list: FfxCommandList) -> FfxErrorCode {
+    let Some(intercept) = INTERCEPTS.find(interface) else {
+        return FfxErrorCode::InvalidPointer;
+    };
+    let result = (intercept.execute_gpu_jobs)(interface, command_list);
+    if result == FfxErrorCode::Ok {
+        lock(&intercept.state).events.push(CaptureEvent::ExecuteGpuJobs);
+    }
+    result
+}
+
+extern "C" fn destroy_backend_context(interface: *mut FfxFsr2Interface) -> FfxErrorCode {
+    let Some(intercept) = INTERCEPTS.find(interface) else {
+        return FfxErrorCode::InvalidPointer;
+    };
+    let result = (intercept.destroy_backend_context)(interface);
+    INTERCEPTS.remove(interface);
+    result
+}
+
+/// Reads back the job stream recorded by an intercepted [`FfxFsr2Interface`].
+#[derive(Clone)]
+pub struct Fsr2CaptureRecorder {
+    state: Arc<Mutex<RecorderState>>,
+}
+
+impl Fsr2CaptureRecorder {
+    /// Everything recorded so far.
+    pub fn capture(&self) -> Fsr2Capture {
+        Fsr2Capture {
+            events: lock(&self.state).events.clone(),
+            ..Default::default()
+        }
+    }
+
+    /// Drops the recorded jobs and frames, but keeps the resources and pipelines they refer to.
+    pub fn clear_frames(&self) {
+        lock(&self.state).events.retain(|event| {
+            matches!(event, CaptureEvent::CreateResource(_) | CaptureEvent::CreatePipeline(_))
+        });
+    }
+}
+
+impl FfxFsr2Interface {
+    /// Wraps the resource, pipeline and job callbacks to record every call into a [`Fsr2Capture`]. Must be
+    /// called before the interface is passed to `ffxFsr2ContextCreate`, so the resources and pipelines FSR2
+    /// creates are part of the capture.
+    pub fn intercept_capture(&mut self) -> Result<Fsr2CaptureRecorder, FfxErrorCode> {
+        self.check_complete()?;
+        if self.scratch_buffer.is_null() {
+            return Err(FfxErrorCode::InvalidPointer);
+        }
+
+        let (intercept, inserted) = INTERCEPTS.insert_with(self, || Intercept {
+            create_resource: self.fp_create_resource.unwrap(),
+            register_resource: self.fp_register_resource.unwrap(),
+            unregister_resources: self.fp_unregister_resources.unwrap(),
+            create_pipeline: self.fp_create_pipeline.unwrap(),
+            schedule_gpu_job: self.fp_schedule_gpu_job.unwrap(),
+            execute_gpu_jobs: self.fp_execute_gpu_jobs.unwrap(),
+            destroy_backend_context: self.fp_destroy_backend_context.unwrap(),
+            state: Arc::new(Mutex::new(RecorderState::default())),
+        });
+        if inserted {
+            self.fp_create_resource = Some(create_resource);
+            self.fp_register_resource = Some(register_resource);
+            self.fp_unregister_resources = Some(unregister_resources);
+            self.fp_create_pipeline = Some(create_pipeline);
+            self.fp_schedule_gpu_job = Some(schedule_gpu_job);
+            self.fp_execute_gpu_jobs = Some(execute_gpu_jobs);
+            self.fp_destroy_backend_context = Some(destroy_backend_context);
+        }
+        Ok(Fsr2CaptureRecorder { state: intercept.state })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::intercept::fake::{fake_interface, FakeBackend};
+    use crate::{FfxConstantBuffer, FfxResourceFlags, FfxResourceType, FfxSurfaceFormat, Fsr2Constants};
+
+    fn description(width: u32, height: u32) -> FfxResourceDescription {
+        FfxResourceDescription {
+            ty: FfxResourceType::Texture2D,
+            surface_format: FfxSurfaceFormat::RGBA16Float,
+            width,
+            height,
+            depth: 1,
+            mip_count: 1,
+            flags: FfxResourceFlags::NONE,
+        }
+    }
+
+    /// Makes the calls FSR2 makes for context creation and two dispatches, on a copy of the interface.
+    fn run_frames(interface: &FfxFsr2Interface) {
+        let mut interface = *interface;
+        let interface = &mut interface;
+
+        let name = ResourceName::new("FSR2_LanczosLutData");
+        let mut lut = [1u8, 2, 3, 4];
+        let create = FfxCreateResourceDescription {
+            heap_type: FfxHeapType::Default,
+            resource_description: description(128, 1),
+            initial_state: FfxResourceState::COMPUTE_READ,
+            init_data_size: lut.len() as u32,
+            init_data: lut.as_mut_ptr().cast(),
+            name: name.as_ptr(),
+            usage: FfxResourceUsage::READ_ONLY,
+            id: 4,
+        };
+        let mut lut_resource = FfxResourceInternal { internal_index: -1 };
+        let result = (interface.fp_create_resource.unwrap())(interface, &create, &mut lut_resource);
+        assert_eq!(result, FfxErrorCode::Ok);
+
+        let mut samplers = [FfxFilterType::Point, FfxFilterType::Linear];
+        let sizes = [31u32];
+        let pipeline_description = FfxPipelineDescription {
+            context_flags: 0,
+            samplers: samplers.as_mut_ptr(),
+            sampler_count: samplers.len(),
+            root_constant_buffer_size: sizes.as_ptr(),
+            root_constant_buffer_count: 1,
+        };
+        let mut pipelines: [FfxPipelineState; 2] = unsafe { std::mem::zeroed() };
+        for (pass, pipeline) in [FfxFsr2Pass::Accumulate, FfxFsr2Pass::Rcas].into_iter().zip(&mut pipelines) {
+            let result = (interface.fp_create_pipeline.unwrap())(interface, pass, &pipeline_description, pipeline);
+            assert_eq!(result, FfxErrorCode::Ok);
+        }
+
+        for frame in 0..2 {
+            let color = FfxResource::new(
+                std::ptr::null_mut(),
+                "Color",
+                description(1280, 720),
+                FfxResourceState::COMPUTE_READ,
+            );
+            let mut color_resource = FfxResourceInternal { internal_index: -1 };
+            let result = (interface.fp_register_resource.unwrap())(interface, &color, &mut color_resource);
+            assert_eq!(result, FfxErrorCode::Ok);
+
+            let clear = FfxGpuJobDescription {
+                ty: FfxJobType::ClearFloat,
+                descriptor: FfxGpuJob {
+                    clear_job: crate::FfxClearFloatJobDescription {
+                        color: [0.0, 0.5, 1.0, 1.0],
+                        target: lut_resource,
+                    },
+                },
+            };
+            assert_eq!((interface.fp_schedule_gpu_job.unwrap())(interface, &clear), FfxErrorCode::Ok);
+
+            let mut compute: FfxComputeJobDescription = unsafe { std::mem::zeroed() };
+            compute.pipeline = pipelines[frame % 2];
+            compute.dimensions = [160, 90, 1];
+            compute.srvs[0] = color_resource;
+            compute.cbs[0] = FfxConstantBuffer::new(&Fsr2Constants {
+                frame_index: frame as i32,
+                ..Default::default()
+            });
+            let job = FfxGpuJobDescription {
+                ty: FfxJobType::Compute,
+                descriptor: FfxGpuJob { compute_job: compute },
+            };
+            assert_eq!((interface.fp_schedule_gpu_job.unwrap())(interface, &job), FfxErrorCode::Ok);
+
+            let result = (interface.fp_execute_gpu_jobs.unwrap())(interface, std::ptr::null_mut());
+            assert_eq!(result, FfxErrorCode::Ok);
+            assert_eq!((interface.fp_unregister_resources.unwrap())(interface), FfxErrorCode::Ok);
+        }
+    }
+
+    #[test]
+    fn capture_and_replay() {
+        let mut backend = FakeBackend::default();
+        let mut interface = fake_interface(&mut backend);
+        let recorder = interface.intercept_capture().unwrap();
+        run_frames(&interface);
+
+        let capture = recorder.capture();
+        assert_eq!(capture.frame_count(), 2);
+        let mut file = Vec::new();
+        capture.write_to(&mut file).unwrap();
+        let capture = Fsr2Capture::read_from(file.as_slice()).unwrap();
+
+        let mut replay_backend = FakeBackend::default();
+        let mut replay_interface = fake_interface(&mut replay_backend);
+        let replay = unsafe { capture.replay(&mut replay_interface, std::ptr::null_mut()) }.unwrap();
+        assert_eq!(replay.frames(), 2);
+        assert_eq!(replay.pipelines().len(), 2);
+        assert_eq!(replay.resource(0).unwrap().internal_index, 0);
+        unsafe { replay.destroy(&mut replay_interface) }.unwrap();
+
+        (interface.fp_destroy_backend_context.unwrap())(&mut interface);
+        assert_eq!(
+            replay_backend.calls[..backend.calls.len()],
+            backend.calls[..],
+            "replayed call stream differs from the captured one"
+        );
+        assert_eq!(
+            replay_backend.calls[backend.calls.len()..],
+            ["destroy_pipeline", "destroy_pipeline", "destroy_resource 0"]
+        );
+    }
+
+    #[test]
+    fn replay_binds_resources() {
+        let mut backend = FakeBackend::default();
+        let mut interface = fake_interface(&mut backend);
+        let recorder = interface.intercept_capture().unwrap();
+        run_frames(&interface);
+        let capture = recorder.capture();
+        (interface.fp_destroy_backend_context.unwrap())(&mut interface);
+
+        let mut texture = 0u32;
+        let texture: *mut u32 = &mut texture;
+        let mut bound = Vec::new();
+        let mut replay_backend = FakeBackend::default();
+        let mut replay_interface = fake_interface(&mut replay_backend);
+        let replay = unsafe {
+            capture.replay_with(&mut replay_interface, std::ptr::null_mut(), |frame, resource| {
+                bound.push((frame, resource.name.clone()));
+                FfxResource::new(texture.cast(), &resource.name, resource.description, resource.state)
+            })
+        }
+        .unwrap();
+        assert_eq!(bound, [(0, "Color".to_string()), (1, "Color".to_string())]);
+        unsafe { replay.destroy(&mut replay_interface) }.unwrap();
+    }
+
+    #[test]
+    fn clear_frames_keeps_resources() {
+        let mut backend = FakeBackend::default();
+        let mut interface = fake_interface(&mut backend);
+        let recorder = interface.intercept_capture().unwrap();
+        run_frames(&interface);
+        recorder.clear_frames();
+
+        let capture = recorder.capture();
+        assert_eq!(capture.frame_count(), 0);
+        assert_eq!(capture.events.len(), 3);
+        (interface.fp_destroy_backend_context.unwrap())(&mut interface);
+    }
+
+    #[test]
+    fn rejects_newer_versions() {
+        let json = format!(r#"{{"format": "{FSR2_CAPTURE_FORMAT}", "version": 99, "events": []}}"#);
+        let err = Fsr2Capture::read_from(json.as_bytes()).unwrap_err();
+        assert!(matches!(err, CaptureError::UnsupportedVersion(99)));
+
+        let err = Fsr2Capture::read_from(r#"{"format": "other", "version": 1}"#.as_bytes()).unwrap_err();
+        assert!(matches!(err, CaptureError::UnknownFormat(_)));
+    }
+}
@@ -0,0 +1,241 @@
+use crate::format::f32_to_f16;
+use crate::{FfxComputeJobDescription, FfxConstantBuffer, FfxFsr2Pass, FFX_MAX_CONST_SIZE};
+use bytemuck::{Pod, Zeroable};
+
+/// A constant buffer layout of the FSR2 shaders.
+pub trait Fsr2ConstantLayout: Pod {
+    /// Name of the constant buffer binding in `FfxPipelineState`.
+    const BINDING_NAME: &'static str;
+    /// The passes binding the layout, the name alone is ambiguous for `cbGenerateReactive`.
+    const PASSES: &'static [FfxFsr2Pass];
+}
+
+/// `cbFSR2`, the constants shared by all FSR2 passes.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+#[repr(C)]
+pub struct Fsr2Constants {
+    pub render_size: [i32; 2],
+    pub max_render_size: [i32; 2],
+    pub display_size: [i32; 2],
+    pub input_color_resource_dimensions: [i32; 2],
+    pub luma_mip_dimensions: [i32; 2],
+    pub luma_mip_level_to_use: i32,
+    pub frame_index: i32,
+
+    /// Coefficients to reconstruct view space depth from device depth, derived from the camera near/far planes.
+    pub device_to_view_depth: [f32; 4],
+    pub jitter_offset: [f32; 2],
+    pub motion_vector_scale: [f32; 2],
+    /// Render size divided by display size.
+    pub downscale_factor: [f32; 2],
+    pub motion_vector_jitter_cancellation: [f32; 2],
+    pub pre_exposure: f32,
+    pub previous_frame_pre_exposure: f32,
+    pub tan_half_fov: f32,
+    pub jitter_phase_count: f32,
+    pub delta_time: f32,
+    pub dynamic_res_change_factor: f32,
+    pub view_space_to_meters_factor: f32,
+}
+
+/// `cbSPD`, the constants of the luminance pyramid pass.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[repr(C)]
+pub struct Fsr2SpdConstants {
+    pub mips: u32,
+    pub num_work_groups: u32,
+    pub work_group_offset: [u32; 2],
+    pub render_size: [u32; 2],
+}
+
+/// `cbRCAS`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[repr(C)]
+pub struct Fsr2RcasConstants {
+    /// `FsrRcasCon`: the sharpening attenuation as `f32` bits, then packed as two halfs.
+    pub rcas_config: [u32; 4],
+}
+
+impl Fsr2RcasConstants {
+    /// The constants FSR2 generates for a `sharpness` in `0..=1`.
+    pub fn from_sharpness(sharpness: f32) -> Self {
+        Self::from_attenuation(crate::rcas_attenuation(sharpness))
+    }
+
+    pub fn from_attenuation(attenuation: f32) -> Self {
+        let half = f32_to_f16(attenuation) as u32;
+        Self {
+            rcas_config: [attenuation.to_bits(), half | half << 16, 0, 0],
+        }
+    }
+
+    /// The `sharpness` of `FfxFsr2DispatchDescription` these constants were generated from.
+    pub fn sharpness(&self) -> f32 {
+        // FSR2 remaps sharpness to 2 - 2 * sharpness stops of attenuation, stored as exp2(-stops).
+        let attenuation = f32::from_bits(self.rcas_config[0]);
+        1.0 + attenuation.log2() / 2.0
+    }
+}
+
+/// `cbGenerateReactive` of `ffxFsr2ContextGenerateReactiveMask`.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+#[repr(C)]
+pub struct Fsr2GenerateReactiveConstants {
+    pub scale: f32,
+    pub threshold: f32,
+    pub binary_value: f32,
+    pub flags: u32,
+}
+
+/// `cbGenerateReactive` of the transparency and composition autogenerate pass. Shares its binding name with
+/// [`Fsr2GenerateReactiveConstants`].
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+#[repr(C)]
+pub struct Fsr2GenerateReactiveConstants2 {
+    pub auto_tc_threshold: f32,
+    pub auto_tc_scale: f32,
+    pub auto_reactive_scale: f32,
+    pub auto_reactive_max: f32,
+}
+
+macro_rules! constant_layouts {
+    ($($ty:ty => $name:literal in [$($pass:ident),*]),* $(,)?) => {
+        $(
+            // SAFETY: Only 4 byte integers and floats, no padding.
+            unsafe impl Zeroable for $ty {}
+            unsafe impl Pod for $ty {}
+
+            impl Fsr2ConstantLayout for $ty {
+                const BINDING_NAME: &'static str = $name;
+                const PASSES: &'static [FfxFsr2Pass] = &[$(FfxFsr2Pass::$pass),*];
+            }
+        )*
+    };
+}
+
+constant_layouts! {
+    Fsr2Constants => "cbFSR2" in [
+        DepthClip,
+        ReconstructPreviousDepth,
+        Lock,
+        Accumulate,
+        AccumulateSharpen,
+        Rcas,
+        ComputeLuminancePyramid,
+        TcrAutogenerate
+    ],
+    Fsr2SpdConstants => "cbSPD" in [ComputeLuminancePyramid],
+    Fsr2RcasConstants => "cbRCAS" in [Rcas],
+    Fsr2GenerateReactiveConstants => "cbGenerateReactive" in [GenerateReactive],
+    Fsr2GenerateReactiveConstants2 => "cbGenerateReactive" in [TcrAutogenerate],
+}
+
+impl FfxConstantBuffer {
+    pub fn new<T: Fsr2ConstantLayout>(constants: &T) -> Self {
+        let words: &[u32] = bytemuck::cast_slice(bytemuck::bytes_of(constants));
+        assert!(words.len() <= FFX_MAX_CONST_SIZE, "constant layout does not fit FfxConstantBuffer");
+        let mut data = [0; FFX_MAX_CONST_SIZE];
+        data[..words.len()].copy_from_slice(words);
+        Self {
+            uint32_size: words.len() as u32,
+            data,
+        }
+    }
+
+    pub fn words(&self) -> &[u32] {
+        &self.data[..(self.uint32_size as usize).min(FFX_MAX_CONST_SIZE)]
+    }
+
+    /// Views the buffer as `T`, `None` if the size does not match.
+    pub fn cast<T: Fsr2ConstantLayout>(&self) -> Option<&T> {
+        let words = self.words();
+        (words.len() * 4 == std::mem::size_of::<T>()).then(|| bytemuck::from_bytes(bytemuck::cast_slice(words)))
+    }
+}
+
+impl FfxComputeJobDescription {
+    /// The constant buffer bound to `T::BINDING_NAME`, viewed as `T`. `pass` is the pass the job's pipeline was
+    /// created for, `None` if it does not bind `T`.
+    pub fn constants<T: Fsr2ConstantLayout>(&self, pass: FfxFsr2Pass) -> Option<&T> {
+        if !T::PASSES.contains(&pass) {
+            return None;
+        }
+        let count = (self.pipeline.const_count as usize).min(self.cbs.len());
+        (0..count)
+            .find(|&i| self.pipeline.cv_resource_bindings[i].name().to_string_lossy() == T::BINDING_NAME)
+            .and_then(|i| self.cbs[i].cast())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ResourceName;
+
+    #[test]
+    fn sizes() {
+        // Sizes of the structs in ffx_fsr2_private.h.
+        assert_eq!(std::mem::size_of::<Fsr2Constants>(), 124);
+        assert_eq!(std::mem::size_of::<Fsr2SpdConstants>(), 24);
+        assert_eq!(std::mem::size_of::<Fsr2RcasConstants>(), 16);
+        assert_eq!(std::mem::size_of::<Fsr2GenerateReactiveConstants>(), 16);
+        assert_eq!(std::mem::size_of::<Fsr2GenerateReactiveConstants2>(), 16);
+    }
+
+    #[test]
+    fn round_trip() {
+        let constants = Fsr2Constants {
+            render_size: [1280, 720],
+            jitter_offset: [0.25, -0.125],
+            pre_exposure: 2.0,
+            view_space_to_meters_factor: 1.0,
+            ..Default::default()
+        };
+        let buffer = FfxConstantBuffer::new(&constants);
+        assert_eq!(buffer.uint32_size, 31);
+        assert_eq!(buffer.cast::<Fsr2Constants>(), Some(&constants));
+        assert_eq!(buffer.cast::<Fsr2RcasConstants>(), None);
+    }
+
+    #[test]
+    fn rcas_sharpness() {
+        let sharpness = 0.8f32;
+        let constants = Fsr2RcasConstants::from_sharpness(sharpness);
+        assert_eq!(constants.rcas_config[1] >> 16, constants.rcas_config[1] & 0xffff);
+        assert!((constants.sharpness() - sharpness).abs() < 1e-6);
+    }
+
+    #[test]
+    fn job_constants() {
+        let mut job: FfxComputeJobDescription = unsafe { std::mem::zeroed() };
+        job.pipeline.const_count = 2;
+        job.pipeline.cv_resource_bindings[0].name = ResourceName::new("cbFSR2").into_raw();
+        job.pipeline.cv_resource_bindings[1].name = ResourceName::new("cbRCAS").into_raw();
+        job.cbs[0] = FfxConstantBuffer::new(&Fsr2Constants::default());
+        job.cbs[1] = FfxConstantBuffer::new(&Fsr2RcasConstants { rcas_config: [1, 2, 3, 4] });
+
+        let pass = FfxFsr2Pass::Rcas;
+        assert_eq!(job.constants::<Fsr2RcasConstants>(pass).unwrap().rcas_config, [1, 2, 3, 4]);
+        assert!(job.constants::<Fsr2Constants>(pass).is_some());
+        assert!(job.constants::<Fsr2SpdConstants>(pass).is_none());
+    }
+
+    #[test]
+    fn generate_reactive_constants_by_pass() {
+        let mut job: FfxComputeJobDescription = unsafe { std::mem::zeroed() };
+        job.pipeline.const_count = 1;
+        job.pipeline.cv_resource_bindings[0].name = ResourceName::new("cbGenerateReactive").into_raw();
+        let constants = Fsr2GenerateReactiveConstants2 {
+            auto_tc_threshold: 0.05,
+            ..Default::default()
+        };
+        job.cbs[0] = FfxConstantBuffer::new(&constants);
+
+        let pass = FfxFsr2Pass::TcrAutogenerate;
+        assert_eq!(job.constants::<Fsr2GenerateReactiveConstants2>(pass), Some(&constants));
+        assert!(job.constants::<Fsr2GenerateReactiveConstants>(pass).is_none());
+        let pass = FfxFsr2Pass::GenerateReactive;
+        assert!(job.constants::<Fsr2GenerateReactiveConstants>(pass).is_some());
+        assert!(job.constants::<Fsr2GenerateReactiveConstants2>(pass).is_none());
+    }
+}
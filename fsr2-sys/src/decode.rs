@@ -0,0 +1,287 @@
+use crate::{
+    FfxComputeJobDescription, FfxFsr2Interface, FfxPipelineState, FfxResourceBinding, FfxResourceDescription,
+    FfxResourceInternal, ResourceName, FFX_MAX_NUM_CONST_BUFFERS, FFX_MAX_NUM_SRVS, FFX_MAX_NUM_UAVS,
+};
+use std::fmt;
+
+/// A binding slot of a pipeline, with its name decoded.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DecodedBinding {
+    pub slot_index: u32,
+    pub resource_identifier: u32,
+    pub name: String,
+}
+
+impl From<&FfxResourceBinding> for DecodedBinding {
+    fn from(binding: &FfxResourceBinding) -> Self {
+        Self {
+            slot_index: binding.slot_index,
+            resource_identifier: binding.resource_identifier,
+            name: binding.name().to_string_lossy(),
+        }
+    }
+}
+
+/// The used bindings of an [`FfxPipelineState`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DecodedPipelineState {
+    pub srvs: Vec<DecodedBinding>,
+    pub uavs: Vec<DecodedBinding>,
+    pub cbs: Vec<DecodedBinding>,
+}
+
+impl FfxPipelineState {
+    pub fn decode(&self) -> DecodedPipelineState {
+        let decode = |bindings: &[FfxResourceBinding], count: u32| {
+            bindings.iter().take(count as usize).map(DecodedBinding::from).collect()
+        };
+        DecodedPipelineState {
+            srvs: decode(&self.srv_resource_bindings, self.srv_count),
+            uavs: decode(&self.uav_resource_bindings, self.uav_count),
+            cbs: decode(&self.cv_resource_bindings, self.const_count),
+        }
+    }
+}
+
+/// A resource bound to an SRV or UAV slot of a compute job.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DecodedResourceSlot {
+    pub binding: DecodedBinding,
+    /// Name the job gives the resource, empty in release builds of FSR2.
+    pub name: String,
+    pub internal_index: i32,
+    /// `None` if the resource is not bound or no description was available.
+    pub description: Option<FfxResourceDescription>,
+    /// Only set for UAVs.
+    pub mip: Option<u32>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DecodedConstantBuffer {
+    pub binding: DecodedBinding,
+    pub name: String,
+    pub slot_index: u32,
+    /// The first `uint32_size` words of the buffer.
+    pub data: Vec<u32>,
+}
+
+/// A readable view of an [`FfxComputeJobDescription`], see [`FfxComputeJobDescription::decode`].
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DecodedComputeJob {
+    pub dimensions: [u32; 3],
+    pub srvs: Vec<DecodedResourceSlot>,
+    pub uavs: Vec<DecodedResourceSlot>,
+    pub cbs: Vec<DecodedConstantBuffer>,
+}
+
+impl FfxComputeJobDescription {
+    /// Pairs every used slot of the job with its binding in `pipeline`. Counts beyond the slot arrays are clamped.
+    /// `describe` looks up the description of a bound resource, usually through `fp_get_resource_description`.
+    pub fn decode(
+        &self,
+        mut describe: impl FnMut(FfxResourceInternal) -> Option<FfxResourceDescription>,
+    ) -> DecodedComputeJob {
+        let pipeline = &self.pipeline;
+        let mut slot = |binding: &FfxResourceBinding, resource: FfxResourceInternal, name, mip| DecodedResourceSlot {
+            binding: binding.into(),
+            name: ResourceName::from_raw(name).to_string_lossy(),
+            internal_index: resource.internal_index,
+            description: (resource.internal_index >= 0).then(|| describe(resource)).flatten(),
+            mip,
+        };
+
+        let srvs = (0..(pipeline.srv_count as usize).min(FFX_MAX_NUM_SRVS))
+            .map(|i| slot(&pipeline.srv_resource_bindings[i], self.srvs[i], self.srv_names[i], None))
+            .collect();
+        let uavs = (0..(pipeline.uav_count as usize).min(FFX_MAX_NUM_UAVS))
+            .map(|i| slot(&pipeline.uav_resource_bindings[i], self.uavs[i], self.uav_names[i], Some(self.uav_mip[i])))
+            .collect();
+        let cbs = (0..(pipeline.const_count as usize).min(FFX_MAX_NUM_CONST_BUFFERS))
+            .map(|i| {
+                let cb = &self.cbs[i];
+                let size = (cb.uint32_size as usize).min(cb.data.len());
+                DecodedConstantBuffer {
+                    binding: (&pipeline.cv_resource_bindings[i]).into(),
+                    name: ResourceName::from_raw(self.cb_names[i]).to_string_lossy(),
+                    slot_index: self.cb_slot_index[i],
+                    data: cb.data[..size].to_vec(),
+                }
+            })
+            .collect();
+
+        DecodedComputeJob {
+            dimensions: self.dimensions,
+            srvs,
+            uavs,
+            cbs,
+        }
+    }
+
+    /// Same as [`Self::decode`], with descriptions from the interface's `fp_get_resource_description`.
+    ///
+    /// # Safety
+    /// `interface` must be the interface the job was scheduled on, and its backend context must be alive.
+    pub unsafe fn decode_with_interface(&self, interface: &mut FfxFsr2Interface) -> DecodedComputeJob {
+        let get_resource_description = interface.fp_get_resource_description;
+        self.decode(|resource| get_resource_description.map(|get| get(interface, resource)))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl DecodedComputeJob {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("decoded jobs are always serializable")
+    }
+}
+
+#[cfg(feature = "serde")]
+impl DecodedPipelineState {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("decoded pipelines are always serializable")
+    }
+}
+
+fn write_slot(f: &mut fmt::Formatter<'_>, kind: &str, slot: &DecodedResourceSlot) -> fmt::Result {
+    write!(f, "  {kind}{} {:?} -> #{}", slot.binding.slot_index, slot.binding.name, slot.internal_index)?;
+    if !slot.name.is_empty() && slot.name != slot.binding.name {
+        write!(f, " {:?}", slot.name)?;
+    }
+    if let Some(mip) = slot.mip {
+        write!(f, " mip {mip}")?;
+    }
+    if let Some(description) = &slot.description {
+        write!(
+            f,
+            " {:?} {:?} {}x{}x{} mips {}",
+            description.ty,
+            description.surface_format,
+            description.width,
+            description.height,
+            description.depth,
+            description.mip_count,
+        )?;
+    }
+    writeln!(f)
+}
+
+impl fmt::Display for DecodedComputeJob {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let [x, y, z] = self.dimensions;
+        writeln!(f, "dispatch {x}x{y}x{z}")?;
+        for srv in &self.srvs {
+            write_slot(f, "t", srv)?;
+        }
+        for uav in &self.uavs {
+            write_slot(f, "u", uav)?;
+        }
+        for cb in &self.cbs {
+            writeln!(f, "  b{} {:?}: {:08x?}", cb.slot_index, cb.binding.name, cb.data)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for DecodedPipelineState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let bindings = [("t", &self.srvs), ("u", &self.uavs), ("b", &self.cbs)];
+        for (kind, bindings) in bindings {
+            for binding in bindings {
+                writeln!(f, "{kind}{} {:?} (id {})", binding.slot_index, binding.name, binding.resource_identifier)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FfxResourceFlags, FfxResourceType, FfxSurfaceFormat};
+
+    fn binding(slot_index: u32, resource_identifier: u32, name: &str) -> FfxResourceBinding {
+        FfxResourceBinding {
+            slot_index,
+            resource_identifier,
+            name: ResourceName::new(name).into_raw(),
+        }
+    }
+
+    fn job() -> FfxComputeJobDescription {
+        let mut job: FfxComputeJobDescription = unsafe { std::mem::zeroed() };
+        job.dimensions = [120, 68, 1];
+        job.pipeline.srv_count = 1;
+        job.pipeline.srv_resource_bindings[0] = binding(0, 1, "r_input_color_jittered");
+        job.srvs[0] = FfxResourceInternal { internal_index: 3 };
+        job.pipeline.uav_count = 1;
+        job.pipeline.uav_resource_bindings[0] = binding(0, 20, "rw_upscaled_output");
+        job.uavs[0] = FfxResourceInternal { internal_index: 7 };
+        job.uav_mip[0] = 2;
+        job.pipeline.const_count = 1;
+        job.pipeline.cv_resource_bindings[0] = binding(1, 0, "cbFSR2");
+        job.cb_slot_index[0] = 1;
+        job.cbs[0].uint32_size = 3;
+        job.cbs[0].data[..3].copy_from_slice(&[1, 2, 3]);
+        job
+    }
+
+    fn describe(resource: FfxResourceInternal) -> Option<FfxResourceDescription> {
+        Some(FfxResourceDescription {
+            ty: FfxResourceType::Texture2D,
+            surface_format: FfxSurfaceFormat::RGBA16Float,
+            width: 1920,
+            height: 1080,
+            depth: 1,
+            mip_count: resource.internal_index as u32,
+            flags: FfxResourceFlags::NONE,
+        })
+    }
+
+    #[test]
+    fn decode_job() {
+        let decoded = job().decode(describe);
+        assert_eq!(decoded.srvs.len(), 1);
+        assert_eq!(decoded.srvs[0].binding.name, "r_input_color_jittered");
+        assert_eq!(decoded.srvs[0].description.unwrap().mip_count, 3);
+        assert_eq!(decoded.uavs[0].mip, Some(2));
+        assert_eq!(decoded.cbs[0].data, [1, 2, 3]);
+        assert_eq!(decoded.cbs[0].slot_index, 1);
+
+        let text = decoded.to_string();
+        assert!(text.starts_with("dispatch 120x68x1\n"));
+        assert!(text.contains("u0 \"rw_upscaled_output\" -> #7 mip 2 Texture2D RGBA16Float 1920x1080x1"));
+    }
+
+    #[test]
+    fn clamps_corrupt_counts() {
+        let mut job = job();
+        job.pipeline.srv_count = u32::MAX;
+        job.pipeline.uav_count = u32::MAX;
+        job.pipeline.const_count = u32::MAX;
+        let decoded = job.decode(describe);
+        assert_eq!(decoded.srvs.len(), FFX_MAX_NUM_SRVS);
+        assert_eq!(decoded.uavs.len(), FFX_MAX_NUM_UAVS);
+        assert_eq!(decoded.cbs.len(), FFX_MAX_NUM_CONST_BUFFERS);
+    }
+
+    #[test]
+    fn decode_pipeline() {
+        let pipeline = job().pipeline.decode();
+        assert_eq!(pipeline.srvs[0].resource_identifier, 1);
+        assert_eq!(pipeline.cbs[0].name, "cbFSR2");
+        assert!(pipeline.to_string().contains("b1 \"cbFSR2\" (id 0)"));
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn json() {
+        let json = job().decode(describe).to_json();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["uavs"][0]["binding"]["name"], "rw_upscaled_output");
+        assert_eq!(value["srvs"][0]["description"]["surface_format"], "RGBA16Float");
+    }
+}
@@ -0,0 +1,15 @@
+//! Filter kernels shared by the CPU upscalers and the CPU backend.
+
+use std::f32::consts::PI;
+
+pub(crate) fn lanczos2(x: f32) -> f32 {
+    let x = x.abs();
+    if x < 1e-5 {
+        1.0
+    } else if x >= 2.0 {
+        0.0
+    } else {
+        let sinc = |x: f32| (PI * x).sin() / (PI * x);
+        sinc(x) * sinc(x / 2.0)
+    }
+}
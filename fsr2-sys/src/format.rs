@@ -0,0 +1,150 @@
+use crate::FfxSurfaceFormat;
+
+/// How the components of a surface format are interpreted by shaders.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum FfxComponentType {
+    Typeless,
+    Float,
+    Unorm,
+    Snorm,
+    Uint,
+    Sint,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct FfxSurfaceFormatInfo {
+    pub bytes_per_pixel: u32,
+    pub channel_count: u32,
+    pub component_type: FfxComponentType,
+}
+
+impl FfxSurfaceFormat {
+    pub const ALL: [FfxSurfaceFormat; 20] = [
+        FfxSurfaceFormat::Unknown,
+        FfxSurfaceFormat::RGBA32Typeless,
+        FfxSurfaceFormat::RGBA32Float,
+        FfxSurfaceFormat::RGBA16Float,
+        FfxSurfaceFormat::RGBA16Unorm,
+        FfxSurfaceFormat::RG32Float,
+        FfxSurfaceFormat::R32Uint,
+        FfxSurfaceFormat::RGBA8Typeless,
+        FfxSurfaceFormat::RGBA8Unorm,
+        FfxSurfaceFormat::R11G11B11Float,
+        FfxSurfaceFormat::RG16Float,
+        FfxSurfaceFormat::RG16Uint,
+        FfxSurfaceFormat::R16Float,
+        FfxSurfaceFormat::R16Uint,
+        FfxSurfaceFormat::R16Unorm,
+        FfxSurfaceFormat::R16Snorm,
+        FfxSurfaceFormat::R8Unorm,
+        FfxSurfaceFormat::R8Uint,
+        FfxSurfaceFormat::RG8Unorm,
+        FfxSurfaceFormat::R32Float,
+    ];
+
+    /// Size and component layout of the format, `None` for `Unknown`.
+    pub fn info(self) -> Option<FfxSurfaceFormatInfo> {
+        use FfxComponentType::*;
+        let (bytes_per_pixel, channel_count, component_type) = match self {
+            FfxSurfaceFormat::Unknown => return None,
+            FfxSurfaceFormat::RGBA32Typeless => (16, 4, Typeless),
+            FfxSurfaceFormat::RGBA32Float => (16, 4, Float),
+            FfxSurfaceFormat::RGBA16Float => (8, 4, Float),
+            FfxSurfaceFormat::RGBA16Unorm => (8, 4, Unorm),
+            FfxSurfaceFormat::RG32Float => (8, 2, Float),
+            FfxSurfaceFormat::R32Uint => (4, 1, Uint),
+            FfxSurfaceFormat::RGBA8Typeless => (4, 4, Typeless),
+            FfxSurfaceFormat::RGBA8Unorm => (4, 4, Unorm),
+            FfxSurfaceFormat::R11G11B11Float => (4, 3, Float),
+            FfxSurfaceFormat::RG16Float => (4, 2, Float),
+            FfxSurfaceFormat::RG16Uint => (4, 2, Uint),
+            FfxSurfaceFormat::R16Float => (2, 1, Float),
+            FfxSurfaceFormat::R16Uint => (2, 1, Uint),
+            FfxSurfaceFormat::R16Unorm => (2, 1, Unorm),
+            FfxSurfaceFormat::R16Snorm => (2, 1, Snorm),
+            FfxSurfaceFormat::R8Unorm => (1, 1, Unorm),
+            FfxSurfaceFormat::R8Uint => (1, 1, Uint),
+            FfxSurfaceFormat::RG8Unorm => (2, 2, Unorm),
+            FfxSurfaceFormat::R32Float => (4, 1, Float),
+        };
+        Some(FfxSurfaceFormatInfo {
+            bytes_per_pixel,
+            channel_count,
+            component_type,
+        })
+    }
+
+    pub fn bytes_per_pixel(self) -> u32 {
+        self.info().map_or(0, |info| info.bytes_per_pixel)
+    }
+
+    pub fn channel_count(self) -> u32 {
+        self.info().map_or(0, |info| info.channel_count)
+    }
+
+    /// The typed format FSR2 uses when it creates a view of a typeless resource.
+    pub fn typed(self) -> Self {
+        match self {
+            FfxSurfaceFormat::RGBA32Typeless => FfxSurfaceFormat::RGBA32Float,
+            FfxSurfaceFormat::RGBA8Typeless => FfxSurfaceFormat::RGBA8Unorm,
+            format => format,
+        }
+    }
+}
+
+pub(crate) fn f16_to_f32(half: u16) -> f32 {
+    let sign = if half & 0x8000 != 0 { -1.0 } else { 1.0 };
+    let exponent = ((half >> 10) & 0x1f) as i32;
+    let mantissa = (half & 0x3ff) as f32;
+    match exponent {
+        0 => sign * mantissa * (-24f32).exp2(),
+        31 if mantissa == 0.0 => sign * f32::INFINITY,
+        31 => f32::NAN,
+        _ => sign * (1.0 + mantissa / 1024.0) * ((exponent - 15) as f32).exp2(),
+    }
+}
+
+/// Rounds to the nearest half, ties away from zero.
+pub(crate) fn f32_to_f16(value: f32) -> u16 {
+    let bits = value.to_bits();
+    let sign = ((bits >> 16) & 0x8000) as u16;
+    let exponent = ((bits >> 23) & 0xff) as i32;
+    let mantissa = bits & 0x7f_ffff;
+    if exponent == 0xff {
+        return sign | 0x7c00 | if mantissa != 0 { 0x200 } else { 0 };
+    }
+    let half_exponent = exponent - 127 + 15;
+    if half_exponent >= 0x1f {
+        return sign | 0x7c00;
+    }
+    if half_exponent <= 0 {
+        if half_exponent < -10 {
+            return sign;
+        }
+        let mantissa = mantissa | 0x80_0000;
+        let shift = (14 - half_exponent) as u32;
+        let round = (mantissa >> (shift - 1)) & 1;
+        return sign | ((mantissa >> shift) + round) as u16;
+    }
+    // A carry out of the mantissa correctly rounds up into the exponent.
+    let half = ((half_exponent as u32) << 10) | (mantissa >> 13);
+    sign | (half + ((mantissa >> 12) & 1)) as u16
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn half_floats() {
+        assert_eq!(f16_to_f32(0x3c00), 1.0);
+        assert_eq!(f16_to_f32(0xc000), -2.0);
+        assert_eq!(f16_to_f32(0x0001), (-24f32).exp2());
+
+        for half in [0x0000, 0x0001, 0x03ff, 0x0400, 0x3555, 0x3c00, 0x7bff, 0xc000] {
+            assert_eq!(f32_to_f16(f16_to_f32(half)), half);
+        }
+        assert_eq!(f32_to_f16(1.0e6), 0x7c00);
+        assert_eq!(f32_to_f16(1.0 + 1.0 / 2048.0), 0x3c01);
+    }
+}
@@ -0,0 +1,296 @@
+//! FSR1, edge adaptive spatial upsampling (EASU) followed by RCAS, as a fallback where FSR2 cannot run, e.g.
+//! without motion vectors. Render sizes follow the same [`FfxFsr2QualityMode`]s as FSR2.
+
+use crate::upscaler::{cpu_images, sharpen};
+use crate::{
+    CpuImage, FfxDimensions2D, FfxErrorCode, FfxFsr2DispatchDescription, Fsr2RcasConstants, Upscaler,
+    UpscalerDescription,
+};
+use bytemuck::{Pod, Zeroable};
+
+/// `FsrEasuCon` of FSR1, the constants of the EASU shader.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[repr(C)]
+pub struct Fsr1EasuConstants {
+    /// Scale and offset from output pixels to input pixels.
+    pub con0: [u32; 4],
+    /// Reciprocal input size and the gather offsets of the 12-tap kernel.
+    pub con1: [u32; 4],
+    pub con2: [u32; 4],
+    pub con3: [u32; 4],
+}
+
+// SAFETY: Only 4 byte integers, no padding.
+unsafe impl Zeroable for Fsr1EasuConstants {}
+unsafe impl Pod for Fsr1EasuConstants {}
+
+impl Fsr1EasuConstants {
+    /// Upscales the `input_viewport` at the top left of a texture of `input_size` to `output_size`.
+    pub fn new(input_viewport: FfxDimensions2D, input_size: FfxDimensions2D, output_size: FfxDimensions2D) -> Self {
+        let viewport = [input_viewport.width as f32, input_viewport.height as f32];
+        let output = [output_size.width as f32, output_size.height as f32];
+        let rcp_input = [1.0 / input_size.width as f32, 1.0 / input_size.height as f32];
+        let bits = |values: [f32; 4]| values.map(f32::to_bits);
+        Self {
+            con0: bits([
+                viewport[0] / output[0],
+                viewport[1] / output[1],
+                0.5 * viewport[0] / output[0] - 0.5,
+                0.5 * viewport[1] / output[1] - 0.5,
+            ]),
+            con1: bits([rcp_input[0], rcp_input[1], rcp_input[0], -rcp_input[1]]),
+            con2: bits([-rcp_input[0], 2.0 * rcp_input[1], rcp_input[0], 2.0 * rcp_input[1]]),
+            con3: bits([0.0, 4.0 * rcp_input[1], 0.0, 0.0]),
+        }
+    }
+
+    /// Maps the centre of an output pixel to input pixel coordinates, relative to input texel centres.
+    fn input_position(&self, x: u32, y: u32) -> [f32; 2] {
+        let [scale_x, scale_y, offset_x, offset_y] = self.con0.map(f32::from_bits);
+        [x as f32 * scale_x + offset_x, y as f32 * scale_y + offset_y]
+    }
+}
+
+/// Constants of both FSR1 passes. RCAS uses the same `FsrRcasCon` as the FSR2 RCAS pass.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Fsr1Constants {
+    pub easu: Fsr1EasuConstants,
+    pub rcas: Fsr2RcasConstants,
+}
+
+/// A spatial upscaler running EASU and optionally RCAS on the CPU.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Fsr1Upscaler {
+    pub display_size: FfxDimensions2D,
+    pub enable_sharpening: bool,
+    /// Same range and mapping as `FfxFsr2DispatchDescription::sharpness`.
+    pub sharpness: f32,
+}
+
+impl Fsr1Upscaler {
+    pub fn new(display_size: FfxDimensions2D) -> Self {
+        Self {
+            display_size,
+            enable_sharpening: true,
+            sharpness: 0.8,
+        }
+    }
+
+    pub fn from_description(description: &UpscalerDescription) -> Self {
+        Self::new(description.display_size)
+    }
+
+    /// Constants for upscaling `render_size` texels of an input of `input_size`.
+    pub fn constants(&self, render_size: FfxDimensions2D, input_size: FfxDimensions2D) -> Fsr1Constants {
+        Fsr1Constants {
+            easu: Fsr1EasuConstants::new(render_size, input_size, self.display_size),
+            rcas: Fsr2RcasConstants::from_sharpness(self.sharpness),
+        }
+    }
+
+    /// Upscales the top left `render_size` texels of `color` into `output`. Fails with `InvalidSize` unless
+    /// `output` is of the display size and texels are left to upscale.
+    pub fn upscale(
+        &self,
+        color: &CpuImage,
+        render_size: FfxDimensions2D,
+        output: &mut CpuImage,
+    ) -> Result<(), FfxErrorCode> {
+        if (output.width(), output.height()) != (self.display_size.width, self.display_size.height) {
+            return Err(FfxErrorCode::InvalidSize);
+        }
+        let input_size = FfxDimensions2D {
+            width: color.width(),
+            height: color.height(),
+        };
+        let constants = Fsr1EasuConstants::new(render_size, input_size, self.display_size);
+        fsr1_easu(color, render_size, &constants, output)?;
+        if self.enable_sharpening {
+            sharpen(output, self.sharpness);
+        }
+        Ok(())
+    }
+}
+
+impl Upscaler for Fsr1Upscaler {
+    fn display_size(&self) -> FfxDimensions2D {
+        self.display_size
+    }
+
+    /// Upscales `color`, sharpening as requested by the dispatch. All other inputs are ignored.
+    unsafe fn dispatch(&mut self, description: &FfxFsr2DispatchDescription) -> Result<(), FfxErrorCode> {
+        let (color, output) = cpu_images(description)?;
+        let upscaler = Self {
+            enable_sharpening: description.enable_sharpening,
+            sharpness: description.sharpness,
+            ..*self
+        };
+        upscaler.upscale(color, description.render_size, output)
+    }
+}
+
+/// `FsrEasuF` for every texel of `output`, loads from `input` are clamped to `input_viewport`. Alpha is
+/// sampled bilinearly. Fails with `InvalidSize` if the viewport is empty.
+pub fn fsr1_easu(
+    input: &CpuImage,
+    input_viewport: FfxDimensions2D,
+    constants: &Fsr1EasuConstants,
+    output: &mut CpuImage,
+) -> Result<(), FfxErrorCode> {
+    let max = [
+        input_viewport.width.min(input.width()) as i32 - 1,
+        input_viewport.height.min(input.height()) as i32 - 1,
+    ];
+    if max[0] < 0 || max[1] < 0 {
+        return Err(FfxErrorCode::InvalidSize);
+    }
+    let load = |x: i32, y: i32| input.load(x.clamp(0, max[0]), y.clamp(0, max[1]));
+    for y in 0..output.height() {
+        for x in 0..output.width() {
+            let position = constants.input_position(x, y);
+            let [r, g, b] = easu_pixel(position, |dx, dy| {
+                let texel = load(position[0].floor() as i32 + dx, position[1].floor() as i32 + dy);
+                [texel[0], texel[1], texel[2]]
+            });
+            let uv = [
+                (position[0] + 0.5) / input.width() as f32,
+                (position[1] + 0.5) / input.height() as f32,
+            ];
+            output.store(x as i32, y as i32, [r, g, b, input.sample(uv)[3]]);
+        }
+    }
+    Ok(())
+}
+
+/// The 12 taps of the EASU kernel around the 2x2 quad at the floor of the position.
+const TAPS: [[i32; 2]; 12] = [
+    [0, -1],
+    [1, -1],
+    [-1, 0],
+    [0, 0],
+    [1, 0],
+    [2, 0],
+    [-1, 1],
+    [0, 1],
+    [1, 1],
+    [2, 1],
+    [0, 2],
+    [1, 2],
+];
+
+fn easu_pixel(position: [f32; 2], load: impl Fn(i32, i32) -> [f32; 3]) -> [f32; 3] {
+    let [pp_x, pp_y] = position.map(|p| p - p.floor());
+    let colors = TAPS.map(|[x, y]| load(x, y));
+    // Named after the taps in FSR1:    b c
+    //                                e f g h
+    //                                i j k l
+    //                                  n o
+    let [b, c, e, f, g, h, i, j, k, l, n, o] = colors.map(|c| c[2] * 0.5 + (c[0] * 0.5 + c[1]));
+
+    // Gradient direction and edge length, accumulated over the bilinear footprint of the 4 inner taps.
+    let mut dir = [0.0f32; 2];
+    let mut len = 0.0f32;
+    let mut accumulate = |weight: f32, [a, b, c, d, e]: [f32; 5]| {
+        let mut axis = |low: f32, center: f32, high: f32| {
+            let difference = high - low;
+            let length = (difference.abs() / (high - center).abs().max((center - low).abs())).clamp(0.0, 1.0);
+            let length = if length.is_nan() { 0.0 } else { length };
+            len += length * length * weight;
+            difference * weight
+        };
+        dir[0] += axis(b, c, d);
+        dir[1] += axis(a, c, e);
+    };
+    accumulate((1.0 - pp_x) * (1.0 - pp_y), [b, e, f, g, j]);
+    accumulate(pp_x * (1.0 - pp_y), [c, f, g, h, k]);
+    accumulate((1.0 - pp_x) * pp_y, [f, i, j, k, n]);
+    accumulate(pp_x * pp_y, [g, j, k, l, o]);
+
+    let length_squared = dir[0] * dir[0] + dir[1] * dir[1];
+    let dir = if length_squared < 1.0 / 32768.0 {
+        [1.0, 0.0]
+    } else {
+        dir.map(|d| d / length_squared.sqrt())
+    };
+    let len = (len * 0.5) * (len * 0.5);
+    // Stretch the kernel along edges, from 1 for axis aligned to sqrt(2) for diagonal ones.
+    let stretch = (dir[0] * dir[0] + dir[1] * dir[1]) / dir[0].abs().max(dir[1].abs());
+    let len2 = [1.0 + (stretch - 1.0) * len, 1.0 - 0.5 * len];
+    // Negative lobe strength, from 0.5 without an edge to 0.21 along strong ones.
+    let lobe = 0.5 + ((1.0 / 4.0 - 0.04) - 0.5) * len;
+    let clip = 1.0 / lobe;
+
+    let mut color = [0.0f32; 3];
+    let mut weight_sum = 0.0f32;
+    for ([x, y], tap) in TAPS.iter().zip(&colors) {
+        let offset = [*x as f32 - pp_x, *y as f32 - pp_y];
+        let v = [
+            (offset[0] * dir[0] + offset[1] * dir[1]) * len2[0],
+            (offset[0] * -dir[1] + offset[1] * dir[0]) * len2[1],
+        ];
+        let d2 = (v[0] * v[0] + v[1] * v[1]).min(clip);
+        // Approximation of lanczos2 windowed by the clipped lobe.
+        let base = (2.0 / 5.0 * d2 - 1.0).powi(2);
+        let window = (lobe * d2 - 1.0).powi(2);
+        let weight = (25.0 / 16.0 * base - (25.0 / 16.0 - 1.0)) * window;
+        for (sum, channel) in color.iter_mut().zip(tap) {
+            *sum += channel * weight;
+        }
+        weight_sum += weight;
+    }
+
+    // Deringing against the 4 inner taps.
+    let inner = [colors[3], colors[4], colors[7], colors[8]];
+    std::array::from_fn(|channel| {
+        let min = inner.iter().map(|c| c[channel]).fold(f32::MAX, f32::min);
+        let max = inner.iter().map(|c| c[channel]).fold(f32::MIN, f32::max);
+        (color[channel] / weight_sum).clamp(min, max)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::size;
+    use crate::{FfxFsr2QualityMode, FfxSurfaceFormat};
+
+    #[test]
+    fn easu_constants() {
+        let constants = Fsr1EasuConstants::new(size(960, 540), size(1920, 1080), size(1920, 1080));
+        assert_eq!(constants.con0.map(f32::from_bits), [0.5, 0.5, -0.25, -0.25]);
+        assert_eq!(f32::from_bits(constants.con3[1]), 4.0 / 1080.0);
+        assert_eq!(constants.input_position(1, 0), [0.25, -0.25]);
+    }
+
+    #[test]
+    fn upscales_edges() {
+        let mut input = CpuImage::new(8, 8, FfxSurfaceFormat::RGBA32Float);
+        for y in 0..8 {
+            for x in 0..8 {
+                let value = if x < 4 { 0.2 } else { 0.8 };
+                input.store(x, y, [value, value, value, 1.0]);
+            }
+        }
+        let mut upscaler = Fsr1Upscaler::new(size(16, 16));
+        upscaler.enable_sharpening = false;
+        assert_eq!(upscaler.render_size(FfxFsr2QualityMode::Performance), size(8, 8));
+        let mut output = CpuImage::new(16, 16, FfxSurfaceFormat::RGBA32Float);
+        upscaler.upscale(&input, size(8, 8), &mut output).unwrap();
+
+        // Flat areas stay flat, the edge stays within the input range and is monotonic.
+        assert!((output.load(2, 5)[0] - 0.2).abs() < 1e-5);
+        assert!((output.load(13, 5)[0] - 0.8).abs() < 1e-5);
+        let row: Vec<f32> = (0..16).map(|x| output.load(x, 5)[0]).collect();
+        assert!(row.windows(2).all(|w| w[0] <= w[1] + 1e-6));
+        assert!(row.iter().all(|&v| (0.2 - 1e-6..=0.8 + 1e-6).contains(&v)));
+        assert_eq!(output.load(7, 7)[3], 1.0);
+
+        // Sharpening steepens the edge.
+        upscaler.enable_sharpening = true;
+        upscaler.sharpness = 1.0;
+        let mut sharpened = CpuImage::new(16, 16, FfxSurfaceFormat::RGBA32Float);
+        upscaler.upscale(&input, size(8, 8), &mut sharpened).unwrap();
+        assert!(sharpened.load(7, 5)[0] <= output.load(7, 5)[0]);
+        assert!(sharpened.load(8, 5)[0] >= output.load(8, 5)[0]);
+    }
+}
@@ -0,0 +1,446 @@
+//! Golden image regression tests: a sequence of dispatches is run through an [`Upscaler`] and every output is
+//! compared with a stored golden image. Set `FSR2_UPDATE_GOLDENS=1` to write the current outputs as the new goldens.
+
+use crate::{
+    CpuImage, FfxErrorCode, FfxFsr2DispatchDescription, FfxSurfaceFormat, ImageMetric, ImageSizeMismatchError,
+    SyntheticScene, Upscaler,
+};
+use std::fmt;
+use std::fs;
+use std::io::{self, BufRead, BufReader, BufWriter, Read, Write};
+use std::path::PathBuf;
+
+/// Environment variable that turns golden comparisons into updates.
+pub const GOLDEN_UPDATE_ENV: &str = "FSR2_UPDATE_GOLDENS";
+/// Largest image [`CpuImage::read_pfm`] accepts, in texels.
+pub const MAX_PFM_TEXELS: usize = 1 << 26;
+
+/// Limits an output has to stay within, compared with its golden.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct GoldenTolerance {
+    pub min_psnr: f32,
+    pub min_ssim: f32,
+    pub max_flip: f32,
+}
+
+impl Default for GoldenTolerance {
+    fn default() -> Self {
+        Self {
+            min_psnr: 40.0,
+            min_ssim: 0.99,
+            max_flip: 0.02,
+        }
+    }
+}
+
+impl GoldenTolerance {
+    fn accepts(&self, metric: ImageMetric, value: f32) -> bool {
+        match metric {
+            ImageMetric::Psnr => value >= self.min_psnr,
+            ImageMetric::Ssim => value >= self.min_ssim,
+            ImageMetric::Flip => value <= self.max_flip,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum GoldenError {
+    Io(io::Error),
+    /// The golden does not exist and updating is disabled.
+    MissingGolden(PathBuf),
+    Malformed(PathBuf),
+    SizeMismatch(PathBuf, ImageSizeMismatchError),
+    Dispatch(u32, FfxErrorCode),
+}
+
+impl fmt::Display for GoldenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GoldenError::Io(err) => write!(f, "failed to access goldens: {err}"),
+            GoldenError::MissingGolden(path) => write!(
+                f,
+                "golden {} does not exist, run with {GOLDEN_UPDATE_ENV}=1 to create it",
+                path.display()
+            ),
+            GoldenError::Malformed(path) => write!(f, "golden {} is not a PFM image", path.display()),
+            GoldenError::SizeMismatch(path, err) => write!(f, "golden {}: {err}", path.display()),
+            GoldenError::Dispatch(frame, code) => write!(f, "dispatch of frame {frame} failed with {code:?}"),
+        }
+    }
+}
+
+impl std::error::Error for GoldenError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            GoldenError::Io(err) => Some(err),
+            GoldenError::SizeMismatch(_, err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for GoldenError {
+    fn from(err: io::Error) -> Self {
+        GoldenError::Io(err)
+    }
+}
+
+/// The metrics of one output against its golden.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GoldenFrameReport {
+    pub frame: u32,
+    pub metrics: Vec<(ImageMetric, f32)>,
+    pub passed: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct GoldenReport {
+    pub name: String,
+    /// True if the goldens were written instead of compared.
+    pub updated: bool,
+    pub frames: Vec<GoldenFrameReport>,
+    /// Where the outputs, error maps and report of failed frames were written.
+    pub failure_directory: Option<PathBuf>,
+}
+
+impl GoldenReport {
+    pub fn passed(&self) -> bool {
+        self.frames.iter().all(|frame| frame.passed)
+    }
+
+    /// Panics with the report if any frame failed.
+    pub fn assert_passed(&self) {
+        assert!(self.passed(), "{self}");
+    }
+}
+
+impl fmt::Display for GoldenReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let failed = self.frames.iter().filter(|frame| !frame.passed).count();
+        writeln!(
+            f,
+            "golden test {}: {failed} of {} frames failed",
+            self.name,
+            self.frames.len()
+        )?;
+        for frame in &self.frames {
+            write!(
+                f,
+                "frame {:4} {}",
+                frame.frame,
+                if frame.passed { "ok    " } else { "FAILED" }
+            )?;
+            for (metric, value) in &frame.metrics {
+                write!(f, " {}={value:.5}", metric.name())?;
+            }
+            writeln!(f)?;
+        }
+        if let Some(directory) = &self.failure_directory {
+            writeln!(
+                f,
+                "outputs and error maps of failed frames are in {}",
+                directory.display()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// A named sequence of outputs stored as `<directory>/<name>/frame_NNNN.pfm`.
+#[derive(Clone, Debug)]
+pub struct GoldenTest {
+    pub name: String,
+    pub directory: PathBuf,
+    pub tolerance: GoldenTolerance,
+    /// Write the outputs as goldens instead of comparing, defaults to whether [`GOLDEN_UPDATE_ENV`] is set.
+    pub update: bool,
+}
+
+impl GoldenTest {
+    pub fn new(name: &str, directory: impl Into<PathBuf>) -> Self {
+        let update = std::env::var(GOLDEN_UPDATE_ENV).is_ok_and(|value| !value.is_empty() && value != "0");
+        Self {
+            name: name.to_owned(),
+            directory: directory.into(),
+            tolerance: GoldenTolerance::default(),
+            update,
+        }
+    }
+
+    fn golden_directory(&self) -> PathBuf {
+        self.directory.join(&self.name)
+    }
+
+    /// Runs `frame_count` dispatches. `dispatch` returns the description of a frame writing into the given output,
+    /// which is a `CpuImage` of the upscaler's display size.
+    ///
+    /// # Safety
+    /// The descriptions must be valid for [`Upscaler::dispatch`], with resources that stay alive until the next
+    /// call of `dispatch`.
+    pub unsafe fn run(
+        &self,
+        upscaler: &mut dyn Upscaler,
+        frame_count: u32,
+        mut dispatch: impl FnMut(u32, &mut CpuImage) -> FfxFsr2DispatchDescription,
+    ) -> Result<GoldenReport, GoldenError> {
+        let golden_directory = self.golden_directory();
+        let failure_directory = golden_directory.join("failures");
+        if failure_directory.exists() {
+            fs::remove_dir_all(&failure_directory)?;
+        }
+        fs::create_dir_all(&golden_directory)?;
+
+        let display_size = upscaler.display_size();
+        let mut frames = Vec::new();
+        for frame in 0..frame_count {
+            let mut output = CpuImage::new(display_size.width, display_size.height, FfxSurfaceFormat::RGBA16Float);
+            let description = dispatch(frame, &mut output);
+            upscaler
+                .dispatch(&description)
+                .map_err(|code| GoldenError::Dispatch(frame, code))?;
+
+            let path = golden_directory.join(format!("frame_{frame:04}.pfm"));
+            if self.update {
+                output.write_pfm(BufWriter::new(fs::File::create(&path)?))?;
+                continue;
+            }
+            let golden = match fs::File::open(&path) {
+                Ok(file) => CpuImage::read_pfm(BufReader::new(file)).map_err(|err| match err.kind() {
+                    io::ErrorKind::InvalidData | io::ErrorKind::UnexpectedEof => GoldenError::Malformed(path.clone()),
+                    _ => GoldenError::Io(err),
+                })?,
+                Err(err) if err.kind() == io::ErrorKind::NotFound => return Err(GoldenError::MissingGolden(path)),
+                Err(err) => return Err(err.into()),
+            };
+
+            let mut metrics = Vec::new();
+            let mut passed = true;
+            let mut flip_map = None;
+            for metric in ImageMetric::ALL {
+                let result = metric
+                    .evaluate(&output, &golden)
+                    .map_err(|err| GoldenError::SizeMismatch(path.clone(), err))?;
+                passed &= self.tolerance.accepts(metric, result.value);
+                metrics.push((metric, result.value));
+                if metric == ImageMetric::Flip {
+                    flip_map = Some(result.error_map);
+                }
+            }
+            if !passed {
+                fs::create_dir_all(&failure_directory)?;
+                let base = failure_directory.join(format!("frame_{frame:04}"));
+                output.write_pfm(BufWriter::new(fs::File::create(base.with_extension("output.pfm"))?))?;
+                if let Some(flip_map) = flip_map {
+                    write_error_ppm(
+                        &flip_map,
+                        BufWriter::new(fs::File::create(base.with_extension("flip.ppm"))?),
+                    )?;
+                }
+            }
+            frames.push(GoldenFrameReport { frame, metrics, passed });
+        }
+
+        let report = GoldenReport {
+            name: self.name.clone(),
+            updated: self.update,
+            failure_directory: failure_directory.exists().then_some(failure_directory.clone()),
+            frames,
+        };
+        if report.failure_directory.is_some() {
+            fs::write(failure_directory.join("report.txt"), report.to_string())?;
+        }
+        Ok(report)
+    }
+
+    /// Runs the first `frame_count` frames of `scene`.
+    ///
+    /// # Safety
+    /// The upscaler must accept resources of the CPU backend, see [`Upscaler::dispatch`].
+    pub unsafe fn run_scene(
+        &self,
+        upscaler: &mut dyn Upscaler,
+        scene: &SyntheticScene,
+        frame_count: u32,
+    ) -> Result<GoldenReport, GoldenError> {
+        let mut current = None;
+        self.run(upscaler, frame_count, |frame, output| {
+            let scene_frame = current.insert(scene.render(frame));
+            scene_frame.dispatch_description(output)
+        })
+    }
+}
+
+impl CpuImage {
+    /// Writes the RGB channels of mip 0 as a little endian PFM image.
+    pub fn write_pfm(&self, mut writer: impl Write) -> io::Result<()> {
+        write!(writer, "PF\n{} {}\n-1.0\n", self.width(), self.height())?;
+        // PFM stores the rows bottom to top.
+        for row in self.texels().chunks(self.width().max(1) as usize).rev() {
+            for texel in row {
+                for channel in &texel[..3] {
+                    writer.write_all(&channel.to_le_bytes())?;
+                }
+            }
+        }
+        writer.flush()
+    }
+
+    /// Reads an RGB or greyscale PFM image into an `RGBA32Float` image with an alpha of 1. Images larger than
+    /// [`MAX_PFM_TEXELS`] are rejected as invalid data.
+    pub fn read_pfm(mut reader: impl BufRead) -> io::Result<CpuImage> {
+        let invalid = |message: &str| io::Error::new(io::ErrorKind::InvalidData, message.to_owned());
+        let mut header = Vec::new();
+        while header.len() < 4 {
+            let mut line = String::new();
+            if reader.read_line(&mut line)? == 0 {
+                return Err(io::ErrorKind::UnexpectedEof.into());
+            }
+            header.extend(line.split_whitespace().map(str::to_owned));
+        }
+        let channels = match header[0].as_str() {
+            "PF" => 3,
+            "Pf" => 1,
+            _ => return Err(invalid("not a PFM image")),
+        };
+        let dimension = |value: &str| value.parse::<u32>().map_err(|_| invalid("malformed PFM header"));
+        let (width, height) = (dimension(&header[1])?, dimension(&header[2])?);
+        let scale = header[3].parse::<f32>().map_err(|_| invalid("malformed PFM header"))?;
+        let length = (width as usize)
+            .checked_mul(height as usize)
+            .filter(|&texels| texels <= MAX_PFM_TEXELS)
+            .ok_or_else(|| invalid("PFM image too large"))?
+            * channels
+            * 4;
+
+        // Grows with the data actually present, so a forged header cannot allocate the maximum size.
+        let mut data = Vec::new();
+        reader.by_ref().take(length as u64).read_to_end(&mut data)?;
+        if data.len() != length {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        let mut image = CpuImage::new(width, height, FfxSurfaceFormat::RGBA32Float);
+        for (index, texel) in data.chunks_exact(channels * 4).enumerate() {
+            let values: Vec<f32> = texel
+                .chunks_exact(4)
+                .map(|bytes| {
+                    let bytes = bytes.try_into().unwrap();
+                    if scale < 0.0 {
+                        f32::from_le_bytes(bytes)
+                    } else {
+                        f32::from_be_bytes(bytes)
+                    }
+                })
+                .collect();
+            let rgb = if channels == 3 {
+                [values[0], values[1], values[2]]
+            } else {
+                [values[0]; 3]
+            };
+            let (x, y) = (index as u32 % width, height - 1 - index as u32 / width);
+            image.store(x as i32, y as i32, [rgb[0], rgb[1], rgb[2], 1.0]);
+        }
+        Ok(image)
+    }
+}
+
+/// Writes the first channel of an error map as a binary PPM heat map, black for no error to white for 1.
+fn write_error_ppm(map: &CpuImage, mut writer: impl Write) -> io::Result<()> {
+    write!(writer, "P6\n{} {}\n255\n", map.width(), map.height())?;
+    for texel in map.texels() {
+        let error = texel[0].clamp(0.0, 1.0) * 3.0;
+        let rgb = [error, error - 1.0, error - 2.0].map(|channel| (channel.clamp(0.0, 1.0) * 255.0).round() as u8);
+        writer.write_all(&rgb)?;
+    }
+    writer.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::size;
+    use crate::{CpuUpscaleFilter, CpuUpscaler, Fsr1Upscaler, SceneDescription, UpscalerDescription};
+    use std::path::Path;
+
+    /// The goldens checked in with the crate, regenerated with `FSR2_UPDATE_GOLDENS=1 cargo test`.
+    fn stored_goldens() -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("goldens")
+    }
+
+    fn stored_scene() -> (SyntheticScene, UpscalerDescription) {
+        let scene = SyntheticScene::new(SceneDescription::new(size(32, 18), size(64, 36)));
+        let description = UpscalerDescription {
+            flags: scene.description().flags,
+            max_render_size: scene.description().render_size,
+            display_size: scene.description().display_size,
+        };
+        (scene, description)
+    }
+
+    #[test]
+    fn pfm_round_trip() {
+        let mut image = CpuImage::new(3, 2, FfxSurfaceFormat::RGBA32Float);
+        image.fill([0.0, 0.0, 0.0, 1.0]);
+        image.store(0, 0, [0.25, 0.5, 1.5, 1.0]);
+        image.store(2, 1, [-1.0, 0.0, 2.0, 1.0]);
+        let mut bytes = Vec::new();
+        image.write_pfm(&mut bytes).unwrap();
+        assert!(bytes.starts_with(b"PF\n3 2\n-1.0\n"));
+        let read = CpuImage::read_pfm(bytes.as_slice()).unwrap();
+        assert_eq!(read.texels(), image.texels());
+        assert!(CpuImage::read_pfm(&b"P6\n1 1\n255\n"[..]).is_err());
+
+        for header in [&b"PF\n4294967295 4294967295\n-1.0\n"[..], b"PF\n65536 65536\n-1.0\n", b"PF\n1.5 1\n-1.0\n"] {
+            let error = CpuImage::read_pfm(header).unwrap_err();
+            assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        }
+        let truncated = CpuImage::read_pfm(&b"PF\n4096 4096\n-1.0\n\0\0\0\0"[..]).unwrap_err();
+        assert_eq!(truncated.kind(), io::ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn update_compare_and_fail() {
+        let directory = std::env::temp_dir().join(format!("fsr2-golden-{}", std::process::id()));
+        let scene = SyntheticScene::new(SceneDescription::new(size(16, 9), size(32, 18)));
+        let description = UpscalerDescription::from_quality_mode(size(32, 18), crate::FfxFsr2QualityMode::Performance);
+        let mut upscaler = CpuUpscaler::new(&description, CpuUpscaleFilter::Lanczos);
+
+        let mut test = GoldenTest::new("lanczos", &directory);
+        test.update = false;
+        let missing = unsafe { test.run_scene(&mut upscaler, &scene, 2) };
+        assert!(matches!(missing, Err(GoldenError::MissingGolden(_))));
+
+        test.update = true;
+        let report = unsafe { test.run_scene(&mut upscaler, &scene, 2) }.unwrap();
+        assert!(report.updated && report.passed());
+        test.update = false;
+        let report = unsafe { test.run_scene(&mut upscaler, &scene, 2) }.unwrap();
+        report.assert_passed();
+        assert_eq!(report.frames.len(), 2);
+        assert_eq!(report.frames[0].metrics[0].1, f32::INFINITY);
+
+        // A different upscaler no longer matches the goldens.
+        let mut bilinear = CpuUpscaler::new(&description, CpuUpscaleFilter::Bilinear);
+        let report = unsafe { test.run_scene(&mut bilinear, &scene, 2) }.unwrap();
+        assert!(!report.passed());
+        let failures = report.failure_directory.clone().unwrap();
+        assert!(failures.join("frame_0000.flip.ppm").exists());
+        assert!(failures.join("frame_0000.output.pfm").exists());
+        assert!(fs::read_to_string(failures.join("report.txt"))
+            .unwrap()
+            .contains("FAILED"));
+
+        fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn stored_goldens_cpu_upscalers() {
+        let (scene, description) = stored_scene();
+        let mut lanczos = CpuUpscaler::new(&description, CpuUpscaleFilter::Lanczos);
+        let test = GoldenTest::new("cpu_lanczos", stored_goldens());
+        unsafe { test.run_scene(&mut lanczos, &scene, 4) }.unwrap().assert_passed();
+
+        let mut fsr1 = Fsr1Upscaler::from_description(&description);
+        let test = GoldenTest::new("fsr1", stored_goldens());
+        unsafe { test.run_scene(&mut fsr1, &scene, 4) }.unwrap().assert_passed();
+    }
+}
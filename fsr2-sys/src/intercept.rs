@@ -0,0 +1,211 @@
+use crate::FfxFsr2Interface;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+pub(crate) fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    // Interception callbacks must not panic across the FFI boundary.
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Intercepted interfaces, keyed by their scratch buffer. FSR2 passes its own copy of the interface to the
+/// callbacks, the scratch buffer is the only thing that identifies the backend.
+pub(crate) struct InterceptRegistry<T> {
+    entries: Mutex<Vec<(usize, T)>>,
+}
+
+impl<T: Clone> InterceptRegistry<T> {
+    pub(crate) const fn new() -> Self {
+        Self {
+            entries: Mutex::new(Vec::new()),
+        }
+    }
+
+    fn key(interface: *const FfxFsr2Interface) -> Option<usize> {
+        (!interface.is_null()).then(|| unsafe { (*interface).scratch_buffer } as usize)
+    }
+
+    pub(crate) fn find(&self, interface: *const FfxFsr2Interface) -> Option<T> {
+        let key = Self::key(interface)?;
+        lock(&self.entries)
+            .iter()
+            .find(|(scratch_buffer, _)| *scratch_buffer == key)
+            .map(|(_, entry)| entry.clone())
+    }
+
+    /// Returns the existing entry instead if `interface` is already intercepted.
+    pub(crate) fn insert_with(&self, interface: &FfxFsr2Interface, entry: impl FnOnce() -> T) -> (T, bool) {
+        let key = interface.scratch_buffer as usize;
+        let mut entries = lock(&self.entries);
+        if let Some((_, existing)) = entries.iter().find(|(scratch_buffer, _)| *scratch_buffer == key) {
+            return (existing.clone(), false);
+        }
+        let entry = entry();
+        entries.push((key, entry.clone()));
+        (entry, true)
+    }
+
+    pub(crate) fn remove(&self, interface: *const FfxFsr2Interface) {
+        if let Some(key) = Self::key(interface) {
+            lock(&self.entries).retain(|(scratch_buffer, _)| *scratch_buffer != key);
+        }
+    }
+}
+
+/// A backend that only records what it is asked to do, its state lives in the scratch buffer like a real one.
+#[cfg(test)]
+pub(crate) mod fake {
+    use crate::*;
+    use std::ffi::c_void;
+
+    #[derive(Default)]
+    pub(crate) struct FakeBackend {
+        next_resource: i32,
+        pub(crate) calls: Vec<String>,
+    }
+
+    unsafe fn backend<'a>(interface: *mut FfxFsr2Interface) -> &'a mut FakeBackend {
+        &mut *((*interface).scratch_buffer as *mut FakeBackend)
+    }
+
+    extern "C" fn create_backend_context(_: *mut FfxFsr2Interface, _: FfxDevice) -> FfxErrorCode {
+        FfxErrorCode::Ok
+    }
+
+    extern "C" fn get_device_capabilities(
+        _: *mut FfxFsr2Interface,
+        capabilities: *mut FfxDeviceCapabilities,
+        _: FfxDevice,
+    ) -> FfxErrorCode {
+        unsafe {
+            *capabilities = FfxDeviceCapabilities {
+                minimum_supported_shader_model: FfxShaderModel::ShaderModel_6_6,
+                wave_lane_count_min: 32,
+                wave_lane_count_max: 64,
+                fp16_supported: true,
+                raytracing_supported: false,
+            }
+        };
+        FfxErrorCode::Ok
+    }
+
+    extern "C" fn destroy_backend_context(_: *mut FfxFsr2Interface) -> FfxErrorCode {
+        FfxErrorCode::Ok
+    }
+
+    extern "C" fn create_resource(
+        interface: *mut FfxFsr2Interface,
+        description: *const FfxCreateResourceDescription,
+        resource: *mut FfxResourceInternal,
+    ) -> FfxErrorCode {
+        let backend = unsafe { backend(interface) };
+        let description = unsafe { &*description };
+        let name = unsafe { description.name() }.unwrap_or_default();
+        backend.calls.push(format!(
+            "create_resource {name} {}x{} {}",
+            description.resource_description.width, description.resource_description.height, description.init_data_size
+        ));
+        unsafe { (*resource).internal_index = backend.next_resource };
+        backend.next_resource += 1;
+        FfxErrorCode::Ok
+    }
+
+    extern "C" fn register_resource(
+        interface: *mut FfxFsr2Interface,
+        resource: *const FfxResource,
+        internal: *mut FfxResourceInternal,
+    ) -> FfxErrorCode {
+        let backend = unsafe { backend(interface) };
+        backend.calls.push(format!("register_resource {}", unsafe { (*resource).name() }));
+        unsafe { (*internal).internal_index = backend.next_resource };
+        backend.next_resource += 1;
+        FfxErrorCode::Ok
+    }
+
+    extern "C" fn unregister_resources(interface: *mut FfxFsr2Interface) -> FfxErrorCode {
+        unsafe { backend(interface) }.calls.push("unregister_resources".into());
+        FfxErrorCode::Ok
+    }
+
+    extern "C" fn get_resource_description(_: *mut FfxFsr2Interface, _: FfxResourceInternal) -> FfxResourceDescription {
+        FfxResource::NULL.description
+    }
+
+    extern "C" fn destroy_resource(interface: *mut FfxFsr2Interface, resource: FfxResourceInternal) -> FfxErrorCode {
+        unsafe { backend(interface) }
+            .calls
+            .push(format!("destroy_resource {}", resource.internal_index));
+        FfxErrorCode::Ok
+    }
+
+    /// Every pipeline reads one SRV named after its pass and has a `cbFSR2` constant buffer.
+    extern "C" fn create_pipeline(
+        interface: *mut FfxFsr2Interface,
+        pass: FfxFsr2Pass,
+        _: *const FfxPipelineDescription,
+        pipeline: *mut FfxPipelineState,
+    ) -> FfxErrorCode {
+        unsafe { backend(interface) }.calls.push(format!("create_pipeline {pass:?}"));
+        let pipeline = unsafe { &mut *pipeline };
+        pipeline.pipeline = (pass as usize + 1) as FfxPipeline;
+        pipeline.srv_count = 1;
+        pipeline.srv_resource_bindings[0].name = ResourceName::new(&format!("{pass:?}")).into_raw();
+        pipeline.const_count = 1;
+        pipeline.cv_resource_bindings[0].name = ResourceName::new("cbFSR2").into_raw();
+        FfxErrorCode::Ok
+    }
+
+    extern "C" fn destroy_pipeline(interface: *mut FfxFsr2Interface, _: *mut FfxPipelineState) -> FfxErrorCode {
+        unsafe { backend(interface) }.calls.push("destroy_pipeline".into());
+        FfxErrorCode::Ok
+    }
+
+    extern "C" fn schedule_gpu_job(interface: *mut FfxFsr2Interface, job: *const FfxGpuJobDescription) -> FfxErrorCode {
+        let job = unsafe { &*job };
+        let call = match job.ty {
+            FfxJobType::ClearFloat => {
+                let clear = unsafe { &job.descriptor.clear_job };
+                format!("clear {} {:?}", clear.target.internal_index, clear.color)
+            }
+            FfxJobType::Copy => {
+                let copy = unsafe { &job.descriptor.copy_job };
+                format!("copy {} {}", copy.src.internal_index, copy.dst.internal_index)
+            }
+            FfxJobType::Compute => {
+                let compute = unsafe { &job.descriptor.compute_job };
+                format!(
+                    "compute {:?} {:?} srv {} cb {:?}",
+                    compute.pipeline.pipeline,
+                    compute.dimensions,
+                    compute.srvs[0].internal_index,
+                    compute.cbs[0].words(),
+                )
+            }
+        };
+        unsafe { backend(interface) }.calls.push(call);
+        FfxErrorCode::Ok
+    }
+
+    extern "C" fn execute_gpu_jobs(interface: *mut FfxFsr2Interface, _: FfxCommandList) -> FfxErrorCode {
+        unsafe { backend(interface) }.calls.push("execute".into());
+        FfxErrorCode::Ok
+    }
+
+    /// A complete interface backed by `backend`, reporting an RDNA-like device.
+    pub(crate) fn fake_interface(backend: &mut FakeBackend) -> FfxFsr2Interface {
+        FfxFsr2Interface {
+            fp_create_backend_context: Some(create_backend_context),
+            fp_get_device_capabilities: Some(get_device_capabilities),
+            fp_destroy_backend_context: Some(destroy_backend_context),
+            fp_create_resource: Some(create_resource),
+            fp_register_resource: Some(register_resource),
+            fp_unregister_resources: Some(unregister_resources),
+            fp_get_resource_description: Some(get_resource_description),
+            fp_destroy_resource: Some(destroy_resource),
+            fp_create_pipeline: Some(create_pipeline),
+            fp_destroy_pipeline: Some(destroy_pipeline),
+            fp_schedule_gpu_job: Some(schedule_gpu_job),
+            fp_execute_gpu_jobs: Some(execute_gpu_jobs),
+            scratch_buffer: backend as *mut FakeBackend as *mut c_void,
+            scratch_buffer_size: std::mem::size_of::<FakeBackend>(),
+        }
+    }
+}
@@ -1,8 +1,11 @@
 use std::ffi::c_void;
+use std::fmt;
 use crate::{FfxCommandList, FfxCreateResourceDescription, FfxDevice, FfxDeviceCapabilities, FfxErrorCode, FfxGpuJobDescription, FfxPipelineDescription, FfxPipelineState, FfxResource, FfxResourceDescription, FfxResourceInternal};
 use widestring::WideChar as wchar_t;
 
-#[repr(u8)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(i32)]
 pub enum FfxFsr2Pass {
     DepthClip = 0,
     ReconstructPreviousDepth = 1,
@@ -17,9 +20,22 @@ pub enum FfxFsr2Pass {
 
 impl FfxFsr2Pass {
     const COUNT: usize = 9;
+
+    pub const ALL: [FfxFsr2Pass; Self::COUNT] = [
+        FfxFsr2Pass::DepthClip,
+        FfxFsr2Pass::ReconstructPreviousDepth,
+        FfxFsr2Pass::Lock,
+        FfxFsr2Pass::Accumulate,
+        FfxFsr2Pass::AccumulateSharpen,
+        FfxFsr2Pass::Rcas,
+        FfxFsr2Pass::ComputeLuminancePyramid,
+        FfxFsr2Pass::GenerateReactive,
+        FfxFsr2Pass::TcrAutogenerate,
+    ];
 }
 
-#[repr(u8)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[repr(i32)]
 pub enum FfxFsr2MsgType {
     Error = 0,
     Warning = 1,
@@ -29,36 +45,115 @@ impl FfxFsr2MsgType {
     const COUNT: usize = 2;
 }
 
-pub type FfxFsr2CreateBackendContextFunc = extern fn(*mut FfxFsr2Interface, FfxDevice) -> FfxErrorCode;
-pub type FfxFsr2GetDeviceCapabilitiesFunc = extern fn(*mut FfxFsr2Interface, *mut FfxDeviceCapabilities, FfxDevice) -> FfxErrorCode;
-pub type FfxFsr2DestroyBackendContextFunc = extern fn(*mut FfxFsr2Interface) -> FfxErrorCode;
-pub type FfxFsr2CreateResourceFunc = extern fn(*mut FfxFsr2Interface, *const FfxCreateResourceDescription, *mut FfxResourceInternal) -> FfxErrorCode;
-pub type FFxFsr2RegisterResourceFunc = extern fn(*mut FfxFsr2Interface, *const FfxResource, *mut FfxResourceInternal) -> FfxErrorCode;
-pub type FfxFsr2UnregisterResourcesFunc = extern fn(*mut FfxFsr2Interface) -> FfxErrorCode;
-pub type FfxFsr2GetResourceDescriptionFunc = extern fn(*mut FfxFsr2Interface, FfxResourceInternal) -> FfxResourceDescription;
-pub type FfxFsr2DestroyResourceFunc = extern fn(*mut FfxFsr2Interface, FfxResourceInternal) -> FfxErrorCode;
-pub type FfxFsr2CreatePipelineFunc = extern fn(*mut FfxFsr2Interface, FfxFsr2Pass, *const FfxPipelineDescription, *mut FfxPipelineState) -> FfxErrorCode;
-pub type FfxFsr2DestroyPipelineFunc = extern fn(*mut FfxFsr2Interface, *mut FfxPipelineState) -> FfxErrorCode;
-pub type FfxFsr2ScheduleGpuJobFunc = extern fn(*mut FfxFsr2Interface, *const FfxGpuJobDescription) -> FfxErrorCode;
-pub type FfxFsr2ExecuteGpuJobsFunc = extern fn(*mut FfxFsr2Interface, FfxCommandList) -> FfxErrorCode;
+pub type FfxFsr2CreateBackendContextFunc = extern "C" fn(*mut FfxFsr2Interface, FfxDevice) -> FfxErrorCode;
+pub type FfxFsr2GetDeviceCapabilitiesFunc = extern "C" fn(*mut FfxFsr2Interface, *mut FfxDeviceCapabilities, FfxDevice) -> FfxErrorCode;
+pub type FfxFsr2DestroyBackendContextFunc = extern "C" fn(*mut FfxFsr2Interface) -> FfxErrorCode;
+pub type FfxFsr2CreateResourceFunc = extern "C" fn(*mut FfxFsr2Interface, *const FfxCreateResourceDescription, *mut FfxResourceInternal) -> FfxErrorCode;
+pub type FFxFsr2RegisterResourceFunc = extern "C" fn(*mut FfxFsr2Interface, *const FfxResource, *mut FfxResourceInternal) -> FfxErrorCode;
+pub type FfxFsr2UnregisterResourcesFunc = extern "C" fn(*mut FfxFsr2Interface) -> FfxErrorCode;
+pub type FfxFsr2GetResourceDescriptionFunc = extern "C" fn(*mut FfxFsr2Interface, FfxResourceInternal) -> FfxResourceDescription;
+pub type FfxFsr2DestroyResourceFunc = extern "C" fn(*mut FfxFsr2Interface, FfxResourceInternal) -> FfxErrorCode;
+pub type FfxFsr2CreatePipelineFunc = extern "C" fn(*mut FfxFsr2Interface, FfxFsr2Pass, *const FfxPipelineDescription, *mut FfxPipelineState) -> FfxErrorCode;
+pub type FfxFsr2DestroyPipelineFunc = extern "C" fn(*mut FfxFsr2Interface, *mut FfxPipelineState) -> FfxErrorCode;
+pub type FfxFsr2ScheduleGpuJobFunc = extern "C" fn(*mut FfxFsr2Interface, *const FfxGpuJobDescription) -> FfxErrorCode;
+pub type FfxFsr2ExecuteGpuJobsFunc = extern "C" fn(*mut FfxFsr2Interface, FfxCommandList) -> FfxErrorCode;
 pub type FfxFsr2Message = extern "system" fn(FfxFsr2MsgType, *const wchar_t);
 
 #[derive(Copy, Clone, Debug)]
 #[repr(C)]
 pub struct FfxFsr2Interface {
-    pub fp_create_backend_context: FfxFsr2CreateBackendContextFunc,
-    pub fp_get_device_capabilities: FfxFsr2GetDeviceCapabilitiesFunc,
-    pub fp_destroy_backend_context: FfxFsr2DestroyBackendContextFunc,
-    pub fp_create_resource: FfxFsr2CreateResourceFunc,
-    pub fp_register_resource: FFxFsr2RegisterResourceFunc,
-    pub fp_unregister_resources: FfxFsr2UnregisterResourcesFunc,
-    pub fp_get_resource_description: FfxFsr2GetResourceDescriptionFunc,
-    pub fp_destroy_resource: FfxFsr2DestroyResourceFunc,
-    pub fp_create_pipeline: FfxFsr2CreatePipelineFunc,
-    pub fp_destroy_pipeline: FfxFsr2DestroyPipelineFunc,
-    pub fp_schedule_gpu_job: FfxFsr2ScheduleGpuJobFunc,
-    pub fp_execute_gpu_jobs: FfxFsr2ExecuteGpuJobsFunc,
+    pub fp_create_backend_context: Option<FfxFsr2CreateBackendContextFunc>,
+    pub fp_get_device_capabilities: Option<FfxFsr2GetDeviceCapabilitiesFunc>,
+    pub fp_destroy_backend_context: Option<FfxFsr2DestroyBackendContextFunc>,
+    pub fp_create_resource: Option<FfxFsr2CreateResourceFunc>,
+    pub fp_register_resource: Option<FFxFsr2RegisterResourceFunc>,
+    pub fp_unregister_resources: Option<FfxFsr2UnregisterResourcesFunc>,
+    pub fp_get_resource_description: Option<FfxFsr2GetResourceDescriptionFunc>,
+    pub fp_destroy_resource: Option<FfxFsr2DestroyResourceFunc>,
+    pub fp_create_pipeline: Option<FfxFsr2CreatePipelineFunc>,
+    pub fp_destroy_pipeline: Option<FfxFsr2DestroyPipelineFunc>,
+    pub fp_schedule_gpu_job: Option<FfxFsr2ScheduleGpuJobFunc>,
+    pub fp_execute_gpu_jobs: Option<FfxFsr2ExecuteGpuJobsFunc>,
 
     pub scratch_buffer: *mut c_void,
     pub scratch_buffer_size: usize,
-}
\ No newline at end of file
+}
+
+impl FfxFsr2Interface {
+    /// Names of all function pointers that are not set. FSR2 rejects such an interface with
+    /// `FfxErrorCode::IncompleteInterface`.
+    pub fn missing_entries(&self) -> Vec<&'static str> {
+        let entries = [
+            ("fp_create_backend_context", self.fp_create_backend_context.is_some()),
+            ("fp_get_device_capabilities", self.fp_get_device_capabilities.is_some()),
+            ("fp_destroy_backend_context", self.fp_destroy_backend_context.is_some()),
+            ("fp_create_resource", self.fp_create_resource.is_some()),
+            ("fp_register_resource", self.fp_register_resource.is_some()),
+            ("fp_unregister_resources", self.fp_unregister_resources.is_some()),
+            ("fp_get_resource_description", self.fp_get_resource_description.is_some()),
+            ("fp_destroy_resource", self.fp_destroy_resource.is_some()),
+            ("fp_create_pipeline", self.fp_create_pipeline.is_some()),
+            ("fp_destroy_pipeline", self.fp_destroy_pipeline.is_some()),
+            ("fp_schedule_gpu_job", self.fp_schedule_gpu_job.is_some()),
+            ("fp_execute_gpu_jobs", self.fp_execute_gpu_jobs.is_some()),
+        ];
+        entries
+            .into_iter()
+            .filter(|(_, present)| !present)
+            .map(|(name, _)| name)
+            .collect()
+    }
+
+    pub fn check_complete(&self) -> Result<(), IncompleteInterfaceError> {
+        let missing = self.missing_entries();
+        if missing.is_empty() {
+            Ok(())
+        } else {
+            Err(IncompleteInterfaceError { missing })
+        }
+    }
+}
+
+/// Returned by [`FfxFsr2Interface::check_complete`], lists the function pointers that are not set.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IncompleteInterfaceError {
+    pub missing: Vec<&'static str>,
+}
+
+impl fmt::Display for IncompleteInterfaceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "FSR2 interface is missing entries: {}", self.missing.join(", "))
+    }
+}
+
+impl std::error::Error for IncompleteInterfaceError {}
+
+impl From<IncompleteInterfaceError> for FfxErrorCode {
+    fn from(_: IncompleteInterfaceError) -> Self {
+        FfxErrorCode::IncompleteInterface
+    }
+}
+
+/// Message callback that ignores all messages.
+pub extern "system" fn ffx_fsr2_message_noop(_ty: FfxFsr2MsgType, _message: *const wchar_t) {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    extern "C" fn execute_gpu_jobs(_: *mut FfxFsr2Interface, _: FfxCommandList) -> FfxErrorCode {
+        FfxErrorCode::Ok
+    }
+
+    #[test]
+    fn reports_missing_entries() {
+        let mut interface: FfxFsr2Interface = unsafe { std::mem::zeroed() };
+        assert_eq!(interface.missing_entries().len(), 12);
+
+        interface.fp_execute_gpu_jobs = Some(execute_gpu_jobs);
+        let err = interface.check_complete().unwrap_err();
+        assert!(!err.missing.contains(&"fp_execute_gpu_jobs"));
+        assert!(err.missing.contains(&"fp_create_backend_context"));
+        assert_eq!(FfxErrorCode::from(err), FfxErrorCode::IncompleteInterface);
+    }
+}
@@ -0,0 +1,56 @@
+//! The camera jitter FSR2 expects, computed in Rust so it is available without linking FSR2.
+
+use crate::{FfxErrorCode, FfxFloatCoords2D};
+
+/// Same as `ffxFsr2GetJitterPhaseCount`, without calling into FSR2.
+pub fn ffx_fsr2_get_jitter_phase_count(render_width: u32, display_width: u32) -> i32 {
+    const BASE_PHASE_COUNT: f32 = 8.0;
+    (BASE_PHASE_COUNT * (display_width as f32 / render_width as f32).powi(2)) as i32
+}
+
+/// Same as `ffxFsr2GetJitterOffset`, the Halton(2, 3) sequence in render pixels. Fails with `InvalidArgument`
+/// unless `phase_count` is positive. Like FSR2, negative indices wrap with C's `%`, so all but multiples of
+/// `phase_count` give (-0.5, -0.5).
+pub fn ffx_fsr2_get_jitter_offset(index: i32, phase_count: i32) -> Result<FfxFloatCoords2D, FfxErrorCode> {
+    if phase_count <= 0 {
+        return Err(FfxErrorCode::InvalidArgument);
+    }
+    let base = (index % phase_count + 1).max(0) as u32;
+    Ok(FfxFloatCoords2D {
+        x: halton(base, 2) - 0.5,
+        y: halton(base, 3) - 0.5,
+    })
+}
+
+pub(crate) fn halton(mut index: u32, base: u32) -> f32 {
+    let mut fraction = 1.0;
+    let mut result = 0.0;
+    while index > 0 {
+        fraction /= base as f32;
+        result += fraction * (index % base) as f32;
+        index /= base;
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn jitter_sequence() {
+        assert_eq!(ffx_fsr2_get_jitter_phase_count(960, 1920), 32);
+        assert_eq!(ffx_fsr2_get_jitter_phase_count(1280, 1920), 18);
+
+        let first = ffx_fsr2_get_jitter_offset(0, 8).unwrap();
+        assert_eq!((first.x, first.y), (0.0, 1.0 / 3.0 - 0.5));
+        let second = ffx_fsr2_get_jitter_offset(1, 8).unwrap();
+        assert_eq!((second.x, second.y), (-0.25, 2.0 / 3.0 - 0.5));
+        assert_eq!(ffx_fsr2_get_jitter_offset(8, 8), Ok(first));
+        assert_eq!(ffx_fsr2_get_jitter_offset(-8, 8), Ok(first));
+        let negative = ffx_fsr2_get_jitter_offset(-1, 8).unwrap();
+        assert_eq!((negative.x, negative.y), (-0.5, -0.5));
+        assert_eq!(ffx_fsr2_get_jitter_offset(-9, 8), Ok(negative));
+        assert_eq!(ffx_fsr2_get_jitter_offset(0, 0), Err(FfxErrorCode::InvalidArgument));
+    }
+}
@@ -0,0 +1,489 @@
+//! Compares the layout of every hand-written #[repr(C)] type against the bindings bindgen generates
+//! from the vendored FSR2 headers. Field names differ between the two (snake_case vs camelCase), so
+//! each test spells out the mapping explicitly.
+
+use crate::*;
+use std::mem::size_of;
+
+#[allow(nonstandard_style, dead_code, clippy::all)]
+mod c {
+    include!(concat!(env!("OUT_DIR"), "/layout_bindings.rs"));
+}
+
+macro_rules! enum_test {
+    ($name:ident: $rust:ty => $c:ty { $($rust_value:expr => $c_value:ident),* $(,)? }) => {
+        #[test]
+        fn $name() {
+            assert_eq!(size_of::<$rust>(), size_of::<$c>(), "size of {}", stringify!($rust));
+            $(
+                assert_eq!($rust_value as i64, c::$c_value as i64, "value of {}", stringify!($rust_value));
+            )*
+        }
+    };
+}
+
+macro_rules! flags_test {
+    ($name:ident: $rust:ty => $c:ty { $($rust_value:expr => $c_value:ident),* $(,)? }) => {
+        #[test]
+        fn $name() {
+            assert_eq!(size_of::<$rust>(), size_of::<$c>(), "size of {}", stringify!($rust));
+            $(
+                assert_eq!($rust_value.bits() as i64, c::$c_value as i64, "value of {}", stringify!($rust_value));
+            )*
+        }
+    };
+}
+
+#[test]
+fn constants() {
+    assert_eq!(FFX_MAX_NUM_SRVS, c::FFX_MAX_NUM_SRVS as usize);
+    assert_eq!(FFX_MAX_NUM_UAVS, c::FFX_MAX_NUM_UAVS as usize);
+    assert_eq!(FFX_MAX_NUM_CONST_BUFFERS, c::FFX_MAX_NUM_CONST_BUFFERS as usize);
+    assert_eq!(FFX_MAX_CONST_SIZE, c::FFX_MAX_CONST_SIZE as usize);
+    assert_eq!(FFX_FSR2_CONTEXT_SIZE, c::FFX_FSR2_CONTEXT_SIZE as usize);
+    assert_eq!(FFX_FSR2_VERSION_MAJOR, c::FFX_FSR2_VERSION_MAJOR);
+    assert_eq!(FFX_FSR2_VERSION_MINOR, c::FFX_FSR2_VERSION_MINOR);
+    assert_eq!(FFX_FSR2_VERSION_PATCH, c::FFX_FSR2_VERSION_PATCH);
+}
+
+#[test]
+fn error_codes() {
+    assert_eq!(size_of::<FfxErrorCode>(), size_of::<c::FfxErrorCode>());
+    assert_eq!(FfxErrorCode::Ok as i32, c::FFX_OK as i32);
+    assert_eq!(FfxErrorCode::InvalidPointer as i32, c::FFX_ERROR_INVALID_POINTER as i32);
+    assert_eq!(FfxErrorCode::InvalidAlignment as i32, c::FFX_ERROR_INVALID_ALIGNMENT as i32);
+    assert_eq!(FfxErrorCode::InvalidSize as i32, c::FFX_ERROR_INVALID_SIZE as i32);
+    assert_eq!(FfxErrorCode::Eof as i32, c::FFX_EOF as i32);
+    assert_eq!(FfxErrorCode::InvalidPath as i32, c::FFX_ERROR_INVALID_PATH as i32);
+    assert_eq!(FfxErrorCode::ErrorEof as i32, c::FFX_ERROR_EOF as i32);
+    assert_eq!(FfxErrorCode::MalformedData as i32, c::FFX_ERROR_MALFORMED_DATA as i32);
+    assert_eq!(FfxErrorCode::OutOfMemory as i32, c::FFX_ERROR_OUT_OF_MEMORY as i32);
+    assert_eq!(FfxErrorCode::IncompleteInterface as i32, c::FFX_ERROR_INCOMPLETE_INTERFACE as i32);
+    assert_eq!(FfxErrorCode::InvalidEnum as i32, c::FFX_ERROR_INVALID_ENUM as i32);
+    assert_eq!(FfxErrorCode::InvalidArgument as i32, c::FFX_ERROR_INVALID_ARGUMENT as i32);
+    assert_eq!(FfxErrorCode::OutOfRange as i32, c::FFX_ERROR_OUT_OF_RANGE as i32);
+    assert_eq!(FfxErrorCode::NullDevice as i32, c::FFX_ERROR_NULL_DEVICE as i32);
+    assert_eq!(FfxErrorCode::BackendApiError as i32, c::FFX_ERROR_BACKEND_API_ERROR as i32);
+    assert_eq!(FfxErrorCode::InsufficientMemory as i32, c::FFX_ERROR_INSUFFICIENT_MEMORY as i32);
+}
+
+enum_test!(surface_format: FfxSurfaceFormat => c::FfxSurfaceFormat {
+    FfxSurfaceFormat::Unknown => FFX_SURFACE_FORMAT_UNKNOWN,
+    FfxSurfaceFormat::RGBA32Typeless => FFX_SURFACE_FORMAT_R32G32B32A32_TYPELESS,
+    FfxSurfaceFormat::RGBA32Float => FFX_SURFACE_FORMAT_R32G32B32A32_FLOAT,
+    FfxSurfaceFormat::RGBA16Float => FFX_SURFACE_FORMAT_R16G16B16A16_FLOAT,
+    FfxSurfaceFormat::RGBA16Unorm => FFX_SURFACE_FORMAT_R16G16B16A16_UNORM,
+    FfxSurfaceFormat::RG32Float => FFX_SURFACE_FORMAT_R32G32_FLOAT,
+    FfxSurfaceFormat::R32Uint => FFX_SURFACE_FORMAT_R32_UINT,
+    FfxSurfaceFormat::RGBA8Typeless => FFX_SURFACE_FORMAT_R8G8B8A8_TYPELESS,
+    FfxSurfaceFormat::RGBA8Unorm => FFX_SURFACE_FORMAT_R8G8B8A8_UNORM,
+    FfxSurfaceFormat::R11G11B11Float => FFX_SURFACE_FORMAT_R11G11B10_FLOAT,
+    FfxSurfaceFormat::RG16Float => FFX_SURFACE_FORMAT_R16G16_FLOAT,
+    FfxSurfaceFormat::RG16Uint => FFX_SURFACE_FORMAT_R16G16_UINT,
+    FfxSurfaceFormat::R16Float => FFX_SURFACE_FORMAT_R16_FLOAT,
+    FfxSurfaceFormat::R16Uint => FFX_SURFACE_FORMAT_R16_UINT,
+    FfxSurfaceFormat::R16Unorm => FFX_SURFACE_FORMAT_R16_UNORM,
+    FfxSurfaceFormat::R16Snorm => FFX_SURFACE_FORMAT_R16_SNORM,
+    FfxSurfaceFormat::R8Unorm => FFX_SURFACE_FORMAT_R8_UNORM,
+    FfxSurfaceFormat::R8Uint => FFX_SURFACE_FORMAT_R8_UINT,
+    FfxSurfaceFormat::RG8Unorm => FFX_SURFACE_FORMAT_R8G8_UNORM,
+    FfxSurfaceFormat::R32Float => FFX_SURFACE_FORMAT_R32_FLOAT,
+});
+
+enum_test!(shader_model: FfxShaderModel => c::FfxShaderModel {
+    FfxShaderModel::ShaderModel_5_1 => FFX_SHADER_MODEL_5_1,
+    FfxShaderModel::ShaderModel_6_0 => FFX_SHADER_MODEL_6_0,
+    FfxShaderModel::ShaderModel_6_1 => FFX_SHADER_MODEL_6_1,
+    FfxShaderModel::ShaderModel_6_2 => FFX_SHADER_MODEL_6_2,
+    FfxShaderModel::ShaderModel_6_3 => FFX_SHADER_MODEL_6_3,
+    FfxShaderModel::ShaderModel_6_4 => FFX_SHADER_MODEL_6_4,
+    FfxShaderModel::ShaderModel_6_5 => FFX_SHADER_MODEL_6_5,
+    FfxShaderModel::ShaderModel_6_6 => FFX_SHADER_MODEL_6_6,
+    FfxShaderModel::ShaderModel_6_7 => FFX_SHADER_MODEL_6_7,
+});
+
+enum_test!(resource_type: FfxResourceType => c::FfxResourceType {
+    FfxResourceType::Buffer => FFX_RESOURCE_TYPE_BUFFER,
+    FfxResourceType::Texture1D => FFX_RESOURCE_TYPE_TEXTURE1D,
+    FfxResourceType::Texture2D => FFX_RESOURCE_TYPE_TEXTURE2D,
+    FfxResourceType::Texture3D => FFX_RESOURCE_TYPE_TEXTURE3D,
+});
+
+enum_test!(heap_type: FfxHeapType => c::FfxHeapType {
+    FfxHeapType::Default => FFX_HEAP_TYPE_DEFAULT,
+    FfxHeapType::Upload => FFX_HEAP_TYPE_UPLOAD,
+});
+
+enum_test!(job_type: FfxJobType => c::FfxGpuJobType {
+    FfxJobType::ClearFloat => FFX_GPU_JOB_CLEAR_FLOAT,
+    FfxJobType::Copy => FFX_GPU_JOB_COPY,
+    FfxJobType::Compute => FFX_GPU_JOB_COMPUTE,
+});
+
+enum_test!(filter_type: FfxFilterType => c::FfxFilterType {
+    FfxFilterType::Point => FFX_FILTER_TYPE_POINT,
+    FfxFilterType::Linear => FFX_FILTER_TYPE_LINEAR,
+});
+
+enum_test!(resource_dimension: FfxResourceDimension => c::FfxResourceDimension {
+    FfxResourceDimension::Texture1D => FFX_RESOURCE_DIMENSION_TEXTURE_1D,
+    FfxResourceDimension::Texture2D => FFX_RESOURCE_DIMENSION_TEXTURE_2D,
+});
+
+enum_test!(resource_view_type: FfxResourceViewType => c::FfxResourceViewType {
+    FfxResourceViewType::UnorderedAccess => FFX_RESOURCE_VIEW_UNORDERED_ACCESS,
+    FfxResourceViewType::ShaderRead => FFX_RESOURCE_VIEW_SHADER_READ,
+});
+
+enum_test!(quality_mode: FfxFsr2QualityMode => c::FfxFsr2QualityMode {
+    FfxFsr2QualityMode::Quality => FFX_FSR2_QUALITY_MODE_QUALITY,
+    FfxFsr2QualityMode::Balanced => FFX_FSR2_QUALITY_MODE_BALANCED,
+    FfxFsr2QualityMode::Performance => FFX_FSR2_QUALITY_MODE_PERFORMANCE,
+    FfxFsr2QualityMode::UltraPerformance => FFX_FSR2_QUALITY_MODE_ULTRA_PERFORMANCE,
+});
+
+enum_test!(pass: FfxFsr2Pass => c::FfxFsr2Pass {
+    FfxFsr2Pass::DepthClip => FFX_FSR2_PASS_DEPTH_CLIP,
+    FfxFsr2Pass::ReconstructPreviousDepth => FFX_FSR2_PASS_RECONSTRUCT_PREVIOUS_DEPTH,
+    FfxFsr2Pass::Lock => FFX_FSR2_PASS_LOCK,
+    FfxFsr2Pass::Accumulate => FFX_FSR2_PASS_ACCUMULATE,
+    FfxFsr2Pass::AccumulateSharpen => FFX_FSR2_PASS_ACCUMULATE_SHARPEN,
+    FfxFsr2Pass::Rcas => FFX_FSR2_PASS_RCAS,
+    FfxFsr2Pass::ComputeLuminancePyramid => FFX_FSR2_PASS_COMPUTE_LUMINANCE_PYRAMID,
+    FfxFsr2Pass::GenerateReactive => FFX_FSR2_PASS_GENERATE_REACTIVE,
+    FfxFsr2Pass::TcrAutogenerate => FFX_FSR2_PASS_TCR_AUTOGENERATE,
+});
+
+enum_test!(msg_type: FfxFsr2MsgType => c::FfxFsr2MsgType {
+    FfxFsr2MsgType::Error => FFX_FSR2_MESSAGE_TYPE_ERROR,
+    FfxFsr2MsgType::Warning => FFX_FSR2_MESSAGE_TYPE_WARNING,
+});
+
+flags_test!(resource_state: FfxResourceState => c::FfxResourceStates {
+    FfxResourceState::UNORDERED_ACCESS => FFX_RESOURCE_STATE_UNORDERED_ACCESS,
+    FfxResourceState::COMPUTE_READ => FFX_RESOURCE_STATE_COMPUTE_READ,
+    FfxResourceState::COPY_SRC => FFX_RESOURCE_STATE_COPY_SRC,
+    FfxResourceState::COPY_DEST => FFX_RESOURCE_STATE_COPY_DEST,
+    FfxResourceState::GENERIC_READ => FFX_RESOURCE_STATE_GENERIC_READ,
+});
+
+flags_test!(resource_usage: FfxResourceUsage => c::FfxResourceUsage {
+    FfxResourceUsage::READ_ONLY => FFX_RESOURCE_USAGE_READ_ONLY,
+    FfxResourceUsage::RENDERTARGET => FFX_RESOURCE_USAGE_RENDERTARGET,
+    FfxResourceUsage::UAV => FFX_RESOURCE_USAGE_UAV,
+});
+
+flags_test!(resource_flags: FfxResourceFlags => c::FfxResourceFlags {
+    FfxResourceFlags::NONE => FFX_RESOURCE_FLAGS_NONE,
+    FfxResourceFlags::ALIASABLE => FFX_RESOURCE_FLAGS_ALIASABLE,
+});
+
+flags_test!(initialization_flags: FfxFsr2InitializationFlagBits => c::FfxFsr2InitializationFlagBits {
+    FfxFsr2InitializationFlagBits::ENABLE_HIGH_DYNAMIC_RANGE => FFX_FSR2_ENABLE_HIGH_DYNAMIC_RANGE,
+    FfxFsr2InitializationFlagBits::ENABLE_DISPLAY_RESOLUTION_MOTION_VECTORS => FFX_FSR2_ENABLE_DISPLAY_RESOLUTION_MOTION_VECTORS,
+    FfxFsr2InitializationFlagBits::ENABLE_MOTION_VECTORS_JITTER_CANCELLATION => FFX_FSR2_ENABLE_MOTION_VECTORS_JITTER_CANCELLATION,
+    FfxFsr2InitializationFlagBits::ENABLE_DEPTH_INVERTED => FFX_FSR2_ENABLE_DEPTH_INVERTED,
+    FfxFsr2InitializationFlagBits::ENABLE_DEPTH_INFINITE => FFX_FSR2_ENABLE_DEPTH_INFINITE,
+    FfxFsr2InitializationFlagBits::ENABLE_AUTO_EXPOSURE => FFX_FSR2_ENABLE_AUTO_EXPOSURE,
+    FfxFsr2InitializationFlagBits::ENABLE_DYNAMIC_RESOLUTION => FFX_FSR2_ENABLE_DYNAMIC_RESOLUTION,
+    FfxFsr2InitializationFlagBits::ENABLE_TEXTURE1D_USAGE => FFX_FSR2_ENABLE_TEXTURE1D_USAGE,
+    FfxFsr2InitializationFlagBits::ENABLE_DEBUG_CHECKING => FFX_FSR2_ENABLE_DEBUG_CHECKING,
+});
+
+layout_test!(device_capabilities: FfxDeviceCapabilities => c::FfxDeviceCapabilities {
+    minimum_supported_shader_model => minimumSupportedShaderModel,
+    wave_lane_count_min => waveLaneCountMin,
+    wave_lane_count_max => waveLaneCountMax,
+    fp16_supported => fp16Supported,
+    raytracing_supported => raytracingSupported,
+});
+
+layout_test!(dimensions_2d: FfxDimensions2D => c::FfxDimensions2D {
+    width => width,
+    height => height,
+});
+
+layout_test!(int_coords_2d: FfxIntCoords2D => c::FfxIntCoords2D {
+    x => x,
+    y => y,
+});
+
+layout_test!(float_coords_2d: FfxFloatCoords2D => c::FfxFloatCoords2D {
+    x => x,
+    y => y,
+});
+
+layout_test!(resource_description: FfxResourceDescription => c::FfxResourceDescription {
+    ty => type_,
+    surface_format => format,
+    width => width,
+    height => height,
+    depth => depth,
+    mip_count => mipCount,
+    flags => flags,
+});
+
+layout_test!(resource: FfxResource => c::FfxResource {
+    resource => resource,
+    name => name,
+    description => description,
+    state => state,
+    is_depth => isDepth,
+    descriptor_data => descriptorData,
+});
+
+layout_test!(resource_internal: FfxResourceInternal => c::FfxResourceInternal {
+    internal_index => internalIndex,
+});
+
+layout_test!(resource_binding: FfxResourceBinding => c::FfxResourceBinding {
+    slot_index => slotIndex,
+    resource_identifier => resourceIdentifier,
+    name => name,
+});
+
+layout_test!(pipeline_state: FfxPipelineState => c::FfxPipelineState {
+    root_signature => rootSignature,
+    pipeline => pipeline,
+    uav_count => uavCount,
+    srv_count => srvCount,
+    const_count => constCount,
+    uav_resource_bindings => uavResourceBindings,
+    srv_resource_bindings => srvResourceBindings,
+    cv_resource_bindings => cbResourceBindings,
+});
+
+layout_test!(create_resource_description: FfxCreateResourceDescription => c::FfxCreateResourceDescription {
+    heap_type => heapType,
+    resource_description => resourceDescription,
+    initial_state => initalState,
+    init_data_size => initDataSize,
+    init_data => initData,
+    name => name,
+    usage => usage,
+    id => id,
+});
+
+layout_test!(pipeline_description: FfxPipelineDescription => c::FfxPipelineDescription {
+    context_flags => contextFlags,
+    samplers => samplers,
+    sampler_count => samplerCount,
+    root_constant_buffer_size => rootConstantBufferSizes,
+    root_constant_buffer_count => rootConstantBufferCount,
+});
+
+layout_test!(constant_buffer: FfxConstantBuffer => c::FfxConstantBuffer {
+    uint32_size => uint32Size,
+    data => data,
+});
+
+layout_test!(clear_float_job_description: FfxClearFloatJobDescription => c::FfxClearFloatJobDescription {
+    color => color,
+    target => target,
+});
+
+layout_test!(compute_job_description: FfxComputeJobDescription => c::FfxComputeJobDescription {
+    pipeline => pipeline,
+    dimensions => dimensions,
+    srvs => srvs,
+    srv_names => srvNames,
+    uavs => uavs,
+    uav_mip => uavMip,
+    uav_names => uavNames,
+    cbs => cbs,
+    cb_names => cbNames,
+    cb_slot_index => cbSlotIndex,
+});
+
+layout_test!(copy_job_description: FfxCopyJobDescription => c::FfxCopyJobDescription {
+    src => src,
+    dst => dst,
+});
+
+layout_test!(gpu_job: FfxGpuJob => c::FfxGpuJobDescription__bindgen_ty_1 {
+    clear_job => clearJobDescriptor,
+    copy_job => copyJobDescriptor,
+    compute_job => computeJobDescriptor,
+});
+
+layout_test!(gpu_job_description: FfxGpuJobDescription => c::FfxGpuJobDescription {
+    ty => jobType,
+    descriptor => __bindgen_anon_1,
+});
+
+layout_test!(interface: FfxFsr2Interface => c::FfxFsr2Interface {
+    fp_create_backend_context => fpCreateBackendContext,
+    fp_get_device_capabilities => fpGetDeviceCapabilities,
+    fp_destroy_backend_context => fpDestroyBackendContext,
+    fp_create_resource => fpCreateResource,
+    fp_register_resource => fpRegisterResource,
+    fp_unregister_resources => fpUnregisterResources,
+    fp_get_resource_description => fpGetResourceDescription,
+    fp_destroy_resource => fpDestroyResource,
+    fp_create_pipeline => fpCreatePipeline,
+    fp_destroy_pipeline => fpDestroyPipeline,
+    fp_schedule_gpu_job => fpScheduleGpuJob,
+    fp_execute_gpu_jobs => fpExecuteGpuJobs,
+    scratch_buffer => scratchBuffer,
+    scratch_buffer_size => scratchBufferSize,
+});
+
+layout_test!(context_description: FfxFsr2ContextDescription => c::FfxFsr2ContextDescription {
+    flags => flags,
+    max_render_size => maxRenderSize,
+    display_size => displaySize,
+    callbacks => callbacks,
+    device => device,
+    fp_message => fpMessage,
+});
+
+layout_test!(dispatch_description: FfxFsr2DispatchDescription => c::FfxFsr2DispatchDescription {
+    command_list => commandList,
+    color => color,
+    depth => depth,
+    motion_vectors => motionVectors,
+    exposure => exposure,
+    reactive => reactive,
+    transparency_and_composition => transparencyAndComposition,
+    output => output,
+    jitter_offset => jitterOffset,
+    motion_vector_scale => motionVectorScale,
+    render_size => renderSize,
+    enable_sharpening => enableSharpening,
+    sharpness => sharpness,
+    frametime_delta => frameTimeDelta,
+    pre_exposure => preExposure,
+    reset => reset,
+    camera_near => cameraNear,
+    camera_far => cameraFar,
+    camera_vertical_fov => cameraFovAngleVertical,
+    viewspace_to_meters_factor => viewSpaceToMetersFactor,
+    enable_auto_reactive => enableAutoReactive,
+    color_opaque_only => colorOpaqueOnly,
+    auto_tc_threshold => autoTcThreshold,
+    auto_tc_scale => autoTcScale,
+    auto_reactive_scale => autoReactiveScale,
+    auto_reactive_max => autoReactiveMax,
+});
+
+layout_test!(generate_reactive_description: FfxFsr2GenerateReactiveDescription => c::FfxFsr2GenerateReactiveDescription {
+    command_list => commandList,
+    color_opaque_only => colorOpaqueOnly,
+    color_pre_upscale => colorPreUpscale,
+    out_reactive => outReactive,
+    render_size => renderSize,
+    scale => scale,
+    cutoff_threshold => cutoffThreshold,
+    binary_value => binaryValue,
+    flags => flags,
+});
+
+layout_test!(context: FfxFsr2Context => c::FfxFsr2Context {
+    data => data,
+});
+
+#[cfg(feature = "vk")]
+mod vk {
+    use super::*;
+
+    layout_test!(extension_properties: VkExtensionProperties => c::VkExtensionProperties {
+        extension_name => extensionName,
+        spec_version => specVersion,
+    });
+
+    layout_test!(memory_type: VkMemoryType => c::VkMemoryType {
+        property_flags => propertyFlags,
+        heap_index => heapIndex,
+    });
+
+    layout_test!(memory_heap: VkMemoryHeap => c::VkMemoryHeap {
+        size => size,
+        flags => flags,
+    });
+
+    layout_test!(physical_device_memory_properties: VkPhysicalDeviceMemoryProperties => c::VkPhysicalDeviceMemoryProperties {
+        memory_type_count => memoryTypeCount,
+        memory_types => memoryTypes,
+        memory_heap_count => memoryHeapCount,
+        memory_heaps => memoryHeaps,
+    });
+
+    layout_test!(physical_device_limits: VkPhysicalDeviceLimits => c::VkPhysicalDeviceLimits {
+        max_image_dimension_2d => maxImageDimension2D,
+        buffer_image_granularity => bufferImageGranularity,
+        max_compute_work_group_size => maxComputeWorkGroupSize,
+        min_memory_map_alignment => minMemoryMapAlignment,
+        non_coherent_atom_size => nonCoherentAtomSize,
+    });
+
+    layout_test!(physical_device_features: VkPhysicalDeviceFeatures => c::VkPhysicalDeviceFeatures {
+        robust_buffer_access => robustBufferAccess,
+        shader_int16 => shaderInt16,
+        inherited_queries => inheritedQueries,
+    });
+
+    layout_test!(physical_device_properties: VkPhysicalDeviceProperties => c::VkPhysicalDeviceProperties {
+        api_version => apiVersion,
+        driver_version => driverVersion,
+        vendor_id => vendorID,
+        device_id => deviceID,
+        device_type => deviceType,
+        device_name => deviceName,
+        pipeline_cache_uuid => pipelineCacheUUID,
+        limits => limits,
+        sparse_properties => sparseProperties,
+    });
+
+    layout_test!(physical_device_properties2: VkPhysicalDeviceProperties2 => c::VkPhysicalDeviceProperties2 {
+        s_type => sType,
+        p_next => pNext,
+        properties => properties,
+    });
+
+    layout_test!(physical_device_features2: VkPhysicalDeviceFeatures2 => c::VkPhysicalDeviceFeatures2 {
+        s_type => sType,
+        p_next => pNext,
+        features => features,
+    });
+
+    layout_test!(physical_device_subgroup_properties: VkPhysicalDeviceSubgroupProperties => c::VkPhysicalDeviceSubgroupProperties {
+        s_type => sType,
+        p_next => pNext,
+        subgroup_size => subgroupSize,
+        supported_stages => supportedStages,
+        supported_operations => supportedOperations,
+        quad_operations_in_all_stages => quadOperationsInAllStages,
+    });
+
+    layout_test!(physical_device_shader_float16_int8_features: VkPhysicalDeviceShaderFloat16Int8Features => c::VkPhysicalDeviceShaderFloat16Int8Features {
+        s_type => sType,
+        p_next => pNext,
+        shader_float16 => shaderFloat16,
+        shader_int8 => shaderInt8,
+    });
+
+    layout_test!(physical_device_subgroup_size_control_properties: VkPhysicalDeviceSubgroupSizeControlProperties => c::VkPhysicalDeviceSubgroupSizeControlProperties {
+        s_type => sType,
+        p_next => pNext,
+        min_subgroup_size => minSubgroupSize,
+        max_subgroup_size => maxSubgroupSize,
+        max_compute_workgroup_subgroups => maxComputeWorkgroupSubgroups,
+        required_subgroup_size_stages => requiredSubgroupSizeStages,
+    });
+
+    layout_test!(physical_device_subgroup_size_control_features: VkPhysicalDeviceSubgroupSizeControlFeatures => c::VkPhysicalDeviceSubgroupSizeControlFeatures {
+        s_type => sType,
+        p_next => pNext,
+        subgroup_size_control => subgroupSizeControl,
+        compute_full_subgroups => computeFullSubgroups,
+    });
+
+    layout_test!(instance_function_pointer_table: FfxFsr2InstanceFunctionPointerTableVk => c::FfxFsr2InstanceFunctionPointerTableVk {
+        fp_enumerate_device_extension_properties => fpEnumerateDeviceExtensionProperties,
+        fp_get_device_proc_addr => fpGetDeviceProcAddr,
+        fp_get_physical_device_memory_properties => fpGetPhysicalDeviceMemoryProperties,
+        fp_get_physical_device_properties => fpGetPhysicalDeviceProperties,
+        fp_get_physical_device_properties2 => fpGetPhysicalDeviceProperties2,
+        fp_get_physical_device_features2 => fpGetPhysicalDeviceFeatures2,
+    });
+}
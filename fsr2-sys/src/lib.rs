@@ -3,16 +3,63 @@
 
 extern crate core;
 
+#[cfg(all(test, any(feature = "layout-tests", feature = "ash")))]
+#[macro_use]
+mod test_macros;
+#[cfg(test)]
+mod test_util;
+
+mod intercept;
+mod filter;
+
 pub mod error;
 pub mod interface;
 pub mod types;
 pub mod backend;
+pub mod resource_name;
+pub mod resource;
+pub mod format;
+pub mod capabilities;
+pub mod decode;
+pub mod constants;
+pub mod rcas;
+pub mod quality_mode;
+pub mod fsr1;
+pub mod upscaler;
+pub mod metrics;
+pub mod stability;
+pub mod jitter;
+pub mod scene;
+pub mod golden;
+pub mod camera;
+#[cfg(feature = "serde")]
+pub mod capture;
+
+#[cfg(all(test, feature = "layout-tests"))]
+mod layout_tests;
 
 use bitflags::bitflags;
 pub use error::*;
 pub use interface::*;
 pub use types::*;
 pub use backend::*;
+pub use resource_name::*;
+pub use resource::*;
+pub use format::*;
+pub use capabilities::*;
+pub use decode::*;
+pub use constants::*;
+pub use rcas::*;
+pub use fsr1::*;
+pub use upscaler::*;
+pub use metrics::*;
+pub use stability::*;
+pub use jitter::*;
+pub use scene::*;
+pub use golden::*;
+pub use camera::*;
+#[cfg(feature = "serde")]
+pub use capture::*;
 
 pub const FFX_FSR2_VERSION_MAJOR: u32 = 2;
 pub const FFX_FSR2_VERSION_MINOR: u32 = 2;
@@ -53,7 +100,8 @@ pub struct FfxFsr2ContextDescription {
     pub display_size: FfxDimensions2D,
     pub callbacks: FfxFsr2Interface,
     pub device: FfxDevice,
-    pub fp_message: FfxFsr2Message,
+    /// `None` disables messages, see also [`ffx_fsr2_message_noop`].
+    pub fp_message: Option<FfxFsr2Message>,
 }
 
 unsafe impl Send for FfxFsr2ContextDescription {}
@@ -90,6 +138,41 @@ pub struct FfxFsr2DispatchDescription {
     pub auto_reactive_max: f32,
 }
 
+impl Default for FfxFsr2DispatchDescription {
+    /// Null resources and zeros, except a `pre_exposure` and `viewspace_to_meters_factor` of 1.
+    fn default() -> Self {
+        let zero = FfxFloatCoords2D { x: 0.0, y: 0.0 };
+        Self {
+            command_list: std::ptr::null_mut(),
+            color: FfxResource::NULL,
+            depth: FfxResource::NULL,
+            motion_vectors: FfxResource::NULL,
+            exposure: FfxResource::NULL,
+            reactive: FfxResource::NULL,
+            transparency_and_composition: FfxResource::NULL,
+            output: FfxResource::NULL,
+            jitter_offset: zero,
+            motion_vector_scale: zero,
+            render_size: FfxDimensions2D { width: 0, height: 0 },
+            enable_sharpening: false,
+            sharpness: 0.0,
+            frametime_delta: 0.0,
+            pre_exposure: 1.0,
+            reset: false,
+            camera_near: 0.0,
+            camera_far: 0.0,
+            camera_vertical_fov: 0.0,
+            viewspace_to_meters_factor: 1.0,
+            enable_auto_reactive: false,
+            color_opaque_only: FfxResource::NULL,
+            auto_tc_threshold: 0.0,
+            auto_tc_scale: 0.0,
+            auto_reactive_scale: 0.0,
+            auto_reactive_max: 0.0,
+        }
+    }
+}
+
 #[derive(Copy, Clone, Debug)]
 #[repr(C)]
 pub struct FfxFsr2GenerateReactiveDescription {
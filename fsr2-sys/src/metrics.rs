@@ -0,0 +1,431 @@
+//! Full reference image quality metrics on [`CpuImage`]s, for scoring upscaled frames against a native
+//! resolution ground truth. Texels are read as linear RGB, alpha is ignored.
+
+use crate::{CpuImage, FfxSurfaceFormat};
+use std::f32::consts::PI;
+use std::fmt;
+
+/// Viewing condition FLIP uses by default, a 0.7 m distance to a 24" 4K monitor.
+pub const FLIP_PIXELS_PER_DEGREE: f32 = 67.0;
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum ImageMetric {
+    /// Peak signal to noise ratio in dB for a peak of 1, higher is better.
+    Psnr,
+    /// Mean structural similarity of the luminance, 1 for identical images.
+    Ssim,
+    /// FLIP at [`FLIP_PIXELS_PER_DEGREE`], 0 for identical images.
+    Flip,
+}
+
+impl ImageMetric {
+    pub const ALL: [ImageMetric; 3] = [ImageMetric::Psnr, ImageMetric::Ssim, ImageMetric::Flip];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ImageMetric::Psnr => "psnr",
+            ImageMetric::Ssim => "ssim",
+            ImageMetric::Flip => "flip",
+        }
+    }
+
+    pub fn evaluate(self, image: &CpuImage, reference: &CpuImage) -> Result<MetricResult, ImageSizeMismatchError> {
+        match self {
+            ImageMetric::Psnr => psnr(image, reference),
+            ImageMetric::Ssim => ssim(image, reference),
+            ImageMetric::Flip => flip(image, reference, FLIP_PIXELS_PER_DEGREE),
+        }
+    }
+}
+
+/// The value of a metric over the whole image, and its per-pixel error.
+#[derive(Clone, Debug)]
+pub struct MetricResult {
+    pub value: f32,
+    /// `R32Float` of the image size, higher is worse for every metric.
+    pub error_map: CpuImage,
+}
+
+/// The compared images differ in size.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ImageSizeMismatchError {
+    pub image: (u32, u32),
+    pub reference: (u32, u32),
+}
+
+impl fmt::Display for ImageSizeMismatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "image is {}x{}, but the reference is {}x{}",
+            self.image.0, self.image.1, self.reference.0, self.reference.1
+        )
+    }
+}
+
+impl std::error::Error for ImageSizeMismatchError {}
+
+/// Peak signal to noise ratio, infinite for identical images. The error map holds the squared error.
+pub fn psnr(image: &CpuImage, reference: &CpuImage) -> Result<MetricResult, ImageSizeMismatchError> {
+    let (width, height) = check_sizes(image, reference)?;
+    let errors: Vec<f32> = image
+        .texels()
+        .iter()
+        .zip(reference.texels())
+        .map(|(a, b)| (0..3).map(|i| (a[i] - b[i]).powi(2)).sum::<f32>() / 3.0)
+        .collect();
+    let mse = mean(&errors);
+    Ok(MetricResult {
+        value: -10.0 * mse.log10(),
+        error_map: error_map(width, height, &errors),
+    })
+}
+
+/// Structural similarity of the luminance with the usual 11x11 gaussian window. The error map holds
+/// `1 - SSIM`.
+pub fn ssim(image: &CpuImage, reference: &CpuImage) -> Result<MetricResult, ImageSizeMismatchError> {
+    const C1: f32 = 0.01 * 0.01;
+    const C2: f32 = 0.03 * 0.03;
+    let (width, height) = check_sizes(image, reference)?;
+    let x = plane(image, luminance);
+    let y = plane(reference, luminance);
+    let window = gaussian_kernel(1.5, 5);
+    let blur = |values: Vec<f32>| convolve(&values, width, height, &window, &window);
+    let mean_x = blur(x.clone());
+    let mean_y = blur(y.clone());
+    let xx = blur(x.iter().map(|v| v * v).collect());
+    let yy = blur(y.iter().map(|v| v * v).collect());
+    let xy = blur(x.iter().zip(&y).map(|(a, b)| a * b).collect());
+
+    let similarity: Vec<f32> = (0..x.len())
+        .map(|i| {
+            let (mx, my) = (mean_x[i], mean_y[i]);
+            let variance_x = xx[i] - mx * mx;
+            let variance_y = yy[i] - my * my;
+            let covariance = xy[i] - mx * my;
+            ((2.0 * mx * my + C1) * (2.0 * covariance + C2))
+                / ((mx * mx + my * my + C1) * (variance_x + variance_y + C2))
+        })
+        .collect();
+    let errors: Vec<f32> = similarity.iter().map(|s| 1.0 - s).collect();
+    Ok(MetricResult {
+        value: mean(&similarity),
+        error_map: error_map(width, height, &errors),
+    })
+}
+
+/// A FLIP style perceptual difference in `0..=1`, following LDR-FLIP (Andersson et al. 2020) but not bit exact
+/// with its reference implementation. Colours are clamped to `0..=1`.
+pub fn flip(
+    image: &CpuImage,
+    reference: &CpuImage,
+    pixels_per_degree: f32,
+) -> Result<MetricResult, ImageSizeMismatchError> {
+    const COLOR_EXPONENT: f32 = 0.7;
+    const COLOR_COMPRESSION: f32 = 0.4;
+    const COLOR_THRESHOLD: f32 = 0.95;
+    const FEATURE_EXPONENT: f32 = 0.5;
+    let (width, height) = check_sizes(image, reference)?;
+
+    // Colour pipeline: contrast sensitivity filtering in YCxCz, then the HyAB distance of the Hunt adjusted L*a*b*.
+    let filtered = |image: &CpuImage| {
+        let channels: [Vec<f32>; 3] =
+            std::array::from_fn(|channel| plane(image, |texel| linear_rgb_to_ycxcz(clamp_rgb(texel))[channel]));
+        let filtered = csf_filter(channels, width, height, pixels_per_degree);
+        (0..filtered[0].len())
+            .map(|i| {
+                let rgb = ycxcz_to_linear_rgb([filtered[0][i], filtered[1][i], filtered[2][i]]);
+                hunt(linear_rgb_to_lab(rgb.map(|c| c.clamp(0.0, 1.0))))
+            })
+            .collect::<Vec<_>>()
+    };
+    let max_error = hyab(
+        hunt(linear_rgb_to_lab([0.0, 1.0, 0.0])),
+        hunt(linear_rgb_to_lab([0.0, 0.0, 1.0])),
+    )
+    .powf(COLOR_EXPONENT);
+    let color_errors = filtered(image).into_iter().zip(filtered(reference)).map(|(a, b)| {
+        let error = hyab(a, b).powf(COLOR_EXPONENT);
+        let knee = COLOR_COMPRESSION * max_error;
+        if error < knee {
+            error * COLOR_THRESHOLD / knee
+        } else {
+            COLOR_THRESHOLD + (error - knee) / (max_error - knee) * (1.0 - COLOR_THRESHOLD)
+        }
+    });
+
+    // Feature pipeline: differences in edges and points of the normalized luminance.
+    let feature_maps = |image: &CpuImage| {
+        let luminance = plane(image, |texel| (linear_rgb_to_ycxcz(clamp_rgb(texel))[0] + 16.0) / 116.0);
+        features(&luminance, width, height, pixels_per_degree)
+    };
+    let (image_edges, image_points) = feature_maps(image);
+    let (reference_edges, reference_points) = feature_maps(reference);
+
+    let errors: Vec<f32> = color_errors
+        .enumerate()
+        .map(|(i, color_error)| {
+            let feature_difference = (image_edges[i] - reference_edges[i])
+                .abs()
+                .max((image_points[i] - reference_points[i]).abs());
+            let feature_error = (feature_difference / 2f32.sqrt()).powf(FEATURE_EXPONENT);
+            color_error.powf(1.0 - feature_error)
+        })
+        .collect();
+    Ok(MetricResult {
+        value: mean(&errors),
+        error_map: error_map(width, height, &errors),
+    })
+}
+
+fn check_sizes(image: &CpuImage, reference: &CpuImage) -> Result<(u32, u32), ImageSizeMismatchError> {
+    let size = (image.width(), image.height());
+    let reference_size = (reference.width(), reference.height());
+    if size != reference_size {
+        return Err(ImageSizeMismatchError {
+            image: size,
+            reference: reference_size,
+        });
+    }
+    Ok(size)
+}
+
+fn mean(values: &[f32]) -> f32 {
+    values.iter().map(|&v| v as f64).sum::<f64>() as f32 / values.len().max(1) as f32
+}
+
+fn plane(image: &CpuImage, channel: impl Fn([f32; 4]) -> f32) -> Vec<f32> {
+    image.texels().iter().map(|&texel| channel(texel)).collect()
+}
+
+fn error_map(width: u32, height: u32, errors: &[f32]) -> CpuImage {
+    let mut map = CpuImage::new(width, height, FfxSurfaceFormat::R32Float);
+    for (texel, &error) in map.texels_mut().iter_mut().zip(errors) {
+        *texel = [error, 0.0, 0.0, 0.0];
+    }
+    map
+}
+
+fn luminance(texel: [f32; 4]) -> f32 {
+    0.2126 * texel[0] + 0.7152 * texel[1] + 0.0722 * texel[2]
+}
+
+fn clamp_rgb(texel: [f32; 4]) -> [f32; 3] {
+    [texel[0], texel[1], texel[2]].map(|c| c.clamp(0.0, 1.0))
+}
+
+/// Normalized to sum to 1, over `-radius..=radius`.
+fn gaussian_kernel(sigma: f32, radius: i32) -> Vec<f32> {
+    let kernel: Vec<f32> = (-radius..=radius)
+        .map(|x| (-(x * x) as f32 / (2.0 * sigma * sigma)).exp())
+        .collect();
+    let sum: f32 = kernel.iter().sum();
+    kernel.into_iter().map(|w| w / sum).collect()
+}
+
+/// Separable convolution with odd sized kernels, clamped to the edge.
+fn convolve(values: &[f32], width: u32, height: u32, kernel_x: &[f32], kernel_y: &[f32]) -> Vec<f32> {
+    let (width, height) = (width as i32, height as i32);
+    let pass = |values: &[f32], kernel: &[f32], horizontal: bool| {
+        let radius = kernel.len() as i32 / 2;
+        (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(|(x, y)| {
+                kernel
+                    .iter()
+                    .zip(-radius..=radius)
+                    .map(|(weight, offset)| {
+                        let (sx, sy) = if horizontal {
+                            ((x + offset).clamp(0, width - 1), y)
+                        } else {
+                            (x, (y + offset).clamp(0, height - 1))
+                        };
+                        weight * values[(sy * width + sx) as usize]
+                    })
+                    .sum()
+            })
+            .collect::<Vec<f32>>()
+    };
+    pass(&pass(values, kernel_x, true), kernel_y, false)
+}
+
+/// Spatial contrast sensitivity of the achromatic, red-green and blue-yellow channels, each a sum of gaussians
+/// `a * sqrt(PI / b) * exp(-PI^2 * x^2 / b)` over the distance `x` in degrees.
+fn csf_filter(channels: [Vec<f32>; 3], width: u32, height: u32, pixels_per_degree: f32) -> [Vec<f32>; 3] {
+    const TERMS: [[(f32, f32); 2]; 3] = [
+        [(1.0, 0.0047), (0.0, 1e-5)],
+        [(1.0, 0.0053), (0.0, 1e-5)],
+        [(34.1, 0.04), (13.5, 0.025)],
+    ];
+    let max_b = 0.04f32;
+    let radius = (3.0 * (max_b / (2.0 * PI * PI)).sqrt() * pixels_per_degree).ceil() as i32;
+    let mut channels = channels;
+    for (values, terms) in channels.iter_mut().zip(TERMS) {
+        // Each gaussian is separable, their sum is the weighted sum of the normalized blurs.
+        let mut total_weight = 0.0;
+        let mut sum = vec![0.0; values.len()];
+        for (a, b) in terms.into_iter().filter(|(a, _)| *a != 0.0) {
+            let kernel: Vec<f32> = (-radius..=radius)
+                .map(|x| {
+                    let degrees = x as f32 / pixels_per_degree;
+                    (-PI * PI * degrees * degrees / b).exp()
+                })
+                .collect();
+            let kernel_sum: f32 = kernel.iter().sum();
+            let weight = a * (PI / b).sqrt() * kernel_sum * kernel_sum;
+            let kernel: Vec<f32> = kernel.into_iter().map(|w| w / kernel_sum).collect();
+            for (sum, value) in sum.iter_mut().zip(convolve(values, width, height, &kernel, &kernel)) {
+                *sum += weight * value;
+            }
+            total_weight += weight;
+        }
+        *values = sum.into_iter().map(|v| v / total_weight).collect();
+    }
+    channels
+}
+
+/// Edge and point strength, from the first and second derivatives of a gaussian.
+fn features(luminance: &[f32], width: u32, height: u32, pixels_per_degree: f32) -> (Vec<f32>, Vec<f32>) {
+    let sigma = 0.5 * 0.082 * pixels_per_degree;
+    let radius = (3.0 * sigma).ceil() as i32;
+    let smooth = gaussian_kernel(sigma, radius);
+    // Positive weights sum to 1 and negative ones to -1.
+    let normalize = |kernel: Vec<f32>| {
+        let positive: f32 = kernel.iter().filter(|w| **w > 0.0).sum();
+        let negative: f32 = -kernel.iter().filter(|w| **w < 0.0).sum::<f32>();
+        kernel
+            .into_iter()
+            .map(|w| if w > 0.0 { w / positive } else { w / negative })
+            .collect::<Vec<f32>>()
+    };
+    let gaussian = |x: i32| (-(x * x) as f32 / (2.0 * sigma * sigma)).exp();
+    let edge = normalize((-radius..=radius).map(|x| -(x as f32) * gaussian(x)).collect());
+    let point = normalize(
+        (-radius..=radius)
+            .map(|x| ((x * x) as f32 / (sigma * sigma) - 1.0) * gaussian(x))
+            .collect(),
+    );
+    let magnitude = |a: Vec<f32>, b: Vec<f32>| a.iter().zip(&b).map(|(a, b)| a.hypot(*b)).collect::<Vec<f32>>();
+    let edges = magnitude(
+        convolve(luminance, width, height, &edge, &smooth),
+        convolve(luminance, width, height, &smooth, &edge),
+    );
+    let points = magnitude(
+        convolve(luminance, width, height, &point, &smooth),
+        convolve(luminance, width, height, &smooth, &point),
+    );
+    (edges, points)
+}
+
+const WHITE_D65: [f32; 3] = [0.950_428_5, 1.0, 1.088_900_4];
+
+fn linear_rgb_to_xyz([r, g, b]: [f32; 3]) -> [f32; 3] {
+    [
+        0.412_390_8 * r + 0.357_584_3 * g + 0.180_480_8 * b,
+        0.212_639 * r + 0.715_168_7 * g + 0.072_192_3 * b,
+        0.019_330_8 * r + 0.119_194_8 * g + 0.950_532_2 * b,
+    ]
+}
+
+fn xyz_to_linear_rgb([x, y, z]: [f32; 3]) -> [f32; 3] {
+    [
+        3.240_97 * x - 1.537_383 * y - 0.498_611 * z,
+        -0.969_243_6 * x + 1.875_967_5 * y + 0.041_555_1 * z,
+        0.055_63 * x - 0.203_977 * y + 1.056_971_5 * z,
+    ]
+}
+
+fn linear_rgb_to_ycxcz(rgb: [f32; 3]) -> [f32; 3] {
+    let [x, y, z] = linear_rgb_to_xyz(rgb);
+    let [x, y, z] = [x / WHITE_D65[0], y / WHITE_D65[1], z / WHITE_D65[2]];
+    [116.0 * y - 16.0, 500.0 * (x - y), 200.0 * (y - z)]
+}
+
+fn ycxcz_to_linear_rgb([luma, cx, cz]: [f32; 3]) -> [f32; 3] {
+    let y = (luma + 16.0) / 116.0;
+    let x = cx / 500.0 + y;
+    let z = y - cz / 200.0;
+    xyz_to_linear_rgb([x * WHITE_D65[0], y * WHITE_D65[1], z * WHITE_D65[2]])
+}
+
+fn linear_rgb_to_lab(rgb: [f32; 3]) -> [f32; 3] {
+    let [x, y, z] = linear_rgb_to_xyz(rgb);
+    let f = |t: f32| {
+        let delta = 6.0f32 / 29.0;
+        if t > delta.powi(3) {
+            t.cbrt()
+        } else {
+            t / (3.0 * delta * delta) + 4.0 / 29.0
+        }
+    };
+    let [fx, fy, fz] = [f(x / WHITE_D65[0]), f(y / WHITE_D65[1]), f(z / WHITE_D65[2])];
+    [116.0 * fy - 16.0, 500.0 * (fx - fy), 200.0 * (fy - fz)]
+}
+
+/// Scales chroma with lightness, dark colours are harder to tell apart.
+fn hunt([l, a, b]: [f32; 3]) -> [f32; 3] {
+    [l, 0.01 * l * a, 0.01 * l * b]
+}
+
+fn hyab(a: [f32; 3], b: [f32; 3]) -> f32 {
+    (a[0] - b[0]).abs() + (a[1] - b[1]).hypot(a[2] - b[2])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn image(width: u32, height: u32, texel: impl Fn(u32, u32) -> [f32; 4]) -> CpuImage {
+        let mut image = CpuImage::new(width, height, FfxSurfaceFormat::RGBA32Float);
+        for y in 0..height {
+            for x in 0..width {
+                image.store(x as i32, y as i32, texel(x, y));
+            }
+        }
+        image
+    }
+
+    fn checker(x: u32, y: u32) -> [f32; 4] {
+        let value = if (x / 2 + y / 2) & 1 == 0 { 0.2 } else { 0.7 };
+        [value, value * 0.5, value, 1.0]
+    }
+
+    #[test]
+    fn identical_images() {
+        let reference = image(16, 16, checker);
+        let psnr = psnr(&reference, &reference).unwrap();
+        assert_eq!(psnr.value, f32::INFINITY);
+        assert!((ssim(&reference, &reference).unwrap().value - 1.0).abs() < 1e-4);
+        let flip = flip(&reference, &reference, FLIP_PIXELS_PER_DEGREE).unwrap();
+        assert!(flip.value < 1e-4);
+        assert_eq!((flip.error_map.width(), flip.error_map.height()), (16, 16));
+    }
+
+    #[test]
+    fn known_differences() {
+        let reference = image(16, 16, |_, _| [0.5, 0.5, 0.5, 1.0]);
+        let brighter = image(16, 16, |_, _| [0.6, 0.6, 0.6, 1.0]);
+        // An error of 0.1 everywhere is an MSE of 0.01, 20 dB.
+        let result = psnr(&brighter, &reference).unwrap();
+        assert!((result.value - 20.0).abs() < 1e-3);
+        assert!((result.error_map.load(3, 3)[0] - 0.01).abs() < 1e-6);
+
+        // Blurring the checker loses structure, black against white is close to the maximum FLIP error.
+        let checker_image = image(16, 16, checker);
+        let blurred = image(16, 16, |_, _| [0.45, 0.225, 0.45, 1.0]);
+        assert!(ssim(&blurred, &checker_image).unwrap().value < 0.5);
+        let black = image(16, 16, |_, _| [0.0, 0.0, 0.0, 1.0]);
+        let white = image(16, 16, |_, _| [1.0; 4]);
+        let flip_error = flip(&black, &white, FLIP_PIXELS_PER_DEGREE).unwrap().value;
+        assert!(flip_error > 0.9 && flip_error <= 1.0, "{flip_error}");
+        assert!(flip(&brighter, &reference, FLIP_PIXELS_PER_DEGREE).unwrap().value < flip_error);
+    }
+
+    #[test]
+    fn size_mismatch() {
+        let error = ImageMetric::Ssim
+            .evaluate(&image(4, 4, checker), &image(8, 4, checker))
+            .unwrap_err();
+        assert_eq!(error.to_string(), "image is 4x4, but the reference is 8x4");
+    }
+}
@@ -0,0 +1,47 @@
+use crate::{FfxDimensions2D, FfxFsr2QualityMode};
+
+impl FfxFsr2QualityMode {
+    pub const ALL: [FfxFsr2QualityMode; 4] = [
+        FfxFsr2QualityMode::Quality,
+        FfxFsr2QualityMode::Balanced,
+        FfxFsr2QualityMode::Performance,
+        FfxFsr2QualityMode::UltraPerformance,
+    ];
+
+    /// Same as `ffxFsr2GetUpscaleRatioFromQualityMode`, without calling into FSR2.
+    pub fn upscale_ratio(self) -> f32 {
+        match self {
+            FfxFsr2QualityMode::Quality => 1.5,
+            FfxFsr2QualityMode::Balanced => 1.7,
+            FfxFsr2QualityMode::Performance => 2.0,
+            FfxFsr2QualityMode::UltraPerformance => 3.0,
+        }
+    }
+
+    /// Same as `ffxFsr2GetRenderResolutionFromQualityMode`, the display size divided by the ratio and truncated.
+    pub fn render_size(self, display_size: FfxDimensions2D) -> FfxDimensions2D {
+        let ratio = self.upscale_ratio();
+        FfxDimensions2D {
+            width: (display_size.width as f32 / ratio) as u32,
+            height: (display_size.height as f32 / ratio) as u32,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_sizes() {
+        let display_size = FfxDimensions2D {
+            width: 1920,
+            height: 1080,
+        };
+        let sizes = FfxFsr2QualityMode::ALL.map(|mode| {
+            let size = mode.render_size(display_size);
+            (size.width, size.height)
+        });
+        assert_eq!(sizes, [(1280, 720), (1129, 635), (960, 540), (640, 360)]);
+    }
+}
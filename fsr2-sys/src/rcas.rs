@@ -0,0 +1,177 @@
+//! Robust contrast adaptive sharpening (RCAS) on the CPU, the sharpening pass FSR2 runs after upscaling when
+//! `enable_sharpening` is set. It can be used on its own, e.g. to sharpen frames that were not upscaled by FSR2.
+
+use crate::Fsr2RcasConstants;
+
+/// Maximum negative lobe weight, `FSR_RCAS_LIMIT` of FSR1.
+pub(crate) const RCAS_LIMIT: f32 = 0.25 - 1.0 / 16.0;
+
+/// The attenuation FSR2 derives from the `sharpness` of `FfxFsr2DispatchDescription`, clamped to `0..=1`.
+pub fn rcas_attenuation(sharpness: f32) -> f32 {
+    // 0 stops of attenuation at full sharpness, 2 stops at none.
+    let stops = 2.0 - 2.0 * sharpness.clamp(0.0, 1.0);
+    (-stops).exp2()
+}
+
+/// Sharpening with the parameters of the FSR2 RCAS pass.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Rcas {
+    /// Scale of the negative lobe, `1.0` sharpens the most.
+    pub attenuation: f32,
+    /// Reduces sharpening of noisy pixels, FSR2 always enables it.
+    pub denoise: bool,
+}
+
+impl Rcas {
+    pub fn new(sharpness: f32) -> Self {
+        Self {
+            attenuation: rcas_attenuation(sharpness),
+            denoise: true,
+        }
+    }
+
+    pub fn from_constants(constants: &Fsr2RcasConstants) -> Self {
+        Self {
+            attenuation: f32::from_bits(constants.rcas_config[0]),
+            denoise: true,
+        }
+    }
+
+    pub fn constants(&self) -> Fsr2RcasConstants {
+        Fsr2RcasConstants::from_attenuation(self.attenuation)
+    }
+
+    /// Sharpens `width * height` texels in rows. Alpha is passed through, edges are clamped.
+    pub fn apply(&self, width: u32, height: u32, input: &[[f32; 4]]) -> Vec<[f32; 4]> {
+        self.apply_with(width, height, input, |texel| texel)
+    }
+
+    /// Same as [`Self::apply`] on 8-bit unorm texels.
+    pub fn apply_unorm8(&self, width: u32, height: u32, input: &[[u8; 4]]) -> Vec<[u8; 4]> {
+        let texels: Vec<_> = input.iter().map(|texel| texel.map(|c| c as f32 / 255.0)).collect();
+        self.apply_with(width, height, &texels, |texel| {
+            texel.map(|c| (c.clamp(0.0, 1.0) * 255.0).round() as u8)
+        })
+    }
+
+    fn apply_with<T>(&self, width: u32, height: u32, input: &[[f32; 4]], map: impl Fn([f32; 4]) -> T) -> Vec<T> {
+        let (width, height) = (width as usize, height as usize);
+        assert_eq!(
+            input.len(),
+            width * height,
+            "RCAS input must hold width * height texels"
+        );
+        let rgb = |x: usize, y: usize| {
+            let texel = input[y * width + x];
+            [texel[0], texel[1], texel[2]]
+        };
+        (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(|(x, y)| {
+                let neighbours = [
+                    rgb(x, y.saturating_sub(1)),
+                    rgb(x.saturating_sub(1), y),
+                    rgb((x + 1).min(width - 1), y),
+                    rgb(x, (y + 1).min(height - 1)),
+                ];
+                let [r, g, b] = rcas_pixel(rgb(x, y), neighbours, self.attenuation, self.denoise);
+                map([r, g, b, input[y * width + x][3]])
+            })
+            .collect()
+    }
+}
+
+impl Default for Rcas {
+    /// The default `sharpness` of the FSR2 samples, 0.8.
+    fn default() -> Self {
+        Self::new(0.8)
+    }
+}
+
+/// `FsrRcasF` of FSR1 on the centre `e` and its neighbours above, left, right and below.
+pub(crate) fn rcas_pixel(e: [f32; 3], [b, d, f, h]: [[f32; 3]; 4], attenuation: f32, denoise: bool) -> [f32; 3] {
+    let mut lobe = f32::MIN;
+    for i in 0..3 {
+        let min4 = b[i].min(d[i]).min(f[i]).min(h[i]);
+        let max4 = b[i].max(d[i]).max(f[i]).max(h[i]);
+        let hit_min = min4 / (4.0 * max4);
+        let hit_max = (1.0 - max4) / (4.0 * min4 - 4.0);
+        let channel_lobe = (-hit_min).max(hit_max);
+        if channel_lobe.is_finite() {
+            lobe = lobe.max(channel_lobe);
+        }
+    }
+    if lobe == f32::MIN {
+        return e;
+    }
+
+    let noise = if denoise {
+        let weighted_luma = |c: [f32; 3]| c[2] * 0.5 + (c[0] * 0.5 + c[1]);
+        let lumas = [b, d, f, h].map(weighted_luma);
+        let center_luma = weighted_luma(e);
+        let range =
+            lumas.iter().fold(center_luma, |max, &l| max.max(l)) - lumas.iter().fold(center_luma, |min, &l| min.min(l));
+        let noise = if range > 0.0 {
+            ((0.25 * lumas.iter().sum::<f32>() - center_luma).abs() / range).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        -0.5 * noise + 1.0
+    } else {
+        1.0
+    };
+
+    let lobe = (-RCAS_LIMIT).max(lobe.min(0.0)) * attenuation * noise;
+    let rcp = 1.0 / (4.0 * lobe + 1.0);
+    std::array::from_fn(|i| (lobe * (b[i] + d[i] + h[i] + f[i]) + e[i]) * rcp)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dot(center: f32, neighbour: f32) -> Vec<[f32; 4]> {
+        let mut texels = vec![[neighbour, neighbour, neighbour, 0.5]; 9];
+        texels[4] = [center, center, center, 0.5];
+        texels
+    }
+
+    #[test]
+    fn known_outputs() {
+        // A 0.5 dot on 0.25 hits the lobe limit: the lobe is -0.1875, the noise factor 0.5 with denoise.
+        let rcas = Rcas::new(1.0);
+        assert_eq!(rcas.attenuation, 1.0);
+        let output = rcas.apply(3, 3, &dot(0.5, 0.25));
+        assert!((output[4][0] - 0.65).abs() < 1e-6);
+        assert_eq!(output[4][3], 0.5);
+
+        let output = Rcas { denoise: false, ..rcas }.apply(3, 3, &dot(0.5, 0.25));
+        assert!((output[4][0] - 1.25).abs() < 1e-5);
+
+        let dot8 = dot(0.5, 0.25)
+            .iter()
+            .map(|t| t.map(|c| (c * 255.0).round() as u8))
+            .collect::<Vec<_>>();
+        assert_eq!(rcas.apply_unorm8(3, 3, &dot8)[4], [166, 166, 166, 128]);
+
+        let flat = vec![[0.3, 0.6, 0.9, 1.0]; 12];
+        for (output, input) in Rcas::default().apply(4, 3, &flat).iter().zip(&flat) {
+            assert!(output.iter().zip(input).all(|(o, i)| (o - i).abs() < 1e-6));
+        }
+    }
+
+    #[test]
+    fn sharpness_mapping() {
+        assert_eq!(rcas_attenuation(0.0), 0.25);
+        assert_eq!(rcas_attenuation(0.5), 0.5);
+        assert_eq!(rcas_attenuation(2.0), 1.0);
+
+        let constants = Rcas::new(0.8).constants();
+        assert!((constants.sharpness() - 0.8).abs() < 1e-6);
+        assert_eq!(Rcas::from_constants(&constants), Rcas::new(0.8));
+        assert_eq!(
+            Rcas::new(1.0).constants().rcas_config,
+            [1.0f32.to_bits(), 0x3c00_3c00, 0, 0]
+        );
+    }
+}
@@ -0,0 +1,195 @@
+use crate::{
+    FfxDimensions2D, FfxFsr2Context, FfxFsr2DispatchDescription, FfxFsr2InitializationFlagBits,
+    FfxResource, FfxResourceDescription, ResourceName,
+};
+use std::marker::PhantomData;
+
+/// An `FfxResource` obtained from a backend helper for a specific FSR2 context. The wrapper can not outlive that
+/// context, but the `FfxResource` from [`Self::raw`] or `From` is not tied to it anymore, so the dispatch
+/// description does not enforce it.
+#[derive(Copy, Clone, Debug)]
+#[repr(transparent)]
+pub struct Fsr2Resource<'ctx> {
+    resource: FfxResource,
+    _context: PhantomData<&'ctx FfxFsr2Context>,
+}
+
+impl<'ctx> Fsr2Resource<'ctx> {
+    /// # Safety
+    /// `resource` must describe a resource that is valid to use with the context `'ctx` refers to.
+    pub unsafe fn from_raw(resource: FfxResource) -> Self {
+        Self {
+            resource,
+            _context: PhantomData,
+        }
+    }
+
+    pub fn raw(&self) -> FfxResource {
+        self.resource
+    }
+
+    pub fn description(&self) -> &FfxResourceDescription {
+        &self.resource.description
+    }
+
+    pub fn name(&self) -> ResourceName {
+        self.resource.name()
+    }
+
+    /// Debug-asserts that the resource is large enough to be bound to `slot`.
+    pub fn debug_assert_fits(
+        &self,
+        slot: Fsr2ResourceSlot,
+        render_size: FfxDimensions2D,
+        display_size: FfxDimensions2D,
+        flags: FfxFsr2InitializationFlagBits,
+    ) {
+        debug_assert_resource_fits(&self.resource, slot, render_size, display_size, flags);
+    }
+}
+
+impl<'ctx> From<Fsr2Resource<'ctx>> for FfxResource {
+    fn from(resource: Fsr2Resource<'ctx>) -> Self {
+        resource.resource
+    }
+}
+
+/// The resource inputs and outputs of `FfxFsr2DispatchDescription`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Fsr2ResourceSlot {
+    Color,
+    Depth,
+    MotionVectors,
+    Exposure,
+    Reactive,
+    TransparencyAndComposition,
+    Output,
+    ColorOpaqueOnly,
+}
+
+impl Fsr2ResourceSlot {
+    pub const ALL: [Fsr2ResourceSlot; 8] = [
+        Fsr2ResourceSlot::Color,
+        Fsr2ResourceSlot::Depth,
+        Fsr2ResourceSlot::MotionVectors,
+        Fsr2ResourceSlot::Exposure,
+        Fsr2ResourceSlot::Reactive,
+        Fsr2ResourceSlot::TransparencyAndComposition,
+        Fsr2ResourceSlot::Output,
+        Fsr2ResourceSlot::ColorOpaqueOnly,
+    ];
+
+    /// The minimum size of a resource bound to this slot.
+    pub fn expected_size(
+        &self,
+        render_size: FfxDimensions2D,
+        display_size: FfxDimensions2D,
+        flags: FfxFsr2InitializationFlagBits,
+    ) -> FfxDimensions2D {
+        match self {
+            Fsr2ResourceSlot::Output => display_size,
+            Fsr2ResourceSlot::MotionVectors
+                if flags.contains(FfxFsr2InitializationFlagBits::ENABLE_DISPLAY_RESOLUTION_MOTION_VECTORS) =>
+            {
+                display_size
+            }
+            Fsr2ResourceSlot::Exposure => FfxDimensions2D {
+                width: 1,
+                height: 1,
+            },
+            _ => render_size,
+        }
+    }
+
+    pub fn get(&self, description: &FfxFsr2DispatchDescription) -> FfxResource {
+        match self {
+            Fsr2ResourceSlot::Color => description.color,
+            Fsr2ResourceSlot::Depth => description.depth,
+            Fsr2ResourceSlot::MotionVectors => description.motion_vectors,
+            Fsr2ResourceSlot::Exposure => description.exposure,
+            Fsr2ResourceSlot::Reactive => description.reactive,
+            Fsr2ResourceSlot::TransparencyAndComposition => description.transparency_and_composition,
+            Fsr2ResourceSlot::Output => description.output,
+            Fsr2ResourceSlot::ColorOpaqueOnly => description.color_opaque_only,
+        }
+    }
+}
+
+fn debug_assert_resource_fits(
+    resource: &FfxResource,
+    slot: Fsr2ResourceSlot,
+    render_size: FfxDimensions2D,
+    display_size: FfxDimensions2D,
+    flags: FfxFsr2InitializationFlagBits,
+) {
+    let expected = slot.expected_size(render_size, display_size, flags);
+    debug_assert!(
+        resource.description.width >= expected.width && resource.description.height >= expected.height,
+        "{slot:?} resource {:?} is {}x{}, but must be at least {}x{}",
+        resource.name(),
+        resource.description.width,
+        resource.description.height,
+        expected.width,
+        expected.height,
+    );
+}
+
+impl FfxFsr2DispatchDescription {
+    /// Debug-asserts that every non-null resource is large enough for the slot it is bound to.
+    /// `display_size` and `flags` must match the ones the context was created with.
+    pub fn debug_assert_resource_sizes(
+        &self,
+        display_size: FfxDimensions2D,
+        flags: FfxFsr2InitializationFlagBits,
+    ) {
+        for slot in Fsr2ResourceSlot::ALL {
+            let resource = slot.get(self);
+            if !resource.resource.is_null() {
+                debug_assert_resource_fits(&resource, slot, self.render_size, display_size, flags);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FfxResourceState, FfxResourceType};
+
+    fn texture(width: u32, height: u32) -> Fsr2Resource<'static> {
+        let mut resource = FfxResource::NULL.with_name("test");
+        resource.description.ty = FfxResourceType::Texture2D;
+        resource.description.width = width;
+        resource.description.height = height;
+        resource.state = FfxResourceState::COMPUTE_READ;
+        unsafe { Fsr2Resource::from_raw(resource) }
+    }
+
+    const RENDER: FfxDimensions2D = FfxDimensions2D { width: 1280, height: 720 };
+    const DISPLAY: FfxDimensions2D = FfxDimensions2D { width: 1920, height: 1080 };
+
+    #[test]
+    fn render_size_slots() {
+        let flags = FfxFsr2InitializationFlagBits::empty();
+        texture(1280, 720).debug_assert_fits(Fsr2ResourceSlot::Color, RENDER, DISPLAY, flags);
+        texture(1280, 720).debug_assert_fits(Fsr2ResourceSlot::MotionVectors, RENDER, DISPLAY, flags);
+        texture(1, 1).debug_assert_fits(Fsr2ResourceSlot::Exposure, RENDER, DISPLAY, flags);
+        texture(1920, 1080).debug_assert_fits(Fsr2ResourceSlot::Output, RENDER, DISPLAY, flags);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic]
+    fn output_too_small() {
+        let flags = FfxFsr2InitializationFlagBits::empty();
+        texture(1280, 720).debug_assert_fits(Fsr2ResourceSlot::Output, RENDER, DISPLAY, flags);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic]
+    fn display_resolution_motion_vectors() {
+        let flags = FfxFsr2InitializationFlagBits::ENABLE_DISPLAY_RESOLUTION_MOTION_VECTORS;
+        texture(1280, 720).debug_assert_fits(Fsr2ResourceSlot::MotionVectors, RENDER, DISPLAY, flags);
+    }
+}
@@ -0,0 +1,159 @@
+use std::fmt;
+use widestring::{WideCStr, WideChar, WideString};
+
+#[allow(nonstandard_style)]
+type wchar_t = WideChar;
+
+/// Number of `wchar_t` units in the fixed size name arrays, including the null terminator.
+pub const FFX_RESOURCE_NAME_SIZE: usize = 64;
+
+/// A null terminated, fixed size resource name as used by `FfxResource::name`, `FfxResourceBinding::name`
+/// and the name arrays in `FfxComputeJobDescription`.
+///
+/// `wchar_t` is 2 bytes (UTF-16) on Windows and 4 bytes (UTF-32) everywhere else, this type handles both.
+/// Names longer than 63 units are truncated without splitting a surrogate pair.
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct ResourceName([wchar_t; FFX_RESOURCE_NAME_SIZE]);
+
+impl ResourceName {
+    pub const EMPTY: Self = Self([0; FFX_RESOURCE_NAME_SIZE]);
+
+    pub fn new(name: &str) -> Self {
+        let wide = WideString::from_str(name);
+        let units = wide.as_slice();
+        let mut len = units
+            .iter()
+            .position(|&unit| unit == 0)
+            .unwrap_or(units.len())
+            .min(FFX_RESOURCE_NAME_SIZE - 1);
+        // Never cut a UTF-16 surrogate pair in half. UTF-32 units are never surrogates.
+        if len > 0 && len < units.len() && (0xD800..0xDC00).contains(&units[len - 1]) {
+            len -= 1;
+        }
+
+        let mut raw = [0; FFX_RESOURCE_NAME_SIZE];
+        raw[..len].copy_from_slice(&units[..len]);
+        Self(raw)
+    }
+
+    pub fn from_raw(raw: [wchar_t; FFX_RESOURCE_NAME_SIZE]) -> Self {
+        Self(raw)
+    }
+
+    pub fn into_raw(self) -> [wchar_t; FFX_RESOURCE_NAME_SIZE] {
+        self.0
+    }
+
+    pub fn as_raw(&self) -> &[wchar_t; FFX_RESOURCE_NAME_SIZE] {
+        &self.0
+    }
+
+    /// Pointer to the null terminated name, valid for as long as `self` is.
+    pub fn as_ptr(&self) -> *const wchar_t {
+        self.0.as_ptr()
+    }
+
+    /// Decodes the name, replacing invalid units with U+FFFD. If the array is not null terminated
+    /// all 64 units are used.
+    pub fn to_string_lossy(&self) -> String {
+        match WideCStr::from_slice_truncate(&self.0) {
+            Ok(name) => name.to_string_lossy(),
+            Err(_) => WideString::from_vec(self.0.to_vec()).to_string_lossy(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0[0] == 0
+    }
+
+    /// Decodes a null terminated wide string, returning `None` for a null pointer.
+    ///
+    /// # Safety
+    /// `name` must either be null or point to a valid, null terminated `wchar_t` string.
+    pub unsafe fn from_ptr_lossy(name: *const wchar_t) -> Option<String> {
+        if name.is_null() {
+            None
+        } else {
+            Some(WideCStr::from_ptr_str(name).to_string_lossy())
+        }
+    }
+}
+
+impl Default for ResourceName {
+    fn default() -> Self {
+        Self::EMPTY
+    }
+}
+
+impl From<&str> for ResourceName {
+    fn from(name: &str) -> Self {
+        Self::new(name)
+    }
+}
+
+impl From<[wchar_t; FFX_RESOURCE_NAME_SIZE]> for ResourceName {
+    fn from(raw: [wchar_t; FFX_RESOURCE_NAME_SIZE]) -> Self {
+        Self(raw)
+    }
+}
+
+impl From<ResourceName> for String {
+    fn from(name: ResourceName) -> Self {
+        name.to_string_lossy()
+    }
+}
+
+impl fmt::Display for ResourceName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_string_lossy())
+    }
+}
+
+impl fmt::Debug for ResourceName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.to_string_lossy(), f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let name = ResourceName::new("FSR2_InputColor");
+        assert_eq!(name.to_string_lossy(), "FSR2_InputColor");
+        assert_eq!(name.as_raw()[15], 0);
+    }
+
+    #[test]
+    fn truncates_to_63_units() {
+        let long = "a".repeat(100);
+        let name = ResourceName::new(&long);
+        assert_eq!(name.to_string_lossy(), "a".repeat(63));
+        assert_eq!(name.as_raw()[63], 0);
+    }
+
+    #[test]
+    fn truncation_keeps_characters_whole() {
+        let long = format!("{}{}", "a".repeat(62), "\u{1F600}");
+        let name = ResourceName::new(&long);
+        let decoded = name.to_string_lossy();
+        assert!(!decoded.contains('\u{FFFD}'));
+        assert!(decoded.starts_with(&"a".repeat(62)));
+    }
+
+    #[test]
+    fn stops_at_interior_nul() {
+        let name = ResourceName::new("abc\0def");
+        assert_eq!(name.to_string_lossy(), "abc");
+    }
+
+    #[test]
+    fn null_pointer() {
+        assert_eq!(unsafe { ResourceName::from_ptr_lossy(std::ptr::null()) }, None);
+        let name = ResourceName::new("depth");
+        assert_eq!(unsafe { ResourceName::from_ptr_lossy(name.as_ptr()) }, Some("depth".to_string()));
+    }
+}
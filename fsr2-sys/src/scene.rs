@@ -0,0 +1,482 @@
+//! A deterministic synthetic scene rendered on the CPU, providing every FSR2 input at render resolution together
+//! with a supersampled ground truth at display resolution. Objects are camera facing shapes at fixed distances,
+//! which keeps the rasteriser trivial while still producing parallax, disocclusions and thin features.
+
+use crate::{
+    ffx_fsr2_get_jitter_offset, ffx_fsr2_get_jitter_phase_count, ffx_get_texture_resource_cpu, CpuImage,
+    FfxDimensions2D, FfxFloatCoords2D, FfxFsr2DispatchDescription, FfxFsr2InitializationFlagBits, FfxResourceState,
+    FfxSurfaceFormat,
+};
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum SceneShape {
+    Rect { half_extent: [f32; 2] },
+    Disc { radius: f32 },
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum SceneMaterial {
+    Opaque,
+    /// Opaque with a checker pattern of `frequency` squares per world unit, moving with the object.
+    Textured {
+        frequency: f32,
+    },
+    /// Blended over the opaque surfaces without writing depth or motion, marked in the reactive mask.
+    Particle {
+        alpha: f32,
+    },
+    /// Opaque with stripes scrolling by `speed` world units per frame that the motion vectors do not follow,
+    /// marked in the transparency and composition mask.
+    Animated {
+        speed: f32,
+    },
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct SceneObject {
+    pub shape: SceneShape,
+    /// Centre in world space at frame 0. The camera looks along +z, so z is the distance to the camera plane.
+    pub position: [f32; 3],
+    /// Movement in x and y per frame.
+    pub velocity: [f32; 2],
+    pub color: [f32; 3],
+    pub material: SceneMaterial,
+}
+
+impl SceneObject {
+    fn center(&self, frame: u32) -> [f32; 2] {
+        [
+            self.position[0] + self.velocity[0] * frame as f32,
+            self.position[1] + self.velocity[1] * frame as f32,
+        ]
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct SceneDescription {
+    pub render_size: FfxDimensions2D,
+    pub display_size: FfxDimensions2D,
+    /// Only the depth conventions are used.
+    pub flags: FfxFsr2InitializationFlagBits,
+    pub camera_near: f32,
+    /// Ignored for the depth buffer with `ENABLE_DEPTH_INFINITE`.
+    pub camera_far: f32,
+    /// In radians.
+    pub camera_vertical_fov: f32,
+    /// Camera movement in x and y per frame.
+    pub camera_pan: [f32; 2],
+    /// Frames at which the camera jumps to another view, FSR2 has to be reset there.
+    pub cuts: Vec<u32>,
+    pub objects: Vec<SceneObject>,
+    /// Samples per axis of each ground truth pixel.
+    pub ground_truth_samples: u32,
+}
+
+impl SceneDescription {
+    /// A scene with a textured background, moving objects, thin wires, particles and an animated surface, a slow
+    /// camera pan and a cut at frame 32.
+    pub fn new(render_size: FfxDimensions2D, display_size: FfxDimensions2D) -> Self {
+        let wire = |x: f32| SceneObject {
+            shape: SceneShape::Rect {
+                half_extent: [0.01, 6.0],
+            },
+            position: [x, 0.0, 15.0],
+            velocity: [0.0; 2],
+            color: [0.95, 0.95, 0.9],
+            material: SceneMaterial::Opaque,
+        };
+        let particle = |i: u32| SceneObject {
+            shape: SceneShape::Disc { radius: 0.3 },
+            position: [-3.0 + 1.3 * i as f32, -4.0 + 0.7 * (i % 3) as f32, 8.0],
+            velocity: [0.01 * (i % 2) as f32, 0.08],
+            color: [1.0, 0.6, 0.2],
+            material: SceneMaterial::Particle { alpha: 0.5 },
+        };
+        let mut objects = vec![
+            SceneObject {
+                shape: SceneShape::Rect {
+                    half_extent: [100.0, 100.0],
+                },
+                position: [0.0, 0.0, 40.0],
+                velocity: [0.0; 2],
+                color: [0.35, 0.4, 0.5],
+                material: SceneMaterial::Textured { frequency: 1.0 },
+            },
+            SceneObject {
+                shape: SceneShape::Rect {
+                    half_extent: [1.5, 1.5],
+                },
+                position: [-4.0, 0.0, 12.0],
+                velocity: [0.15, 0.0],
+                color: [0.8, 0.2, 0.15],
+                material: SceneMaterial::Textured { frequency: 2.0 },
+            },
+            SceneObject {
+                shape: SceneShape::Disc { radius: 1.2 },
+                position: [3.0, 1.5, 10.0],
+                velocity: [0.0, -0.05],
+                color: [0.2, 0.7, 0.3],
+                material: SceneMaterial::Opaque,
+            },
+            SceneObject {
+                shape: SceneShape::Rect {
+                    half_extent: [1.0, 1.0],
+                },
+                position: [0.0, -3.0, 9.0],
+                velocity: [0.0; 2],
+                color: [0.5, 0.3, 0.8],
+                material: SceneMaterial::Animated { speed: 0.1 },
+            },
+        ];
+        objects.extend([-2.0, 0.5, 5.0].map(wire));
+        objects.extend((0..6).map(particle));
+        Self {
+            render_size,
+            display_size,
+            flags: FfxFsr2InitializationFlagBits::empty(),
+            camera_near: 0.1,
+            camera_far: 100.0,
+            camera_vertical_fov: 60f32.to_radians(),
+            camera_pan: [0.05, 0.0],
+            cuts: vec![32],
+            objects,
+            ground_truth_samples: 4,
+        }
+    }
+}
+
+/// The inputs of one frame, in the formats FSR2 expects.
+#[derive(Clone, Debug)]
+pub struct SceneFrame {
+    pub index: u32,
+    /// `RGBA16Float` at render size, one sample per pixel at the jittered pixel centre.
+    pub color: CpuImage,
+    /// `R32Float` device depth in the convention of the description's flags.
+    pub depth: CpuImage,
+    /// `RG16Float` motion to the previous frame in render pixels, without jitter.
+    pub motion_vectors: CpuImage,
+    /// `R8Unorm`.
+    pub reactive: CpuImage,
+    /// `R8Unorm`.
+    pub transparency_and_composition: CpuImage,
+    /// `RGBA16Float` at display size, supersampled without jitter.
+    pub ground_truth: CpuImage,
+    pub jitter_offset: FfxFloatCoords2D,
+    pub motion_vector_scale: FfxFloatCoords2D,
+    pub camera_near: f32,
+    pub camera_far: f32,
+    pub camera_vertical_fov: f32,
+    pub frametime_delta: f32,
+    /// The first frame and cuts.
+    pub reset: bool,
+}
+
+impl SceneFrame {
+    /// A dispatch of this frame into `output` on the CPU backend. The resources point into the frame, which must
+    /// not move or be dropped before the dispatch.
+    pub fn dispatch_description(&mut self, output: &mut CpuImage) -> FfxFsr2DispatchDescription {
+        let read = FfxResourceState::COMPUTE_READ;
+        FfxFsr2DispatchDescription {
+            color: ffx_get_texture_resource_cpu(&mut self.color, Some("color"), read, false),
+            depth: ffx_get_texture_resource_cpu(&mut self.depth, Some("depth"), read, true),
+            motion_vectors: ffx_get_texture_resource_cpu(&mut self.motion_vectors, Some("motion_vectors"), read, false),
+            reactive: ffx_get_texture_resource_cpu(&mut self.reactive, Some("reactive"), read, false),
+            transparency_and_composition: ffx_get_texture_resource_cpu(
+                &mut self.transparency_and_composition,
+                Some("transparency_and_composition"),
+                read,
+                false,
+            ),
+            output: ffx_get_texture_resource_cpu(output, Some("output"), FfxResourceState::UNORDERED_ACCESS, false),
+            jitter_offset: self.jitter_offset,
+            motion_vector_scale: self.motion_vector_scale,
+            render_size: FfxDimensions2D {
+                width: self.color.width(),
+                height: self.color.height(),
+            },
+            frametime_delta: self.frametime_delta,
+            reset: self.reset,
+            camera_near: self.camera_near,
+            camera_far: self.camera_far,
+            camera_vertical_fov: self.camera_vertical_fov,
+            ..Default::default()
+        }
+    }
+}
+
+/// The surface seen by a sample.
+struct Hit {
+    color: [f32; 3],
+    /// Distance of the opaque surface.
+    distance: f32,
+    /// World space motion of the opaque surface since the previous frame.
+    velocity: [f32; 2],
+    reactive: f32,
+    composition: f32,
+}
+
+/// Renders the frames of a [`SceneDescription`].
+#[derive(Clone, Debug)]
+pub struct SyntheticScene {
+    description: SceneDescription,
+}
+
+impl SyntheticScene {
+    pub fn new(description: SceneDescription) -> Self {
+        Self { description }
+    }
+
+    pub fn description(&self) -> &SceneDescription {
+        &self.description
+    }
+
+    /// Camera position in x and y at `frame`, including the cuts before it.
+    pub fn camera_position(&self, frame: u32) -> [f32; 2] {
+        const CUT_OFFSET: [f32; 2] = [25.0, 7.0];
+        let cuts = self.description.cuts.iter().filter(|&&cut| cut <= frame).count() as f32;
+        [
+            self.description.camera_pan[0] * frame as f32 + CUT_OFFSET[0] * cuts,
+            self.description.camera_pan[1] * frame as f32 + CUT_OFFSET[1] * cuts,
+        ]
+    }
+
+    pub fn render(&self, frame: u32) -> SceneFrame {
+        let description = &self.description;
+        let render_size = description.render_size;
+        let phase_count = ffx_fsr2_get_jitter_phase_count(render_size.width, description.display_size.width).max(1);
+        let jitter = ffx_fsr2_get_jitter_offset(frame as i32, phase_count).unwrap();
+
+        let (width, height) = (render_size.width, render_size.height);
+        let mut color = CpuImage::new(width, height, FfxSurfaceFormat::RGBA16Float);
+        let mut depth = CpuImage::new(width, height, FfxSurfaceFormat::R32Float);
+        let mut motion_vectors = CpuImage::new(width, height, FfxSurfaceFormat::RG16Float);
+        let mut reactive = CpuImage::new(width, height, FfxSurfaceFormat::R8Unorm);
+        let mut composition = CpuImage::new(width, height, FfxSurfaceFormat::R8Unorm);
+        let camera = self.camera_position(frame);
+        let previous_camera = self.camera_position(frame.saturating_sub(1));
+        for y in 0..height {
+            for x in 0..width {
+                // The jittered projection shows the scene at `center - jitter` in the pixel center.
+                let uv = [
+                    (x as f32 + 0.5 - jitter.x) / width as f32,
+                    (y as f32 + 0.5 - jitter.y) / height as f32,
+                ];
+                let hit = self.trace(frame, uv, render_size);
+                let (x, y) = (x as i32, y as i32);
+                color.store(x, y, [hit.color[0], hit.color[1], hit.color[2], 1.0]);
+                depth.store(x, y, [self.device_depth(hit.distance), 0.0, 0.0, 0.0]);
+                reactive.store(x, y, [hit.reactive, 0.0, 0.0, 0.0]);
+                composition.store(x, y, [hit.composition, 0.0, 0.0, 0.0]);
+
+                // Motion of the surface point seen at the unjittered pixel center.
+                let uv = [(x as f32 + 0.5) / width as f32, (y as f32 + 0.5) / height as f32];
+                let point = self.unproject(uv, hit.distance, camera, render_size);
+                let previous_point = [point[0] - hit.velocity[0], point[1] - hit.velocity[1]];
+                let previous_uv = self.project(previous_point, hit.distance, previous_camera, render_size);
+                let motion = [
+                    (previous_uv[0] - uv[0]) * width as f32,
+                    (previous_uv[1] - uv[1]) * height as f32,
+                ];
+                motion_vectors.store(x, y, [motion[0], motion[1], 0.0, 0.0]);
+            }
+        }
+
+        let display_size = description.display_size;
+        let samples = description.ground_truth_samples.max(1);
+        let mut ground_truth = CpuImage::new(display_size.width, display_size.height, FfxSurfaceFormat::RGBA16Float);
+        for y in 0..display_size.height {
+            for x in 0..display_size.width {
+                let mut sum = [0.0f32; 3];
+                for sample in 0..samples * samples {
+                    let offset = [
+                        ((sample % samples) as f32 + 0.5) / samples as f32,
+                        ((sample / samples) as f32 + 0.5) / samples as f32,
+                    ];
+                    let uv = [
+                        (x as f32 + offset[0]) / display_size.width as f32,
+                        (y as f32 + offset[1]) / display_size.height as f32,
+                    ];
+                    let hit = self.trace(frame, uv, display_size);
+                    for (sum, channel) in sum.iter_mut().zip(hit.color) {
+                        *sum += channel;
+                    }
+                }
+                let [r, g, b] = sum.map(|channel| channel / (samples * samples) as f32);
+                ground_truth.store(x as i32, y as i32, [r, g, b, 1.0]);
+            }
+        }
+
+        SceneFrame {
+            index: frame,
+            color,
+            depth,
+            motion_vectors,
+            reactive,
+            transparency_and_composition: composition,
+            ground_truth,
+            jitter_offset: jitter,
+            motion_vector_scale: FfxFloatCoords2D { x: 1.0, y: 1.0 },
+            camera_near: description.camera_near,
+            camera_far: description.camera_far,
+            camera_vertical_fov: description.camera_vertical_fov,
+            frametime_delta: 1000.0 / 60.0,
+            reset: frame == 0 || description.cuts.contains(&frame),
+        }
+    }
+
+    /// Half the extent of the view at distance 1.
+    fn view_extent(&self, size: FfxDimensions2D) -> [f32; 2] {
+        let half_height = (self.description.camera_vertical_fov * 0.5).tan();
+        [half_height * size.width as f32 / size.height as f32, half_height]
+    }
+
+    /// World position at `distance` seen at `uv`, with y up.
+    fn unproject(&self, uv: [f32; 2], distance: f32, camera: [f32; 2], size: FfxDimensions2D) -> [f32; 2] {
+        let extent = self.view_extent(size);
+        [
+            camera[0] + (uv[0] * 2.0 - 1.0) * extent[0] * distance,
+            camera[1] + (1.0 - uv[1] * 2.0) * extent[1] * distance,
+        ]
+    }
+
+    fn project(&self, point: [f32; 2], distance: f32, camera: [f32; 2], size: FfxDimensions2D) -> [f32; 2] {
+        let extent = self.view_extent(size);
+        [
+            ((point[0] - camera[0]) / (extent[0] * distance) + 1.0) * 0.5,
+            (1.0 - (point[1] - camera[1]) / (extent[1] * distance)) * 0.5,
+        ]
+    }
+
+    fn trace(&self, frame: u32, uv: [f32; 2], size: FfxDimensions2D) -> Hit {
+        let camera = self.camera_position(frame);
+        let mut hit = Hit {
+            color: [0.0; 3],
+            distance: self.description.camera_far,
+            velocity: [0.0; 2],
+            reactive: 0.0,
+            composition: 0.0,
+        };
+        let covers = |object: &SceneObject| {
+            let point = self.unproject(uv, object.position[2], camera, size);
+            let center = object.center(frame);
+            let local = [point[0] - center[0], point[1] - center[1]];
+            let inside = match object.shape {
+                SceneShape::Rect { half_extent } => {
+                    local[0].abs() <= half_extent[0] && local[1].abs() <= half_extent[1]
+                }
+                SceneShape::Disc { radius } => local[0].hypot(local[1]) <= radius,
+            };
+            inside.then_some(local)
+        };
+
+        for object in &self.description.objects {
+            if matches!(object.material, SceneMaterial::Particle { .. }) || object.position[2] >= hit.distance {
+                continue;
+            }
+            let Some(local) = covers(object) else {
+                continue;
+            };
+            let checker = |local: [f32; 2], frequency: f32| {
+                let cell = (local[0] * frequency).floor() + (local[1] * frequency).floor();
+                if cell.rem_euclid(2.0) < 1.0 {
+                    1.0
+                } else {
+                    0.6
+                }
+            };
+            let (shade, composition) = match object.material {
+                SceneMaterial::Textured { frequency } => (checker(local, frequency), 0.0),
+                SceneMaterial::Animated { speed } => {
+                    let stripe = ((local[0] + speed * frame as f32) * 4.0).sin() * 0.25 + 0.75;
+                    (stripe, 1.0)
+                }
+                _ => (1.0, 0.0),
+            };
+            hit.color = object.color.map(|channel| channel * shade);
+            hit.distance = object.position[2];
+            hit.velocity = object.velocity;
+            hit.composition = composition;
+        }
+
+        // Particles in front of the opaque surface, blended back to front.
+        let mut particles: Vec<&SceneObject> = self
+            .description
+            .objects
+            .iter()
+            .filter(|object| matches!(object.material, SceneMaterial::Particle { .. }))
+            .filter(|object| object.position[2] < hit.distance && covers(object).is_some())
+            .collect();
+        particles.sort_by(|a, b| b.position[2].total_cmp(&a.position[2]));
+        for particle in particles {
+            let SceneMaterial::Particle { alpha } = particle.material else {
+                continue;
+            };
+            for (channel, particle_channel) in hit.color.iter_mut().zip(particle.color) {
+                *channel += (particle_channel - *channel) * alpha;
+            }
+            hit.reactive = hit.reactive.max(alpha);
+        }
+        hit
+    }
+
+    /// Device depth of a distance, in the convention of the description's flags.
+    fn device_depth(&self, distance: f32) -> f32 {
+        let (near, far) = (self.description.camera_near, self.description.camera_far);
+        let flags = self.description.flags;
+        let inverted = flags.contains(FfxFsr2InitializationFlagBits::ENABLE_DEPTH_INVERTED);
+        let infinite = flags.contains(FfxFsr2InitializationFlagBits::ENABLE_DEPTH_INFINITE);
+        match (inverted, infinite) {
+            (false, false) => far / (far - near) * (1.0 - near / distance),
+            (true, false) => near * (far - distance) / (distance * (far - near)),
+            (false, true) => 1.0 - near / distance,
+            (true, true) => near / distance,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::size;
+
+    #[test]
+    fn renders_all_inputs() {
+        let scene = SyntheticScene::new(SceneDescription::new(size(64, 36), size(128, 72)));
+        let frame = scene.render(1);
+        assert_eq!((frame.color.width(), frame.color.height()), (64, 36));
+        assert_eq!((frame.ground_truth.width(), frame.ground_truth.height()), (128, 72));
+        assert_eq!(frame.depth.format(), FfxSurfaceFormat::R32Float);
+        assert_eq!(frame.motion_vectors.format(), FfxSurfaceFormat::RG16Float);
+        assert_eq!(frame.jitter_offset, ffx_fsr2_get_jitter_offset(1, 32).unwrap());
+        assert!(!frame.reset);
+        assert!(scene.render(0).reset && scene.render(32).reset);
+
+        // Particles are marked reactive, the animated surface in the composition mask.
+        assert!(frame.reactive.texels().iter().any(|texel| texel[0] == 0.5));
+        assert!(frame
+            .transparency_and_composition
+            .texels()
+            .iter()
+            .any(|texel| texel[0] == 1.0));
+
+        // The background only moves with the camera pan, 0.05 world units per frame at a distance of 40.
+        let extent = 30f32.to_radians().tan() * 64.0 / 36.0 * 40.0;
+        let expected = 0.05 / (2.0 * extent) * 64.0;
+        let motion = frame.motion_vectors.load(60, 2);
+        assert!((motion[0] - expected).abs() < 1e-4, "{motion:?}");
+        assert_eq!(motion[1], 0.0);
+        let expected_depth = 100.0 / 99.9 * (1.0 - 0.1 / 40.0);
+        assert!((frame.depth.load(60, 2)[0] - expected_depth).abs() < 1e-6);
+    }
+
+    #[test]
+    fn depth_conventions() {
+        let mut description = SceneDescription::new(size(32, 18), size(32, 18));
+        description.flags = FfxFsr2InitializationFlagBits::ENABLE_DEPTH_INVERTED;
+        let frame = SyntheticScene::new(description).render(0);
+        // The moving box at a distance of 12 is nearer than the background, so its inverted depth is larger.
+        let background = frame.depth.load(31, 0)[0];
+        assert!((background - 0.1 * 60.0 / (40.0 * 99.9)).abs() < 1e-6);
+        assert!(frame.depth.texels().iter().any(|texel| texel[0] > background * 3.0));
+    }
+}
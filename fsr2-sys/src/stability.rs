@@ -0,0 +1,372 @@
+//! Temporal stability of upscaled frame sequences: flicker, reprojected temporal error and ghost trails. These
+//! catch the typical failures of temporal upscaling that metrics on single frames miss.
+
+use crate::{
+    CpuImage, FfxDimensions2D, FfxFloatCoords2D, FfxFsr2InitializationFlagBits, FfxSurfaceFormat,
+    ImageSizeMismatchError,
+};
+
+/// One upscaled frame with the inputs of the `FfxFsr2DispatchDescription` that produced it.
+#[derive(Copy, Clone, Debug)]
+pub struct StabilityFrame<'a> {
+    /// The upscaled output, every frame must have the same size.
+    pub color: &'a CpuImage,
+    pub depth: &'a CpuImage,
+    pub motion_vectors: &'a CpuImage,
+    pub motion_vector_scale: FfxFloatCoords2D,
+    pub render_size: FfxDimensions2D,
+    pub camera_near: f32,
+    pub camera_far: f32,
+    /// History is discarded, e.g. on a camera cut.
+    pub reset: bool,
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct StabilityOptions {
+    /// Depth and motion vector conventions, as passed to FSR2.
+    pub flags: FfxFsr2InitializationFlagBits,
+    /// Relative change of the view depth at which a reprojected pixel counts as disoccluded.
+    pub disocclusion_threshold: f32,
+    /// Luminance change per frame above which a recently disoccluded pixel is part of a ghost trail.
+    pub ghost_threshold: f32,
+    /// Frames after a disocclusion during which changes count as ghosting.
+    pub trail_frames: u32,
+}
+
+impl Default for StabilityOptions {
+    fn default() -> Self {
+        Self {
+            flags: FfxFsr2InitializationFlagBits::empty(),
+            disocclusion_threshold: 0.05,
+            ghost_threshold: 0.02,
+            trail_frames: 8,
+        }
+    }
+}
+
+/// Stability of a frame relative to the previous one.
+#[derive(Clone, Debug)]
+pub struct FrameStability {
+    /// False for the first frame and resets, which are not compared.
+    pub has_history: bool,
+    /// Mean absolute difference to the reprojected previous frame, over pixels that were visible in it.
+    pub temporal_error: f32,
+    /// `R32Float` of the output size.
+    pub temporal_error_map: CpuImage,
+    pub disoccluded_pixels: usize,
+    pub ghost_pixels: usize,
+    /// `R32Float` of the output size, 1 for pixels in a ghost trail.
+    pub ghost_map: CpuImage,
+}
+
+#[derive(Clone, Debug)]
+pub struct StabilityReport {
+    /// Mean squared second temporal difference of the luminance along the motion, which is zero for steady
+    /// and linearly changing pixels and grows with shimmering.
+    pub flicker_energy: f32,
+    /// `R32Float` of the output size, the flicker energy per pixel.
+    pub flicker_map: CpuImage,
+    pub frames: Vec<FrameStability>,
+}
+
+impl StabilityReport {
+    /// Mean temporal error of the frames that have a predecessor.
+    pub fn temporal_error(&self) -> f32 {
+        let errors: Vec<f32> = self
+            .frames
+            .iter()
+            .filter(|frame| frame.has_history)
+            .map(|frame| frame.temporal_error)
+            .collect();
+        errors.iter().sum::<f32>() / errors.len().max(1) as f32
+    }
+
+    pub fn ghost_pixels(&self) -> usize {
+        self.frames.iter().map(|frame| frame.ghost_pixels).sum()
+    }
+}
+
+/// Where the pixels of a frame were in the previous frame.
+struct Reprojection {
+    /// Previous UV of every output pixel, `None` if it was off screen or occluded.
+    previous: Vec<Option<[f32; 2]>>,
+    disoccluded: usize,
+}
+
+/// Analyzes consecutive frames.
+pub fn analyze_stability(
+    frames: &[StabilityFrame],
+    options: &StabilityOptions,
+) -> Result<StabilityReport, ImageSizeMismatchError> {
+    let (width, height) = frames
+        .first()
+        .map_or((0, 0), |frame| (frame.color.width(), frame.color.height()));
+    for frame in frames {
+        if (frame.color.width(), frame.color.height()) != (width, height) {
+            return Err(ImageSizeMismatchError {
+                image: (frame.color.width(), frame.color.height()),
+                reference: (width, height),
+            });
+        }
+    }
+
+    let pixel_count = (width * height) as usize;
+    let mut flicker = vec![0.0f32; pixel_count];
+    let mut flicker_samples = vec![0u32; pixel_count];
+    // Frames since each pixel was last disoccluded, following the motion.
+    let mut ages = vec![u32::MAX; pixel_count];
+    let mut previous_reprojection: Option<Reprojection> = None;
+    let mut results = Vec::with_capacity(frames.len());
+
+    for (index, frame) in frames.iter().enumerate() {
+        if index == 0 || frame.reset {
+            previous_reprojection = None;
+            ages.fill(u32::MAX);
+            results.push(FrameStability {
+                has_history: false,
+                temporal_error: 0.0,
+                temporal_error_map: CpuImage::new(width, height, FfxSurfaceFormat::R32Float),
+                disoccluded_pixels: 0,
+                ghost_pixels: 0,
+                ghost_map: CpuImage::new(width, height, FfxSurfaceFormat::R32Float),
+            });
+            continue;
+        }
+
+        let previous = &frames[index - 1];
+        let reprojection = reproject(frame, previous, options);
+        let mut temporal_error_map = CpuImage::new(width, height, FfxSurfaceFormat::R32Float);
+        let mut ghost_map = CpuImage::new(width, height, FfxSurfaceFormat::R32Float);
+        let mut error_sum = 0.0f64;
+        let mut error_count = 0usize;
+        let mut ghost_pixels = 0;
+        let mut new_ages = vec![0; pixel_count];
+
+        for (i, previous_uv) in reprojection.previous.iter().enumerate() {
+            let (x, y) = ((i as u32 % width) as i32, (i as u32 / width) as i32);
+            let Some(previous_uv) = *previous_uv else {
+                continue;
+            };
+            let current = frame.color.load(x, y);
+            let warped = previous.color.sample(previous_uv);
+            let error = (0..3).map(|c| (current[c] - warped[c]).abs()).sum::<f32>() / 3.0;
+            temporal_error_map.store(x, y, [error, 0.0, 0.0, 0.0]);
+            error_sum += error as f64;
+            error_count += 1;
+
+            let age = ages[pixel_index(previous_uv, width, height)].saturating_add(1);
+            new_ages[i] = age;
+            // A correctly resolved disocclusion is stable, a ghost keeps fading out over the next frames.
+            if age <= options.trail_frames && (luma(current) - luma(warped)).abs() > options.ghost_threshold {
+                ghost_map.store(x, y, [1.0, 0.0, 0.0, 0.0]);
+                ghost_pixels += 1;
+            }
+
+            // The second difference along the motion needs the pixel in the two previous frames.
+            let Some(second_uv) = previous_reprojection
+                .as_ref()
+                .and_then(|reprojection| reprojection.previous[pixel_index(previous_uv, width, height)])
+            else {
+                continue;
+            };
+            let before = frames[index - 2].color.sample(second_uv);
+            let difference = luma(current) - 2.0 * luma(warped) + luma(before);
+            flicker[i] += difference * difference;
+            flicker_samples[i] += 1;
+        }
+
+        ages = new_ages;
+        results.push(FrameStability {
+            has_history: true,
+            temporal_error: (error_sum / error_count.max(1) as f64) as f32,
+            temporal_error_map,
+            disoccluded_pixels: reprojection.disoccluded,
+            ghost_pixels,
+            ghost_map,
+        });
+        previous_reprojection = Some(reprojection);
+    }
+
+    let mut flicker_map = CpuImage::new(width, height, FfxSurfaceFormat::R32Float);
+    for ((texel, energy), samples) in flicker_map.texels_mut().iter_mut().zip(&flicker).zip(&flicker_samples) {
+        texel[0] = energy / (*samples).max(1) as f32;
+    }
+    let total_samples: u32 = flicker_samples.iter().sum();
+    Ok(StabilityReport {
+        flicker_energy: flicker.iter().sum::<f32>() / total_samples.max(1) as f32,
+        flicker_map,
+        frames: results,
+    })
+}
+
+fn reproject(frame: &StabilityFrame, previous: &StabilityFrame, options: &StabilityOptions) -> Reprojection {
+    let (width, height) = (frame.color.width(), frame.color.height());
+    let display_motion_vectors = options
+        .flags
+        .contains(FfxFsr2InitializationFlagBits::ENABLE_DISPLAY_RESOLUTION_MOTION_VECTORS);
+    let motion_size = if display_motion_vectors {
+        [width as f32, height as f32]
+    } else {
+        [frame.render_size.width as f32, frame.render_size.height as f32]
+    };
+    let mut disoccluded = 0;
+    let previous_uvs = (0..height)
+        .flat_map(|y| (0..width).map(move |x| (x, y)))
+        .map(|(x, y)| {
+            let uv = [(x as f32 + 0.5) / width as f32, (y as f32 + 0.5) / height as f32];
+            let motion = load_at(frame.motion_vectors, uv, motion_size);
+            // Motion vectors point from the current to the previous frame.
+            let previous_uv = [
+                uv[0] + motion[0] * frame.motion_vector_scale.x / motion_size[0],
+                uv[1] + motion[1] * frame.motion_vector_scale.y / motion_size[1],
+            ];
+            if !(0.0..=1.0).contains(&previous_uv[0]) || !(0.0..=1.0).contains(&previous_uv[1]) {
+                return None;
+            }
+            let depth = view_depth(render_load(frame, frame.depth, uv)[0], frame, options.flags);
+            let previous_depth = view_depth(
+                render_load(previous, previous.depth, previous_uv)[0],
+                previous,
+                options.flags,
+            );
+            if (depth - previous_depth).abs() > options.disocclusion_threshold * depth.min(previous_depth) {
+                disoccluded += 1;
+                return None;
+            }
+            Some(previous_uv)
+        })
+        .collect();
+    Reprojection {
+        previous: previous_uvs,
+        disoccluded,
+    }
+}
+
+/// Nearest texel of a render resolution input.
+fn render_load(frame: &StabilityFrame, image: &CpuImage, uv: [f32; 2]) -> [f32; 4] {
+    load_at(
+        image,
+        uv,
+        [frame.render_size.width as f32, frame.render_size.height as f32],
+    )
+}
+
+fn load_at(image: &CpuImage, uv: [f32; 2], size: [f32; 2]) -> [f32; 4] {
+    image.load((uv[0] * size[0]).floor() as i32, (uv[1] * size[1]).floor() as i32)
+}
+
+fn pixel_index(uv: [f32; 2], width: u32, height: u32) -> usize {
+    let x = ((uv[0] * width as f32) as u32).min(width - 1);
+    let y = ((uv[1] * height as f32) as u32).min(height - 1);
+    (y * width + x) as usize
+}
+
+fn luma(texel: [f32; 4]) -> f32 {
+    0.2126 * texel[0] + 0.7152 * texel[1] + 0.0722 * texel[2]
+}
+
+/// Distance to the camera from device depth, for the depth conventions of FSR2.
+fn view_depth(depth: f32, frame: &StabilityFrame, flags: FfxFsr2InitializationFlagBits) -> f32 {
+    let (near, far) = (frame.camera_near, frame.camera_far);
+    let inverted = flags.contains(FfxFsr2InitializationFlagBits::ENABLE_DEPTH_INVERTED);
+    let infinite = flags.contains(FfxFsr2InitializationFlagBits::ENABLE_DEPTH_INFINITE);
+    let view = match (inverted, infinite) {
+        (false, false) => near * far / (far - depth * (far - near)),
+        (true, false) => near * far / (near + depth * (far - near)),
+        (false, true) => near / (1.0 - depth),
+        (true, true) => near / depth,
+    };
+    if view.is_finite() {
+        view
+    } else {
+        f32::MAX
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SIZE: u32 = 16;
+
+    struct Inputs {
+        color: CpuImage,
+        depth: CpuImage,
+        motion_vectors: CpuImage,
+    }
+
+    /// A bright 4x4 square at `square_x` in front of a dark background, moving right by `motion` pixels per
+    /// frame. `trail` brightens the background behind the square, like a ghost would.
+    fn inputs(square_x: i32, motion: f32, trail: f32) -> Inputs {
+        let mut color = CpuImage::new(SIZE, SIZE, FfxSurfaceFormat::RGBA32Float);
+        let mut depth = CpuImage::new(SIZE, SIZE, FfxSurfaceFormat::R32Float);
+        let mut motion_vectors = CpuImage::new(SIZE, SIZE, FfxSurfaceFormat::RG32Float);
+        for y in 0..SIZE as i32 {
+            for x in 0..SIZE as i32 {
+                let in_square = (square_x..square_x + 4).contains(&x) && (6..10).contains(&y);
+                let behind = (square_x - 3..square_x).contains(&x) && (6..10).contains(&y);
+                let value = if in_square {
+                    0.9
+                } else if behind {
+                    0.1 + trail * (x - square_x + 4) as f32 / 3.0
+                } else {
+                    0.1
+                };
+                color.store(x, y, [value, value, value, 1.0]);
+                depth.store(x, y, [if in_square { 0.5 } else { 0.9 }, 0.0, 0.0, 0.0]);
+                let velocity = if in_square { -motion } else { 0.0 };
+                motion_vectors.store(x, y, [velocity, 0.0, 0.0, 0.0]);
+            }
+        }
+        Inputs {
+            color,
+            depth,
+            motion_vectors,
+        }
+    }
+
+    fn analyze(sequence: &[Inputs]) -> StabilityReport {
+        let frames: Vec<StabilityFrame> = sequence
+            .iter()
+            .map(|inputs| StabilityFrame {
+                color: &inputs.color,
+                depth: &inputs.depth,
+                motion_vectors: &inputs.motion_vectors,
+                motion_vector_scale: FfxFloatCoords2D { x: 1.0, y: 1.0 },
+                render_size: FfxDimensions2D {
+                    width: SIZE,
+                    height: SIZE,
+                },
+                camera_near: 0.1,
+                camera_far: 100.0,
+                reset: false,
+            })
+            .collect();
+        analyze_stability(&frames, &StabilityOptions::default()).unwrap()
+    }
+
+    #[test]
+    fn clean_motion_is_stable() {
+        let report = analyze(&(0..4).map(|i| inputs(2 + 2 * i, 2.0, 0.0)).collect::<Vec<_>>());
+        assert_eq!(report.frames.len(), 4);
+        assert!(report.temporal_error() < 1e-6);
+        assert!(report.flicker_energy < 1e-6);
+        assert_eq!(report.ghost_pixels(), 0);
+        // The square uncovers 2 columns of 4 pixels per frame.
+        assert_eq!(report.frames[1].disoccluded_pixels, 8);
+    }
+
+    #[test]
+    fn detects_ghosts_and_flicker() {
+        let report = analyze(&(0..4).map(|i| inputs(2 + 2 * i, 2.0, 0.3)).collect::<Vec<_>>());
+        assert!(report.ghost_pixels() > 0);
+        // Uncovered in frame 1, still fading in frame 2.
+        assert_eq!(report.frames[2].ghost_map.load(3, 7)[0], 1.0);
+
+        // A static image alternating in brightness.
+        let report = analyze(&(0..4).map(|i| inputs(2, 0.0, 0.1 * (i % 2) as f32)).collect::<Vec<_>>());
+        assert!(report.flicker_energy > 0.0);
+        assert!(report.flicker_map.load(1, 7)[0] > 0.0);
+        assert_eq!(report.flicker_map.load(12, 2)[0], 0.0);
+    }
+}
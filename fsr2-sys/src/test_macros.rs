@@ -0,0 +1,19 @@
+/// Asserts that two types have the same size and alignment, and that the listed fields are at the same offsets.
+macro_rules! layout_test {
+    ($name:ident: $rust:ty => $c:ty { $($rust_field:ident => $c_field:ident),* $(,)? }) => {
+        #[test]
+        fn $name() {
+            assert_eq!(::std::mem::size_of::<$rust>(), ::std::mem::size_of::<$c>(), "size of {}", stringify!($rust));
+            assert_eq!(::std::mem::align_of::<$rust>(), ::std::mem::align_of::<$c>(), "alignment of {}", stringify!($rust));
+            $(
+                assert_eq!(
+                    ::std::mem::offset_of!($rust, $rust_field),
+                    ::std::mem::offset_of!($c, $c_field),
+                    "offset of {}::{}",
+                    stringify!($rust),
+                    stringify!($rust_field)
+                );
+            )*
+        }
+    };
+}
@@ -0,0 +1,7 @@
+//! Helpers shared by the unit tests.
+
+use crate::FfxDimensions2D;
+
+pub(crate) fn size(width: u32, height: u32) -> FfxDimensions2D {
+    FfxDimensions2D { width, height }
+}
@@ -1,4 +1,5 @@
-use bitflags::{bitflags, Flags};
+use crate::ResourceName;
+use bitflags::bitflags;
 use std::ffi::c_void;
 use widestring::WideChar;
 
@@ -11,6 +12,7 @@ pub const FFX_MAX_NUM_CONST_BUFFERS: usize = 2;
 pub const FFX_MAX_CONST_SIZE: usize = 64;
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(i32)]
 pub enum FfxSurfaceFormat {
     Unknown = 0,
@@ -37,6 +39,7 @@ pub enum FfxSurfaceFormat {
 
 bitflags! {
     #[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[repr(transparent)]
     pub struct FfxResourceUsage: i32 {
         const READ_ONLY = 0;
@@ -47,6 +50,7 @@ bitflags! {
 
 bitflags! {
     #[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[repr(transparent)]
     pub struct FfxResourceState: i32 {
         const UNORDERED_ACCESS = 1 << 0;
@@ -66,6 +70,7 @@ pub enum FfxResourceDimension {
 
 bitflags! {
     #[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[repr(transparent)]
     pub struct FfxResourceFlags: i32 {
         const NONE = 0;
@@ -81,6 +86,7 @@ pub enum FfxResourceViewType {
 }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(i32)]
 pub enum FfxFilterType {
     Point = 0,
@@ -88,7 +94,7 @@ pub enum FfxFilterType {
 }
 
 #[allow(nonstandard_style)]
-#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
 #[repr(i32)]
 pub enum FfxShaderModel {
     ShaderModel_5_1 = 0,
@@ -103,6 +109,7 @@ pub enum FfxShaderModel {
 }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(i32)]
 pub enum FfxResourceType {
     Buffer = 0,
@@ -112,6 +119,7 @@ pub enum FfxResourceType {
 }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(i32)]
 pub enum FfxHeapType {
     Default = 0,
@@ -163,6 +171,7 @@ pub struct FfxFloatCoords2D {
 }
 
 #[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(C)]
 pub struct FfxResourceDescription {
     pub ty: FfxResourceType,
@@ -202,6 +211,35 @@ impl FfxResource {
         is_depth: false,
         descriptor_data: 0,
     };
+
+    pub fn new(
+        resource: *mut c_void,
+        name: &str,
+        description: FfxResourceDescription,
+        state: FfxResourceState,
+    ) -> Self {
+        FfxResource {
+            resource,
+            name: ResourceName::new(name).into_raw(),
+            description,
+            state,
+            is_depth: false,
+            descriptor_data: 0,
+        }
+    }
+
+    pub fn with_name(mut self, name: &str) -> Self {
+        self.set_name(name);
+        self
+    }
+
+    pub fn set_name(&mut self, name: &str) {
+        self.name = ResourceName::new(name).into_raw();
+    }
+
+    pub fn name(&self) -> ResourceName {
+        ResourceName::from_raw(self.name)
+    }
 }
 
 #[derive(Copy, Clone, Debug)]
@@ -218,6 +256,12 @@ pub struct FfxResourceBinding {
     pub name: [wchar_t; 64],
 }
 
+impl FfxResourceBinding {
+    pub fn name(&self) -> ResourceName {
+        ResourceName::from_raw(self.name)
+    }
+}
+
 #[derive(Copy, Clone, Debug)]
 #[repr(C)]
 pub struct FfxPipelineState {
@@ -245,6 +289,14 @@ pub struct FfxCreateResourceDescription {
     pub id: u32,
 }
 
+impl FfxCreateResourceDescription {
+    /// # Safety
+    /// `self.name` must be null or point to a valid, null terminated wide string.
+    pub unsafe fn name(&self) -> Option<String> {
+        ResourceName::from_ptr_lossy(self.name)
+    }
+}
+
 #[derive(Copy, Clone, Debug)]
 #[repr(C)]
 pub struct FfxPipelineDescription {
@@ -272,7 +324,7 @@ pub struct FfxClearFloatJobDescription {
 #[derive(Copy, Clone, Debug)]
 #[repr(C)]
 pub struct FfxComputeJobDescription {
-    pub pipeline: FfxPipeline,
+    pub pipeline: FfxPipelineState,
     pub dimensions: [u32; 3],
     pub srvs: [FfxResourceInternal; FFX_MAX_NUM_SRVS],
     pub srv_names: [[wchar_t; 64]; FFX_MAX_NUM_SRVS],
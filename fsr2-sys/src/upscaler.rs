@@ -0,0 +1,349 @@
+//! A common interface for FSR2 and the upscalers that can stand in for it, so an engine can switch between them
+//! without changing how it submits frames.
+
+use crate::filter::lanczos2;
+use crate::{
+    ffxFsr2ContextCreate, ffxFsr2ContextDestroy, ffxFsr2ContextDispatch, CpuImage, FfxDevice, FfxDimensions2D,
+    FfxErrorCode, FfxFsr2Context, FfxFsr2ContextDescription, FfxFsr2DispatchDescription,
+    FfxFsr2InitializationFlagBits, FfxFsr2Interface, FfxFsr2QualityMode, Rcas, FFX_FSR2_CONTEXT_SIZE,
+};
+
+/// Setup of an upscaler, the backend independent part of `FfxFsr2ContextDescription`.
+#[derive(Copy, Clone, Debug)]
+pub struct UpscalerDescription {
+    pub flags: FfxFsr2InitializationFlagBits,
+    pub max_render_size: FfxDimensions2D,
+    pub display_size: FfxDimensions2D,
+}
+
+impl UpscalerDescription {
+    /// A description for rendering at the size of `quality_mode`, without dynamic resolution.
+    pub fn from_quality_mode(display_size: FfxDimensions2D, quality_mode: FfxFsr2QualityMode) -> Self {
+        Self {
+            flags: FfxFsr2InitializationFlagBits::empty(),
+            max_render_size: quality_mode.render_size(display_size),
+            display_size,
+        }
+    }
+}
+
+/// An upscaler taking the same per-frame input as `ffxFsr2ContextDispatch`. Implementations use the inputs they
+/// support and ignore the others, e.g. spatial upscalers only read `color`.
+pub trait Upscaler {
+    fn display_size(&self) -> FfxDimensions2D;
+
+    fn render_size(&self, quality_mode: FfxFsr2QualityMode) -> FfxDimensions2D {
+        quality_mode.render_size(self.display_size())
+    }
+
+    /// Upscales one frame into `description.output`.
+    ///
+    /// # Safety
+    /// The resources in `description` must be valid for the upscaler, for the CPU upscalers they must be null or
+    /// obtained from [`crate::ffx_get_texture_resource_cpu`], with the output not aliasing any input.
+    unsafe fn dispatch(&mut self, description: &FfxFsr2DispatchDescription) -> Result<(), FfxErrorCode>;
+}
+
+/// FSR2 on any backend interface.
+pub struct Fsr2Upscaler {
+    context: Box<FfxFsr2Context>,
+    display_size: FfxDimensions2D,
+    flags: FfxFsr2InitializationFlagBits,
+}
+
+impl Fsr2Upscaler {
+    /// Creates an FSR2 context.
+    ///
+    /// # Safety
+    /// `interface` and `device` must stay valid until the upscaler is dropped.
+    pub unsafe fn new(
+        description: &UpscalerDescription,
+        interface: FfxFsr2Interface,
+        device: FfxDevice,
+    ) -> Result<Self, FfxErrorCode> {
+        interface.check_complete()?;
+        let mut context = Box::new(FfxFsr2Context {
+            data: [0; FFX_FSR2_CONTEXT_SIZE],
+        });
+        let context_description = FfxFsr2ContextDescription {
+            flags: description.flags,
+            max_render_size: description.max_render_size,
+            display_size: description.display_size,
+            callbacks: interface,
+            device,
+            fp_message: None,
+        };
+        match ffxFsr2ContextCreate(context.as_mut(), &context_description) {
+            FfxErrorCode::Ok => Ok(Self {
+                context,
+                display_size: description.display_size,
+                flags: description.flags,
+            }),
+            error => Err(error),
+        }
+    }
+
+    pub fn context(&mut self) -> &mut FfxFsr2Context {
+        &mut self.context
+    }
+}
+
+impl Upscaler for Fsr2Upscaler {
+    fn display_size(&self) -> FfxDimensions2D {
+        self.display_size
+    }
+
+    /// Debug-asserts that the resources are large enough, see
+    /// [`FfxFsr2DispatchDescription::debug_assert_resource_sizes`].
+    unsafe fn dispatch(&mut self, description: &FfxFsr2DispatchDescription) -> Result<(), FfxErrorCode> {
+        description.debug_assert_resource_sizes(self.display_size, self.flags);
+        match ffxFsr2ContextDispatch(self.context.as_mut(), description) {
+            FfxErrorCode::Ok => Ok(()),
+            error => Err(error),
+        }
+    }
+}
+
+impl Drop for Fsr2Upscaler {
+    fn drop(&mut self) {
+        unsafe {
+            ffxFsr2ContextDestroy(self.context.as_mut());
+        }
+    }
+}
+
+/// The filter of a [`CpuUpscaler`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum CpuUpscaleFilter {
+    Bilinear,
+    /// Separable lanczos2 over 4x4 texels.
+    Lanczos,
+}
+
+/// A plain resampling upscaler on [`CpuImage`]s, mostly useful as a baseline in tests. The jitter of the input is
+/// removed, optional sharpening uses RCAS like FSR2.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct CpuUpscaler {
+    pub display_size: FfxDimensions2D,
+    pub filter: CpuUpscaleFilter,
+}
+
+impl CpuUpscaler {
+    pub fn new(description: &UpscalerDescription, filter: CpuUpscaleFilter) -> Self {
+        Self {
+            display_size: description.display_size,
+            filter,
+        }
+    }
+
+    /// Upscales the top left `render_size` texels of `color` into `output`. Fails with `InvalidSize` if no
+    /// texels are left to upscale.
+    pub fn upscale(
+        &self,
+        color: &CpuImage,
+        render_size: FfxDimensions2D,
+        jitter: [f32; 2],
+        output: &mut CpuImage,
+    ) -> Result<(), FfxErrorCode> {
+        let max = [
+            render_size.width.min(color.width()) as i32 - 1,
+            render_size.height.min(color.height()) as i32 - 1,
+        ];
+        if max[0] < 0 || max[1] < 0 {
+            return Err(FfxErrorCode::InvalidSize);
+        }
+        let load = |x: i32, y: i32| color.load(x.clamp(0, max[0]), y.clamp(0, max[1]));
+        let scale = [
+            render_size.width as f32 / output.width() as f32,
+            render_size.height as f32 / output.height() as f32,
+        ];
+        for y in 0..output.height() {
+            for x in 0..output.width() {
+                // The scene at a render pixel centre is shifted by the jitter, sample where it ended up.
+                let position = [
+                    (x as f32 + 0.5) * scale[0] - 0.5 + jitter[0],
+                    (y as f32 + 0.5) * scale[1] - 0.5 + jitter[1],
+                ];
+                let value = match self.filter {
+                    CpuUpscaleFilter::Bilinear => filter(position, 1, load, |d| (1.0 - d.abs()).max(0.0)),
+                    CpuUpscaleFilter::Lanczos => filter(position, 2, load, lanczos2),
+                };
+                output.store(x as i32, y as i32, value);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Separable filter over the `2 * radius` texels around `position`, with weights normalized to 1.
+fn filter(
+    position: [f32; 2],
+    radius: i32,
+    load: impl Fn(i32, i32) -> [f32; 4],
+    kernel: impl Fn(f32) -> f32,
+) -> [f32; 4] {
+    let base = position.map(|p| p.floor() as i32);
+    let mut sum = [0.0f32; 4];
+    let mut weight_sum = 0.0;
+    for y in base[1] - radius + 1..=base[1] + radius {
+        for x in base[0] - radius + 1..=base[0] + radius {
+            let weight = kernel(position[0] - x as f32) * kernel(position[1] - y as f32);
+            for (sum, channel) in sum.iter_mut().zip(load(x, y)) {
+                *sum += channel * weight;
+            }
+            weight_sum += weight;
+        }
+    }
+    sum.map(|channel| channel / weight_sum)
+}
+
+impl Upscaler for CpuUpscaler {
+    fn display_size(&self) -> FfxDimensions2D {
+        self.display_size
+    }
+
+    unsafe fn dispatch(&mut self, description: &FfxFsr2DispatchDescription) -> Result<(), FfxErrorCode> {
+        let (color, output) = cpu_images(description)?;
+        if (output.width(), output.height()) != (self.display_size.width, self.display_size.height) {
+            return Err(FfxErrorCode::InvalidSize);
+        }
+        let jitter = [description.jitter_offset.x, description.jitter_offset.y];
+        self.upscale(color, description.render_size, jitter, output)?;
+        if description.enable_sharpening {
+            sharpen(output, description.sharpness);
+        }
+        Ok(())
+    }
+}
+
+/// The color input and output of a dispatch to a CPU upscaler.
+///
+/// # Safety
+/// See [`Upscaler::dispatch`].
+pub(crate) unsafe fn cpu_images<'a>(
+    description: &FfxFsr2DispatchDescription,
+) -> Result<(&'a CpuImage, &'a mut CpuImage), FfxErrorCode> {
+    let color = description.color.resource as *const CpuImage;
+    let output = description.output.resource as *mut CpuImage;
+    if color.is_null() || output.is_null() {
+        return Err(FfxErrorCode::InvalidPointer);
+    }
+    if std::ptr::eq(color, output) {
+        return Err(FfxErrorCode::InvalidArgument);
+    }
+    Ok((&*color, &mut *output))
+}
+
+pub(crate) fn sharpen(image: &mut CpuImage, sharpness: f32) {
+    let width = image.width();
+    let sharpened = Rcas::new(sharpness).apply(width, image.height(), image.texels());
+    for (index, texel) in sharpened.into_iter().enumerate() {
+        let index = index as u32;
+        image.store((index % width) as i32, (index / width) as i32, texel);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::size;
+    use crate::{ffx_get_texture_resource_cpu, FfxFloatCoords2D, FfxResourceState, FfxSurfaceFormat, Fsr1Upscaler};
+
+    /// A horizontal ramp, sampled at the jittered render pixel centres.
+    fn ramp(render_size: FfxDimensions2D, jitter: [f32; 2]) -> CpuImage {
+        let mut image = CpuImage::new(render_size.width, render_size.height, FfxSurfaceFormat::RGBA32Float);
+        for y in 0..render_size.height as i32 {
+            for x in 0..render_size.width as i32 {
+                let value = (x as f32 + 0.5 - jitter[0]) / render_size.width as f32;
+                image.store(x, y, [value, value, value, 1.0]);
+            }
+        }
+        image
+    }
+
+    #[test]
+    fn upscalers_are_interchangeable() {
+        let description = UpscalerDescription::from_quality_mode(size(32, 16), FfxFsr2QualityMode::Performance);
+        let mut upscalers: Vec<Box<dyn Upscaler>> = vec![
+            Box::new(CpuUpscaler::new(&description, CpuUpscaleFilter::Bilinear)),
+            Box::new(CpuUpscaler::new(&description, CpuUpscaleFilter::Lanczos)),
+            Box::new(Fsr1Upscaler::from_description(&description)),
+        ];
+
+        let jitter = [0.25, -0.125];
+        for upscaler in &mut upscalers {
+            let render_size = upscaler.render_size(FfxFsr2QualityMode::Performance);
+            assert_eq!(render_size, description.max_render_size);
+            let mut color = ramp(render_size, jitter);
+            let mut output = CpuImage::new(32, 16, FfxSurfaceFormat::RGBA32Float);
+            let dispatch = FfxFsr2DispatchDescription {
+                color: ffx_get_texture_resource_cpu(&mut color, Some("color"), FfxResourceState::COMPUTE_READ, false),
+                output: ffx_get_texture_resource_cpu(
+                    &mut output,
+                    Some("output"),
+                    FfxResourceState::UNORDERED_ACCESS,
+                    false,
+                ),
+                jitter_offset: FfxFloatCoords2D {
+                    x: jitter[0],
+                    y: jitter[1],
+                },
+                render_size,
+                ..Default::default()
+            };
+            unsafe { upscaler.dispatch(&dispatch) }.unwrap();
+
+            // Away from the edges every upscaler reproduces the ramp at display resolution, to within half a render
+            // pixel. FSR1 does not remove the jitter.
+            for x in 4..28 {
+                let expected = (x as f32 + 0.5) / 32.0;
+                assert!((output.load(x, 8)[0] - expected).abs() < 0.5 / 16.0);
+            }
+        }
+    }
+
+    #[test]
+    fn removes_jitter() {
+        let description = UpscalerDescription::from_quality_mode(size(16, 8), FfxFsr2QualityMode::Performance);
+        let upscaler = CpuUpscaler::new(&description, CpuUpscaleFilter::Bilinear);
+        let jitter = [0.25, 0.0];
+        let mut output = CpuImage::new(16, 8, FfxSurfaceFormat::RGBA32Float);
+        upscaler.upscale(&ramp(size(8, 4), jitter), size(8, 4), jitter, &mut output).unwrap();
+        for x in 2..14 {
+            assert!((output.load(x, 3)[0] - (x as f32 + 0.5) / 16.0).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn rejects_missing_resources() {
+        let mut upscaler = CpuUpscaler::new(
+            &UpscalerDescription::from_quality_mode(size(16, 8), FfxFsr2QualityMode::Quality),
+            CpuUpscaleFilter::Lanczos,
+        );
+        let result = unsafe { upscaler.dispatch(&FfxFsr2DispatchDescription::default()) };
+        assert_eq!(result, Err(FfxErrorCode::InvalidPointer));
+    }
+
+    #[test]
+    fn rejects_invalid_sizes() {
+        let description = UpscalerDescription::from_quality_mode(size(16, 8), FfxFsr2QualityMode::Performance);
+        let mut upscalers: Vec<Box<dyn Upscaler>> = vec![
+            Box::new(CpuUpscaler::new(&description, CpuUpscaleFilter::Lanczos)),
+            Box::new(Fsr1Upscaler::from_description(&description)),
+        ];
+        for upscaler in &mut upscalers {
+            for (render_size, output_size) in [(size(0, 4), size(16, 8)), (size(8, 4), size(8, 4))] {
+                let mut color = CpuImage::new(8, 4, FfxSurfaceFormat::RGBA32Float);
+                let mut output = CpuImage::new(output_size.width, output_size.height, FfxSurfaceFormat::RGBA32Float);
+                let dispatch = FfxFsr2DispatchDescription {
+                    color: ffx_get_texture_resource_cpu(&mut color, None, FfxResourceState::COMPUTE_READ, false),
+                    output: ffx_get_texture_resource_cpu(&mut output, None, FfxResourceState::UNORDERED_ACCESS, false),
+                    render_size,
+                    ..Default::default()
+                };
+                let result = unsafe { upscaler.dispatch(&dispatch) };
+                assert_eq!(result, Err(FfxErrorCode::InvalidSize), "{render_size:?} into {output_size:?}");
+            }
+        }
+    }
+}